@@ -0,0 +1,82 @@
+//! Compares tail insertion in `LinkedList` against the previous design,
+//! which had to walk from the head to find the last node.
+//!
+//! Run with `cargo bench --bench linked_list`.
+
+use dsa_rs::linked_lists::LinkedList;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// The `Box`-linked list `LinkedList` used before it tracked its tail
+struct WalkingList<T> {
+    head: Option<Box<WalkingNode<T>>>,
+}
+
+struct WalkingNode<T> {
+    value: T,
+    next: Option<Box<WalkingNode<T>>>,
+}
+
+impl<T> WalkingList<T> {
+    fn new() -> Self {
+        Self { head: None }
+    }
+
+    fn insert_at_tail(&mut self, value: T) {
+        let new_node = Box::new(WalkingNode { value, next: None });
+        if let Some(mut current) = self.head.as_mut() {
+            while let Some(ref mut next) = current.next {
+                current = next;
+            }
+            current.next = Some(new_node);
+        } else {
+            self.head = Some(new_node);
+        }
+    }
+
+    fn remove_from_head(&mut self) -> Option<T> {
+        self.head.take().map(|node| {
+            self.head = node.next;
+            node.value
+        })
+    }
+}
+
+impl<T> Drop for WalkingList<T> {
+    fn drop(&mut self) {
+        while self.remove_from_head().is_some() {}
+    }
+}
+
+/// Time `f` over a few runs and keep the fastest
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .expect("at least one run")
+}
+
+fn main() {
+    println!("{:>8}  {:>14}  {:>14}", "n", "tail pointer", "walk to tail");
+    for n in [1_000, 4_000, 16_000] {
+        let tracked = time(|| {
+            let mut list = LinkedList::new();
+            for i in 0..n {
+                list.insert_at_tail(i);
+            }
+            black_box(&list);
+        });
+        let walking = time(|| {
+            let mut list = WalkingList::new();
+            for i in 0..n {
+                list.insert_at_tail(i);
+            }
+            black_box(&list);
+        });
+        println!("{n:>8}  {tracked:>14.2?}  {walking:>14.2?}");
+    }
+}
@@ -0,0 +1,114 @@
+//! Compares `UnrolledList` against `LinkedList` and `DynamicArray` for
+//! iteration, indexed reads and insertion in the middle.
+//!
+//! Run with `cargo bench --bench unrolled_list`.
+
+use dsa_rs::dynamic_arrays::DynamicArray;
+use dsa_rs::linked_lists::LinkedList;
+use dsa_rs::unrolled_list::UnrolledList;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// How many reads and insertions each indexed benchmark makes
+const OPS: usize = 1_000;
+
+/// Time `f` over a few runs and keep the fastest
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .expect("at least one run")
+}
+
+/// Spread `OPS` indexes over `0..n` in a fixed, cache-unfriendly order
+fn indexes(n: usize) -> Vec<usize> {
+    (0..OPS).map(|i| i * 7_919 % n).collect()
+}
+
+fn print_row(name: &str, n: usize, times: [Duration; 3]) {
+    let [unrolled, linked, dynamic] = times;
+    println!("{name:>10}  {n:>8}  {unrolled:>12.2?}  {linked:>12.2?}  {dynamic:>12.2?}");
+}
+
+fn main() {
+    println!(
+        "{:>10}  {:>8}  {:>12}  {:>12}  {:>12}",
+        "", "n", "unrolled", "linked", "dynamic"
+    );
+    for n in [10_000, 100_000] {
+        let unrolled: UnrolledList<usize> = (0..n).collect();
+        let linked: LinkedList<usize> = (0..n).collect();
+        let dynamic: DynamicArray<usize> = (0..n).collect();
+
+        print_row(
+            "iterate",
+            n,
+            [
+                time(|| {
+                    black_box(unrolled.iter().sum::<usize>());
+                }),
+                time(|| {
+                    black_box(linked.iter().sum::<usize>());
+                }),
+                time(|| {
+                    black_box(dynamic.iter().sum::<usize>());
+                }),
+            ],
+        );
+
+        let reads = indexes(n);
+        print_row(
+            "get",
+            n,
+            [
+                time(|| {
+                    for &i in &reads {
+                        black_box(unrolled.get(i).ok());
+                    }
+                }),
+                time(|| {
+                    for &i in &reads {
+                        black_box(linked.get(i).ok());
+                    }
+                }),
+                time(|| {
+                    for &i in &reads {
+                        black_box(dynamic.get(i));
+                    }
+                }),
+            ],
+        );
+
+        print_row(
+            "insert",
+            n,
+            [
+                time(|| {
+                    let mut list = unrolled.clone();
+                    for &i in &reads {
+                        list.insert_at_index(i, i).unwrap();
+                    }
+                    black_box(&list);
+                }),
+                time(|| {
+                    let mut list = linked.clone();
+                    for &i in &reads {
+                        list.insert_at_index(i, i).unwrap();
+                    }
+                    black_box(&list);
+                }),
+                time(|| {
+                    let mut array = dynamic.clone();
+                    for &i in &reads {
+                        array.insert(i, i).unwrap();
+                    }
+                    black_box(&array);
+                }),
+            ],
+        );
+    }
+}
@@ -0,0 +1,800 @@
+// Allocator implementation in Rust
+
+//! Allocators that collections can get their memory from instead of the
+//! global heap.
+//!
+//! [`Allocator`] mirrors the unstable `core::alloc::Allocator` trait so it
+//! can be used on stable. [`Global`] forwards to the global allocator,
+//! [`Bump`] carves blocks out of chunks that are freed all at once, and
+//! [`Counting`] wraps another allocator to record how a collection uses it.
+
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, realloc};
+use core::alloc::Layout;
+use core::cell::Cell;
+use core::fmt;
+use core::mem;
+use core::ptr::{self, NonNull};
+
+/// Size of the first chunk a [`Bump`] allocates, header included
+const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+/// The error returned when an allocator can't satisfy a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+impl core::error::Error for AllocError {}
+
+/// A source of memory blocks, mirroring `core::alloc::Allocator`.
+///
+/// # Safety
+///
+/// A block returned by `allocate`, `grow` or `shrink` must fit the requested
+/// layout and stay valid until it is deallocated, even after the value that
+/// allocated it is dropped. Lists move nodes between each other without
+/// reallocating them when [`same_as`](Self::same_as) holds, so two values it
+/// holds for must accept each other's blocks, and a clone must be the same
+/// allocator as the value it was cloned from.
+pub unsafe trait Allocator {
+    /// Allocate a block that fits `layout`, which may be larger than asked
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
+
+    /// Free a block
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block from this allocator that fits `layout`, and
+    /// must not be used afterwards.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+    /// Check if `other` is the same allocator, so blocks from either can be
+    /// freed through the other. The default is `false`.
+    fn same_as(&self, _other: &Self) -> bool
+    where
+        Self: Sized,
+    {
+        false
+    }
+
+    /// Grow a block to fit `new_layout`, keeping its contents.
+    ///
+    /// The default moves the contents to a fresh block.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block from this allocator that fits `old_layout`, and
+    /// `new_layout` must be at least as large. On success the old pointer
+    /// must not be used again.
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        debug_assert!(new_layout.size() >= old_layout.size());
+        reallocate_by_copy(self, ptr, old_layout, new_layout)
+    }
+
+    /// Shrink a block to fit `new_layout`, keeping the contents that fit.
+    ///
+    /// The default moves the contents to a fresh block.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block from this allocator that fits `old_layout`, and
+    /// `new_layout` must be no larger. On success the old pointer must not
+    /// be used again.
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        debug_assert!(new_layout.size() <= old_layout.size());
+        reallocate_by_copy(self, ptr, old_layout, new_layout)
+    }
+}
+
+// SAFETY: every call is forwarded to the allocator behind the reference.
+unsafe impl<A: Allocator + ?Sized> Allocator for &A {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        (**self).deallocate(ptr, layout)
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        ptr::eq(*self, *other)
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        (**self).grow(ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        (**self).shrink(ptr, old_layout, new_layout)
+    }
+}
+
+/// Move a block's contents to a fresh block of `new_layout` and free it
+///
+/// # Safety
+///
+/// `ptr` must be a block from `alloc` that fits `old_layout`.
+unsafe fn reallocate_by_copy<A: Allocator + ?Sized>(
+    alloc: &A,
+    ptr: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> Result<NonNull<[u8]>, AllocError> {
+    let block = alloc.allocate(new_layout)?;
+    let kept = old_layout.size().min(new_layout.size());
+    ptr::copy_nonoverlapping(ptr.as_ptr(), block.cast::<u8>().as_ptr(), kept);
+    alloc.deallocate(ptr, old_layout);
+    Ok(block)
+}
+
+/// Turn a pointer from a raw allocation function into a block of `size`
+/// bytes
+fn block(ptr: *mut u8, size: usize) -> Result<NonNull<[u8]>, AllocError> {
+    NonNull::new(ptr)
+        .map(|ptr| NonNull::slice_from_raw_parts(ptr, size))
+        .ok_or(AllocError)
+}
+
+/// Get an empty, well-aligned block for a zero-sized layout
+fn dangling(layout: Layout) -> NonNull<[u8]> {
+    // SAFETY: alignments are never zero.
+    let ptr = unsafe { NonNull::new_unchecked(ptr::without_provenance_mut(layout.align())) };
+    NonNull::slice_from_raw_parts(ptr, 0)
+}
+
+/// Move `value` into a block from `alloc`, aborting if allocation fails
+pub(crate) fn alloc_value<T, A: Allocator + ?Sized>(alloc: &A, value: T) -> NonNull<T> {
+    let layout = Layout::new::<T>();
+    let ptr = match alloc.allocate(layout) {
+        Ok(block) => block.cast::<T>(),
+        Err(AllocError) => handle_alloc_error(layout),
+    };
+    // SAFETY: the block fits a `T` and holds nothing yet.
+    unsafe { ptr.as_ptr().write(value) };
+    ptr
+}
+
+/// Move the value out of a block from [`alloc_value`] and free the block
+///
+/// # Safety
+///
+/// `ptr` must come from [`alloc_value`] with `alloc` or an allocator it is
+/// [the same as](Allocator::same_as), and must not be used afterwards.
+pub(crate) unsafe fn free_value<T, A: Allocator + ?Sized>(alloc: &A, ptr: NonNull<T>) -> T {
+    let value = ptr.as_ptr().read();
+    alloc.deallocate(ptr.cast(), Layout::new::<T>());
+    value
+}
+
+/// The global heap allocator, which every collection uses by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Global;
+
+impl Global {
+    /// Resize a block with `realloc` when the layouts allow it
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block from `Global` that fits `old_layout`.
+    unsafe fn reallocate(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if old_layout.size() == 0
+            || new_layout.size() == 0
+            || old_layout.align() != new_layout.align()
+        {
+            return reallocate_by_copy(self, ptr, old_layout, new_layout);
+        }
+        block(
+            realloc(ptr.as_ptr(), old_layout, new_layout.size()),
+            new_layout.size(),
+        )
+    }
+}
+
+// SAFETY: non-empty blocks come from the global allocator and stay valid
+// until they are passed back to it.
+unsafe impl Allocator for Global {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.size() == 0 {
+            return Ok(dangling(layout));
+        }
+        // SAFETY: the layout has a non-zero size.
+        block(unsafe { alloc(layout) }, layout.size())
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            dealloc(ptr.as_ptr(), layout);
+        }
+    }
+
+    fn same_as(&self, _other: &Self) -> bool {
+        true
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.reallocate(ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.reallocate(ptr, old_layout, new_layout)
+    }
+}
+
+/// The header at the start of every chunk of a [`Bump`]
+struct Chunk {
+    /// The chunk allocated before this one
+    prev: Option<NonNull<Chunk>>,
+    /// The layout the chunk was allocated with, header included
+    layout: Layout,
+}
+
+/// Bytes at the start of a chunk taken up by its header
+const HEADER: usize = mem::size_of::<Chunk>();
+
+/// An allocator that hands out blocks by bumping a cursor through chunks
+/// taken from the global heap.
+///
+/// Freeing a block only gives its memory back if it is the most recent
+/// allocation; everything else is reclaimed at once by [`Bump::reset`] or
+/// when the allocator is dropped. When a chunk runs out the next one is
+/// twice its size, so allocation only fails if the global heap does.
+///
+/// Only `&Bump` is an [`Allocator`], so collections borrow the bump and
+/// can't outlive it, and one bump can back many of them:
+/// `DynamicArray::new_in(&bump)`. A bump can't be moved into a collection:
+///
+/// ```compile_fail
+/// use dsa_rs::allocator::Bump;
+/// use dsa_rs::doubly_linked_list::DoublyLinkedList;
+///
+/// let list: DoublyLinkedList<i32, Bump> = DoublyLinkedList::new_in(Bump::new());
+/// ```
+pub struct Bump {
+    /// The most recent chunk, which links to the older ones
+    chunk: Cell<Option<NonNull<Chunk>>>,
+    /// The next free byte in the current chunk
+    cursor: Cell<*mut u8>,
+    /// One past the last byte of the current chunk
+    end: Cell<*mut u8>,
+}
+
+// SAFETY: the bump owns its chunks and nothing else points into them
+// without borrowing the bump.
+unsafe impl Send for Bump {}
+
+impl Bump {
+    /// Create a new bump allocator that allocates its first chunk lazily
+    pub fn new() -> Self {
+        Self {
+            chunk: Cell::new(None),
+            cursor: Cell::new(ptr::null_mut()),
+            end: Cell::new(ptr::null_mut()),
+        }
+    }
+
+    /// Create a new bump allocator with room for `bytes` bytes up front
+    pub fn with_capacity(bytes: usize) -> Self {
+        let bump = Self::new();
+        if bytes > 0 && bump.push_chunk(bytes).is_err() {
+            let layout = Layout::from_size_align(bytes, mem::align_of::<Chunk>())
+                .expect("capacity overflow");
+            handle_alloc_error(layout);
+        }
+        bump
+    }
+
+    /// Get the number of bytes across all chunks that can hold blocks
+    pub fn capacity(&self) -> usize {
+        let mut capacity = 0;
+        let mut chunk = self.chunk.get();
+        while let Some(current) = chunk {
+            // SAFETY: every chunk in the list is live until the bump drops.
+            let header = unsafe { &*current.as_ptr() };
+            capacity += header.layout.size() - HEADER;
+            chunk = header.prev;
+        }
+        capacity
+    }
+
+    /// Get the number of bytes left in the current chunk
+    pub fn remaining(&self) -> usize {
+        self.end.get().addr() - self.cursor.get().addr()
+    }
+
+    /// Free every block at once, keeping only the newest, largest chunk
+    pub fn reset(&mut self) {
+        let Some(chunk) = self.chunk.get() else {
+            return;
+        };
+        // SAFETY: the `&mut` borrow proves that no block is still in use;
+        // the older chunks are unlinked before they are freed.
+        unsafe {
+            free_chunks((*chunk.as_ptr()).prev.take());
+            self.cursor.set(chunk.as_ptr().cast::<u8>().add(HEADER));
+        }
+    }
+
+    /// Allocate a chunk with room for at least `bytes` bytes and make it the
+    /// current one
+    fn push_chunk(&self, bytes: usize) -> Result<(), AllocError> {
+        let previous = self.chunk.get().map_or(0, |chunk| {
+            // SAFETY: the current chunk is live.
+            unsafe { (*chunk.as_ptr()).layout.size() }
+        });
+        let size = bytes
+            .checked_add(HEADER)
+            .ok_or(AllocError)?
+            .max(previous.saturating_mul(2))
+            .max(DEFAULT_CHUNK_SIZE);
+        let layout =
+            Layout::from_size_align(size, mem::align_of::<Chunk>()).map_err(|_| AllocError)?;
+        let start = Global.allocate(layout)?.cast::<u8>();
+        let chunk = start.cast::<Chunk>();
+        // SAFETY: the chunk is large and aligned enough for its header, and
+        // the header is followed by `size - HEADER` free bytes.
+        unsafe {
+            chunk.as_ptr().write(Chunk {
+                prev: self.chunk.get(),
+                layout,
+            });
+            self.cursor.set(start.as_ptr().add(HEADER));
+            self.end.set(start.as_ptr().add(size));
+        }
+        self.chunk.set(Some(chunk));
+        Ok(())
+    }
+
+    /// Carve a block for `layout` out of the current chunk, if it fits
+    fn bump(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let cursor = self.cursor.get();
+        let mask = layout.align() - 1;
+        let aligned = cursor.addr().checked_add(mask)? & !mask;
+        let block_end = aligned.checked_add(layout.size())?;
+        if block_end > self.end.get().addr() {
+            return None;
+        }
+        let start = cursor.wrapping_add(aligned - cursor.addr());
+        self.cursor.set(start.wrapping_add(layout.size()));
+        NonNull::new(start)
+    }
+
+    /// Check if the block at `ptr` of `size` bytes is the latest one
+    fn is_latest(&self, ptr: NonNull<u8>, size: usize) -> bool {
+        ptr.as_ptr().wrapping_add(size) == self.cursor.get()
+    }
+}
+
+/// Free `chunk` and every chunk before it
+///
+/// # Safety
+///
+/// No block in the chunks may be used afterwards.
+unsafe fn free_chunks(mut chunk: Option<NonNull<Chunk>>) {
+    while let Some(current) = chunk {
+        let Chunk { prev, layout } = current.as_ptr().read();
+        Global.deallocate(current.cast(), layout);
+        chunk = prev;
+    }
+}
+
+// SAFETY: blocks are disjoint ranges of chunks that stay allocated until the
+// bump is reset, which needs `&mut`, or dropped, and the reference keeps both
+// from happening while any collection can use them. References to the same
+// bump are the same allocator.
+unsafe impl Allocator for &Bump {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.size() == 0 {
+            return Ok(dangling(layout));
+        }
+        let ptr = match self.bump(layout) {
+            Some(ptr) => ptr,
+            None => {
+                // Room for the block however the new chunk's start is aligned
+                let worst_case = layout
+                    .size()
+                    .checked_add(layout.align() - 1)
+                    .ok_or(AllocError)?;
+                self.push_chunk(worst_case)?;
+                self.bump(layout).ok_or(AllocError)?
+            }
+        };
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if self.is_latest(ptr, layout.size()) {
+            self.cursor.set(ptr.as_ptr());
+        }
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        ptr::eq(*self, *other)
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let addr = ptr.as_ptr().addr();
+        if self.is_latest(ptr, old_layout.size())
+            && addr & (new_layout.align() - 1) == 0
+            && self.end.get().addr() - addr >= new_layout.size()
+        {
+            // The latest block can grow in place into the rest of the chunk
+            self.cursor.set(ptr.as_ptr().add(new_layout.size()));
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+        reallocate_by_copy(self, ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if ptr.as_ptr().addr() & (new_layout.align() - 1) != 0 {
+            return reallocate_by_copy(self, ptr, old_layout, new_layout);
+        }
+        if self.is_latest(ptr, old_layout.size()) {
+            self.cursor.set(ptr.as_ptr().add(new_layout.size()));
+        }
+        Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+    }
+}
+
+impl Default for Bump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Bump {
+    fn drop(&mut self) {
+        // SAFETY: blocks borrow the bump, so none outlive it.
+        unsafe { free_chunks(self.chunk.get()) };
+    }
+}
+
+impl fmt::Debug for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bump")
+            .field("capacity", &self.capacity())
+            .field("remaining", &self.remaining())
+            .finish()
+    }
+}
+
+/// What a [`Counting`] allocator has seen so far
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Blocks handed out by `allocate`
+    pub allocations: usize,
+    /// Blocks given back through `deallocate`
+    pub deallocations: usize,
+    /// Blocks resized by `grow` or `shrink`
+    pub reallocations: usize,
+    /// Bytes requested by the blocks that are currently allocated
+    pub bytes_in_use: usize,
+    /// The most bytes that were in use at once
+    pub peak_bytes: usize,
+}
+
+impl AllocStats {
+    /// Get the number of blocks that are currently allocated
+    pub fn live(&self) -> usize {
+        self.allocations - self.deallocations
+    }
+}
+
+/// An allocator that forwards to `A` and records every request.
+///
+/// Give each collection its own counter, by value or by reference, to see
+/// how many allocations and bytes it makes. Lists with different counters
+/// are different allocators, so nodes moving between them are reallocated
+/// and each counter balances on its own.
+#[derive(Debug, Default)]
+pub struct Counting<A = Global> {
+    inner: A,
+    stats: Cell<AllocStats>,
+}
+
+impl<A> Counting<A> {
+    /// Create a new counting allocator around `inner`
+    pub fn new(inner: A) -> Self {
+        Self {
+            inner,
+            stats: Cell::new(AllocStats::default()),
+        }
+    }
+
+    /// Get the allocator the requests are forwarded to
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Get the statistics recorded so far
+    pub fn stats(&self) -> AllocStats {
+        self.stats.get()
+    }
+
+    /// Record a request that changed the bytes in use from `old` to `new`
+    fn record(&self, old: usize, new: usize, update: impl FnOnce(&mut AllocStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        stats.bytes_in_use = stats.bytes_in_use - old + new;
+        stats.peak_bytes = stats.peak_bytes.max(stats.bytes_in_use);
+        self.stats.set(stats);
+    }
+}
+
+// SAFETY: every block comes from and goes back to `inner`.
+unsafe impl<A: Allocator> Allocator for Counting<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let block = self.inner.allocate(layout)?;
+        self.record(0, layout.size(), |stats| stats.allocations += 1);
+        Ok(block)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.inner.deallocate(ptr, layout);
+        self.record(layout.size(), 0, |stats| stats.deallocations += 1);
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        ptr::eq(self, other)
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let block = self.inner.grow(ptr, old_layout, new_layout)?;
+        self.record(old_layout.size(), new_layout.size(), |stats| {
+            stats.reallocations += 1
+        });
+        Ok(block)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let block = self.inner.shrink(ptr, old_layout, new_layout)?;
+        self.record(old_layout.size(), new_layout.size(), |stats| {
+            stats.reallocations += 1
+        });
+        Ok(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Allocate a block for `layout` and fill it with `byte`
+    fn filled(alloc: impl Allocator, layout: Layout, byte: u8) -> NonNull<u8> {
+        let block = alloc.allocate(layout).unwrap();
+        assert!(block.len() >= layout.size());
+        let ptr = block.cast::<u8>();
+        assert_eq!(ptr.as_ptr().addr() & (layout.align() - 1), 0);
+        // SAFETY: the block has room for `layout.size()` bytes.
+        unsafe { ptr.as_ptr().write_bytes(byte, layout.size()) };
+        ptr
+    }
+
+    fn layout(size: usize, align: usize) -> Layout {
+        Layout::from_size_align(size, align).unwrap()
+    }
+
+    #[test]
+    fn test_global_round_trip() {
+        let ptr = filled(Global, layout(16, 8), 7);
+        // SAFETY: the block fits the old layout and the new one is larger.
+        unsafe {
+            let grown = Global.grow(ptr, layout(16, 8), layout(64, 8)).unwrap();
+            let bytes = core::slice::from_raw_parts(grown.cast::<u8>().as_ptr(), 16);
+            assert!(bytes.iter().all(|&b| b == 7));
+            let moved = Global
+                .grow(grown.cast(), layout(64, 8), layout(64, 64))
+                .unwrap();
+            assert_eq!(*moved.cast::<u8>().as_ptr().add(15), 7);
+            Global.deallocate(moved.cast(), layout(64, 64));
+        }
+    }
+
+    #[test]
+    fn test_zero_sized_blocks_are_aligned() {
+        let bump = Bump::new();
+        for alloc in [&Global as &dyn Allocator, &&bump] {
+            let block = alloc.allocate(layout(0, 32)).unwrap();
+            assert_eq!(block.len(), 0);
+            assert_eq!(block.cast::<u8>().as_ptr().addr() % 32, 0);
+            // SAFETY: the block came from this allocator.
+            unsafe { alloc.deallocate(block.cast(), layout(0, 32)) };
+        }
+        assert_eq!(bump.capacity(), 0);
+    }
+
+    #[test]
+    fn test_bump_aligns_blocks() {
+        let bump = Bump::with_capacity(256);
+        let a = filled(&bump, layout(3, 1), 1);
+        let b = filled(&bump, layout(8, 8), 2);
+        let c = filled(&bump, layout(1, 64), 3);
+        assert!(a < b && b < c);
+        // SAFETY: the blocks are live and were filled above.
+        unsafe {
+            assert_eq!(*a.as_ptr().add(2), 1);
+            assert_eq!(*b.as_ptr().add(7), 2);
+            assert_eq!(*c.as_ptr(), 3);
+        }
+    }
+
+    #[test]
+    fn test_bump_reuses_latest_block() {
+        let bump = &Bump::with_capacity(256);
+        let remaining = bump.remaining();
+        let a = filled(bump, layout(32, 8), 0);
+        let b = filled(bump, layout(32, 8), 0);
+        // SAFETY: both blocks came from `bump` and are freed once.
+        unsafe {
+            bump.deallocate(a, layout(32, 8));
+            assert_eq!(bump.remaining(), remaining - 64);
+            bump.deallocate(b, layout(32, 8));
+        }
+        assert_eq!(bump.remaining(), remaining - 32);
+    }
+
+    #[test]
+    fn test_bump_grows_latest_block_in_place() {
+        let bump = &Bump::with_capacity(256);
+        let ptr = filled(bump, layout(16, 8), 5);
+        // SAFETY: each pointer is the live block returned by the last call.
+        unsafe {
+            let grown = bump.grow(ptr, layout(16, 8), layout(64, 8)).unwrap();
+            assert_eq!(grown.cast::<u8>(), ptr);
+            let other = filled(bump, layout(8, 8), 6);
+            let moved = bump
+                .grow(grown.cast(), layout(64, 8), layout(128, 8))
+                .unwrap();
+            assert_ne!(moved.cast::<u8>(), ptr);
+            assert_eq!(*moved.cast::<u8>().as_ptr().add(15), 5);
+            assert_eq!(*other.as_ptr(), 6);
+            let shrunk = bump
+                .shrink(moved.cast(), layout(128, 8), layout(8, 8))
+                .unwrap();
+            assert_eq!(shrunk.cast::<u8>(), moved.cast::<u8>());
+        }
+    }
+
+    #[test]
+    fn test_bump_adds_chunks() {
+        let mut bump = Bump::new();
+        let blocks: Vec<_> = (0..100u8)
+            .map(|i| filled(&bump, layout(100, 4), i))
+            .collect();
+        for (i, block) in blocks.iter().enumerate() {
+            // SAFETY: every block is still live.
+            assert_eq!(unsafe { *block.as_ptr().add(99) }, i as u8);
+        }
+        let capacity = bump.capacity();
+        assert!(capacity >= 100 * 100);
+        filled(&bump, layout(4 * capacity, 8), 9);
+        assert!(bump.capacity() >= 5 * capacity);
+
+        bump.reset();
+        assert_eq!(bump.remaining(), bump.capacity());
+        assert!(bump.capacity() >= 4 * capacity);
+    }
+
+    #[test]
+    fn test_counting_records_requests() {
+        let counting = Counting::new(Global);
+        let ptr = filled(&counting, layout(32, 8), 0);
+        let other = filled(&counting, layout(16, 8), 0);
+        // SAFETY: each pointer is a live block from `counting`.
+        unsafe {
+            let grown = counting.grow(ptr, layout(32, 8), layout(96, 8)).unwrap();
+            let shrunk = counting
+                .shrink(grown.cast(), layout(96, 8), layout(8, 8))
+                .unwrap();
+            counting.deallocate(other, layout(16, 8));
+            assert_eq!(
+                counting.stats(),
+                AllocStats {
+                    allocations: 2,
+                    deallocations: 1,
+                    reallocations: 2,
+                    bytes_in_use: 8,
+                    peak_bytes: 112,
+                }
+            );
+            counting.deallocate(shrunk.cast(), layout(8, 8));
+        }
+        assert_eq!(counting.stats().live(), 0);
+        assert_eq!(counting.stats().bytes_in_use, 0);
+    }
+
+    #[test]
+    fn test_counting_over_bump() {
+        let bump = Bump::with_capacity(1024);
+        let counting = Counting::new(&bump);
+        filled(&counting, layout(40, 8), 0);
+        assert_eq!(counting.stats().allocations, 1);
+        assert_eq!(counting.inner().remaining(), bump.remaining());
+        assert!(bump.remaining() <= 1024 - 40);
+    }
+
+    #[test]
+    fn test_values_round_trip() {
+        let counting = Counting::new(Global);
+        let ptr = alloc_value(&counting, String::from("node"));
+        assert_eq!(counting.stats().bytes_in_use, mem::size_of::<String>());
+        // SAFETY: `ptr` came from `alloc_value` and is not used again.
+        let value = unsafe { free_value(&counting, ptr) };
+        assert_eq!(value, "node");
+        assert_eq!(counting.stats().live(), 0);
+    }
+
+    #[test]
+    fn test_same_as() {
+        assert!(Global.same_as(&Global));
+        let (first, second) = (Bump::new(), Bump::new());
+        assert!((&first).same_as(&&first));
+        assert!(!(&first).same_as(&&second));
+        let counting = Counting::new(Global);
+        assert!(counting.same_as(&counting));
+        assert!(!counting.same_as(&Counting::new(Global)));
+    }
+
+    #[test]
+    fn test_alloc_error_display() {
+        assert_eq!(AllocError.to_string(), "memory allocation failed");
+    }
+}
@@ -0,0 +1,722 @@
+// Arena-backed Linked List implementation in Rust
+
+use crate::dynamic_arrays::DynamicArray;
+use crate::error::Error;
+
+/// A handle to a node in an [`ArenaList`].
+///
+/// Handles pair a slot index with the generation of the node that was in
+/// the slot when the handle was made, so a handle to a removed node is
+/// rejected even after its slot has been reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    index: u32,
+    generation: u32,
+}
+
+/// A node in the arena, linked to the next one by handle
+struct Node<T> {
+    value: T,
+    next: Option<NodeId>,
+    /// How many nodes link to this one, which lets removal skip scanning
+    /// the arena when nothing but the head points here
+    incoming: usize,
+}
+
+/// What a slot of the arena holds
+enum Entry<T> {
+    Occupied(Node<T>),
+    /// A free slot, linked to the next free slot
+    Vacant {
+        next_free: Option<u32>,
+    },
+}
+
+/// A slot of the arena and the generation of its current or last node
+struct Slot<T> {
+    generation: u32,
+    entry: Entry<T>,
+}
+
+/// A cycle found in an [`ArenaList`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// The first node on the cycle reached from the head
+    pub start: NodeId,
+    /// The number of nodes on the cycle
+    pub length: usize,
+}
+
+/// A singly linked list whose nodes live in a [`DynamicArray`] and link to
+/// each other by `u32` index.
+///
+/// Unlike [`LinkedList`](crate::linked_lists::LinkedList), any node's `next`
+/// can point at any node, so the links can form a cycle or share nodes.
+/// Nodes sit next to each other in one allocation, which keeps traversal
+/// cache friendly. Removed slots go on a free list and are reused by later
+/// insertions; [`NodeId`] handles carry a generation so stale handles are
+/// detected instead of silently naming the new node.
+pub struct ArenaList<T> {
+    slots: DynamicArray<Slot<T>>,
+    head: Option<NodeId>,
+    free: Option<u32>,
+    length: usize,
+}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ArenaList<T> {
+    /// Create a new empty arena list
+    pub fn new() -> Self {
+        Self {
+            slots: DynamicArray::new(),
+            head: None,
+            free: None,
+            length: 0,
+        }
+    }
+
+    /// Get the number of live nodes in the arena, linked or not
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Check if the arena has no live nodes
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Check if `id` refers to a live node
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_ok()
+    }
+
+    /// Add a node that nothing links to yet, reusing a free slot if there
+    /// is one. O(1) amortized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena already has `u32::MAX` slots.
+    pub fn add(&mut self, value: T) -> NodeId {
+        let node = Entry::Occupied(Node {
+            value,
+            next: None,
+            incoming: 0,
+        });
+        self.length += 1;
+        match self.free {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                let Entry::Vacant { next_free } = slot.entry else {
+                    unreachable!("free list points at an occupied slot");
+                };
+                self.free = next_free;
+                slot.entry = node;
+                NodeId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                let index = u32::try_from(self.slots.len())
+                    .ok()
+                    .filter(|&index| index < u32::MAX)
+                    .expect("arena list is full");
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry: node,
+                });
+                NodeId {
+                    index,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Add a node in front of the head and make it the head. O(1).
+    pub fn push_front(&mut self, value: T) -> NodeId {
+        let id = self.add(value);
+        self.relink(id, self.head);
+        self.head = Some(id);
+        id
+    }
+
+    /// Add a node linked in after `id`. O(1).
+    pub fn insert_after(&mut self, id: NodeId, value: T) -> Result<NodeId, Error> {
+        let next = self.node(id)?.next;
+        let new = self.add(value);
+        self.relink(new, next);
+        self.relink(id, Some(new));
+        Ok(new)
+    }
+
+    /// Remove the head node and make its successor the head. O(1) unless
+    /// other nodes link to the head, as on a cycle through it.
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.remove(self.head?).ok()
+    }
+
+    /// Remove the node after `id`, linking `id` to the node after that.
+    /// O(1) unless other nodes link to the removed one.
+    pub fn remove_after(&mut self, id: NodeId) -> Result<Option<T>, Error> {
+        match self.node(id)?.next {
+            Some(next) => self.remove(next).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Remove a node, redirecting the head and every link to it to the node
+    /// after it.
+    ///
+    /// O(1) when no other node links to it; otherwise the whole arena is
+    /// scanned for those links, which is O(n) in the number of slots.
+    pub fn remove(&mut self, id: NodeId) -> Result<T, Error> {
+        let next = self.node(id)?.next;
+        self.relink(id, None);
+        // A node linking to itself leaves nothing to redirect to.
+        let next = next.filter(|&next| next != id);
+        if self.head == Some(id) {
+            self.head = next;
+        }
+        if self.node(id)?.incoming > 0 {
+            self.redirect(id, next);
+        }
+        let node = self.free_slot(id).expect("node was checked");
+        Ok(node.value)
+    }
+
+    /// Get the first node of the list
+    pub fn head(&self) -> Option<NodeId> {
+        self.head
+    }
+
+    /// Make `head` the first node of the list
+    pub fn set_head(&mut self, head: Option<NodeId>) -> Result<(), Error> {
+        if let Some(id) = head {
+            self.node(id)?;
+        }
+        self.head = head;
+        Ok(())
+    }
+
+    /// Get the node that `id` links to
+    pub fn next(&self, id: NodeId) -> Result<Option<NodeId>, Error> {
+        Ok(self.node(id)?.next)
+    }
+
+    /// Link `id` to `next`, which may be any node, including `id` itself
+    pub fn set_next(&mut self, id: NodeId, next: Option<NodeId>) -> Result<(), Error> {
+        self.node(id)?;
+        if let Some(next) = next {
+            self.node(next)?;
+        }
+        self.relink(id, next);
+        Ok(())
+    }
+
+    /// Get a reference to the value of a node
+    pub fn get(&self, id: NodeId) -> Result<&T, Error> {
+        Ok(&self.node(id)?.value)
+    }
+
+    /// Get a mutable reference to the value of a node
+    pub fn get_mut(&mut self, id: NodeId) -> Result<&mut T, Error> {
+        Ok(&mut self.node_mut(id)?.value)
+    }
+
+    /// Remove every node. Slots are kept, and handles from before the call
+    /// stay invalid after their slots are reused.
+    pub fn clear(&mut self) {
+        for index in 0..self.slots.len() {
+            if matches!(self.slots[index].entry, Entry::Occupied(_)) {
+                let generation = self.slots[index].generation;
+                self.free_slot(NodeId {
+                    index: index as u32,
+                    generation,
+                });
+            }
+        }
+        self.head = None;
+    }
+
+    /// Iterate over the values by following the links from the head.
+    ///
+    /// If the links form a cycle the iterator never ends; bound it with
+    /// [`Iterator::take`] or check [`has_cycle`](Self::has_cycle) first.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            next: self.head,
+        }
+    }
+
+    /// Check if following the links from the head loops forever
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle_floyd().is_some()
+    }
+
+    /// Find the cycle reachable from the head with Floyd's tortoise and
+    /// hare.
+    ///
+    /// The hare moves two nodes for each one the tortoise moves, so they
+    /// meet inside a cycle if there is one. Restarting the tortoise from the
+    /// head and moving both one node at a time then makes them meet at the
+    /// cycle's start. O(μ + λ) time and O(1) space, where μ is the length of
+    /// the path to the cycle and λ the length of the cycle.
+    pub fn find_cycle_floyd(&self) -> Option<Cycle> {
+        let head = self.head?;
+        let mut tortoise = head;
+        let mut hare = head;
+        loop {
+            tortoise = self.step(tortoise)?;
+            hare = self.step(self.step(hare)?)?;
+            if tortoise == hare {
+                break;
+            }
+        }
+        let mut start = head;
+        while start != hare {
+            start = self.step(start)?;
+            hare = self.step(hare)?;
+        }
+        let mut length = 1;
+        let mut node = self.step(start)?;
+        while node != start {
+            node = self.step(node)?;
+            length += 1;
+        }
+        Some(Cycle { start, length })
+    }
+
+    /// Find the cycle reachable from the head with Brent's algorithm.
+    ///
+    /// The tortoise teleports to the hare every power of two steps, which
+    /// finds the cycle length directly and calls `next` fewer times than
+    /// Floyd's algorithm. A second pass with the hare `length` nodes ahead
+    /// finds the start. O(μ + λ) time and O(1) space.
+    pub fn find_cycle_brent(&self) -> Option<Cycle> {
+        let head = self.head?;
+        let mut power = 1;
+        let mut length = 1;
+        let mut tortoise = head;
+        let mut hare = self.step(head)?;
+        while tortoise != hare {
+            if power == length {
+                tortoise = hare;
+                power *= 2;
+                length = 0;
+            }
+            hare = self.step(hare)?;
+            length += 1;
+        }
+        let mut start = head;
+        let mut hare = head;
+        for _ in 0..length {
+            hare = self.step(hare)?;
+        }
+        while start != hare {
+            start = self.step(start)?;
+            hare = self.step(hare)?;
+        }
+        Some(Cycle { start, length })
+    }
+
+    /// Get the live node `id` refers to
+    fn node(&self, id: NodeId) -> Result<&Node<T>, Error> {
+        match self.slots.get(id.index as usize) {
+            Some(Slot {
+                generation,
+                entry: Entry::Occupied(node),
+            }) if *generation == id.generation => Ok(node),
+            _ => Err(Error::InvalidHandle),
+        }
+    }
+
+    /// Get the live node `id` refers to, mutably
+    fn node_mut(&mut self, id: NodeId) -> Result<&mut Node<T>, Error> {
+        match self.slots.get_mut(id.index as usize) {
+            Some(Slot {
+                generation,
+                entry: Entry::Occupied(node),
+            }) if *generation == id.generation => Ok(node),
+            _ => Err(Error::InvalidHandle),
+        }
+    }
+
+    /// Follow the link out of a node. Links only ever point at live nodes.
+    fn step(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).expect("links point at live nodes").next
+    }
+
+    /// Link a live node to `next`, keeping the incoming counts in step
+    fn relink(&mut self, id: NodeId, next: Option<NodeId>) {
+        let node = self.node_mut(id).expect("node is live");
+        let old = core::mem::replace(&mut node.next, next);
+        if let Some(old) = old {
+            self.node_mut(old)
+                .expect("links point at live nodes")
+                .incoming -= 1;
+        }
+        if let Some(next) = next {
+            self.node_mut(next).expect("node is live").incoming += 1;
+        }
+    }
+
+    /// Point every link to `from` at `to` instead
+    fn redirect(&mut self, from: NodeId, to: Option<NodeId>) {
+        let mut redirected = 0;
+        for slot in self.slots.iter_mut() {
+            if let Entry::Occupied(node) = &mut slot.entry {
+                if node.next == Some(from) {
+                    node.next = to;
+                    redirected += 1;
+                }
+            }
+        }
+        self.node_mut(from).expect("node is live").incoming -= redirected;
+        if let Some(to) = to {
+            self.node_mut(to).expect("node is live").incoming += redirected;
+        }
+    }
+
+    /// Take the node out of its slot and put the slot on the free list.
+    ///
+    /// A slot whose generation would wrap around is retired instead of
+    /// reused, so no handle can ever match a later node by accident.
+    fn free_slot(&mut self, id: NodeId) -> Option<Node<T>> {
+        self.node(id).ok()?;
+        let slot = &mut self.slots[id.index as usize];
+        let retired = slot.generation == u32::MAX;
+        let next_free = if retired { None } else { self.free };
+        let Entry::Occupied(node) =
+            core::mem::replace(&mut slot.entry, Entry::Vacant { next_free })
+        else {
+            unreachable!("node was checked");
+        };
+        slot.generation = slot.generation.wrapping_add(1);
+        if !retired {
+            self.free = Some(id.index);
+        }
+        self.length -= 1;
+        Some(node)
+    }
+}
+
+impl<T> FromIterator<T> for ArenaList<T> {
+    /// Build a list that links the values in order from the head
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        let mut prev: Option<NodeId> = None;
+        for value in iter {
+            let id = match prev {
+                Some(prev) => list.insert_after(prev, value).expect("prev is live"),
+                None => list.push_front(value),
+            };
+            prev = Some(id);
+        }
+        list
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArenaList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// An iterator following the links of an [`ArenaList`] from its head
+pub struct Iter<'a, T> {
+    list: &'a ArenaList<T>,
+    next: Option<NodeId>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self
+            .list
+            .node(self.next?)
+            .expect("links point at live nodes");
+        self.next = node.next;
+        Some(&node.value)
+    }
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            list: self.list,
+            next: self.next,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a chain of `tail + cycle` nodes whose last node links back to
+    /// node `tail`, or ends when `cycle` is zero
+    fn rho(tail: usize, cycle: usize) -> (ArenaList<usize>, Vec<NodeId>) {
+        let mut list = ArenaList::new();
+        let ids: Vec<NodeId> = (0..tail + cycle).map(|value| list.add(value)).collect();
+        for pair in ids.windows(2) {
+            list.set_next(pair[0], Some(pair[1])).unwrap();
+        }
+        list.set_head(ids.first().copied()).unwrap();
+        if cycle > 0 {
+            list.set_next(ids[tail + cycle - 1], Some(ids[tail]))
+                .unwrap();
+        }
+        (list, ids)
+    }
+
+    fn to_vec(list: &ArenaList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_new() {
+        let list: ArenaList<i32> = ArenaList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.head(), None);
+        assert_eq!(list.iter().next(), None);
+        assert!(!list.has_cycle());
+    }
+
+    #[test]
+    fn test_link_nodes() {
+        let mut list = ArenaList::new();
+        let b = list.add("b");
+        let a = list.add("a");
+        list.set_head(Some(a)).unwrap();
+        list.set_next(a, Some(b)).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.next(a), Ok(Some(b)));
+        assert_eq!(list.next(b), Ok(None));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), ["a", "b"]);
+        *list.get_mut(b).unwrap() = "c";
+        assert_eq!(list.get(b), Ok(&"c"));
+    }
+
+    #[test]
+    fn test_push_front_and_insert_after() {
+        let mut list = ArenaList::new();
+        let three = list.push_front(3);
+        let one = list.push_front(1);
+        list.insert_after(one, 2).unwrap();
+        list.insert_after(three, 4).unwrap();
+        assert_eq!(to_vec(&list), [1, 2, 3, 4]);
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn test_pop_front_and_remove_after() {
+        let mut list: ArenaList<i32> = (1..=5).collect();
+        assert_eq!(list.pop_front(), Some(1));
+        let head = list.head().unwrap();
+        assert_eq!(list.remove_after(head), Ok(Some(3)));
+        assert_eq!(to_vec(&list), [2, 4, 5]);
+        let last = list
+            .next(list.next(head).unwrap().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(list.remove_after(last), Ok(None));
+        assert_eq!(list.len(), 3);
+        while list.pop_front().is_some() {}
+        assert!(list.is_empty());
+        assert_eq!(list.head(), None);
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let mut list: ArenaList<i32> = (0..3).collect();
+        let missing = NodeId {
+            index: 3,
+            generation: 0,
+        };
+        assert_eq!(list.get(missing), Err(Error::InvalidHandle));
+        assert_eq!(list.next(missing), Err(Error::InvalidHandle));
+        assert!(list.set_head(Some(missing)).is_err());
+        let head = list.head().unwrap();
+        assert!(list.set_next(head, Some(missing)).is_err());
+        assert_eq!(to_vec(&list), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_removed_slots_are_reused_with_new_generation() {
+        let mut list = ArenaList::new();
+        let a = list.add(1);
+        let b = list.add(2);
+        assert_eq!(list.remove(a), Ok(1));
+        assert!(!list.contains(a));
+        let c = list.add(3);
+        assert_eq!(c.index, a.index);
+        assert_ne!(c, a);
+        assert_eq!(list.get(a), Err(Error::InvalidHandle));
+        assert_eq!(list.remove(a), Err(Error::InvalidHandle));
+        assert_eq!(list.get(c), Ok(&3));
+        assert_eq!(list.get(b), Ok(&2));
+        assert_eq!(list.slots.len(), 2);
+    }
+
+    #[test]
+    fn test_free_list_reuses_last_removed_first() {
+        let mut list = ArenaList::new();
+        let ids: Vec<NodeId> = (0..4).map(|value| list.add(value)).collect();
+        list.remove(ids[1]).unwrap();
+        list.remove(ids[3]).unwrap();
+        assert_eq!(list.add(10).index, ids[3].index);
+        assert_eq!(list.add(11).index, ids[1].index);
+        assert_eq!(list.add(12).index, 4);
+        assert_eq!(list.len(), 5);
+    }
+
+    #[test]
+    fn test_exhausted_slots_are_retired() {
+        let mut list = ArenaList::new();
+        let id = list.add(1);
+        list.slots[0].generation = u32::MAX;
+        let id = NodeId {
+            generation: u32::MAX,
+            ..id
+        };
+        assert_eq!(list.remove(id), Ok(1));
+        let fresh = list.add(2);
+        assert_eq!(fresh.index, 1);
+        assert!(!list.contains(NodeId {
+            index: 0,
+            generation: 0
+        }));
+    }
+
+    #[test]
+    fn test_remove_redirects_links() {
+        let mut list: ArenaList<i32> = (1..=4).collect();
+        let second = list.next(list.head().unwrap()).unwrap().unwrap();
+        assert_eq!(list.remove(second), Ok(2));
+        assert_eq!(to_vec(&list), [1, 3, 4]);
+        let head = list.head().unwrap();
+        assert_eq!(list.remove(head), Ok(1));
+        assert_eq!(to_vec(&list), [3, 4]);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut list: ArenaList<i32> = (0..5).collect();
+        let head = list.head().unwrap();
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.head(), None);
+        let fresh = list.push_front(7);
+        assert!(!list.contains(head));
+        assert_eq!(list.get(fresh), Ok(&7));
+        assert_eq!(list.slots.len(), 5);
+    }
+
+    #[test]
+    fn test_no_cycle() {
+        let (list, _) = rho(5, 0);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        assert!(!list.has_cycle());
+        assert_eq!(list.find_cycle_floyd(), None);
+        assert_eq!(list.find_cycle_brent(), None);
+    }
+
+    #[test]
+    fn test_self_loop() {
+        let mut list = ArenaList::new();
+        let only = list.push_front(1);
+        list.set_next(only, Some(only)).unwrap();
+        let cycle = Cycle {
+            start: only,
+            length: 1,
+        };
+        assert_eq!(list.find_cycle_floyd(), Some(cycle));
+        assert_eq!(list.find_cycle_brent(), Some(cycle));
+        assert_eq!(list.iter().take(3).count(), 3);
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.head(), None);
+    }
+
+    #[test]
+    fn test_cycle_through_head() {
+        let (mut list, ids) = rho(0, 4);
+        let cycle = Cycle {
+            start: ids[0],
+            length: 4,
+        };
+        assert_eq!(list.find_cycle_floyd(), Some(cycle));
+        assert_eq!(list.find_cycle_brent(), Some(cycle));
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.head(), Some(ids[1]));
+        assert_eq!(
+            list.find_cycle_brent(),
+            Some(Cycle {
+                start: ids[1],
+                length: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_rho_shapes() {
+        for tail in 0..12 {
+            for cycle in 0..12 {
+                let (list, ids) = rho(tail, cycle);
+                let expected = (cycle > 0).then(|| Cycle {
+                    start: ids[tail],
+                    length: cycle,
+                });
+                assert_eq!(list.find_cycle_floyd(), expected, "{tail} + {cycle}");
+                assert_eq!(list.find_cycle_brent(), expected, "{tail} + {cycle}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_removing_from_a_cycle() {
+        let (mut list, ids) = rho(2, 4);
+        assert_eq!(list.remove_after(ids[5]), Ok(Some(2)));
+        assert_eq!(
+            list.find_cycle_floyd(),
+            Some(Cycle {
+                start: ids[3],
+                length: 3
+            })
+        );
+        assert_eq!(list.remove(ids[3]), Ok(3));
+        assert_eq!(list.next(ids[1]), Ok(Some(ids[4])));
+        assert_eq!(list.next(ids[5]), Ok(Some(ids[4])));
+        assert_eq!(
+            list.find_cycle_brent(),
+            Some(Cycle {
+                start: ids[4],
+                length: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_breaking_a_cycle() {
+        let (mut list, _) = rho(3, 5);
+        assert!(list.has_cycle());
+        let cycle = list.find_cycle_brent().unwrap();
+        let mut last = cycle.start;
+        for _ in 1..cycle.length {
+            last = list.next(last).unwrap().unwrap();
+        }
+        list.set_next(last, None).unwrap();
+        assert!(!list.has_cycle());
+        assert_eq!(list.iter().count(), 8);
+    }
+}
@@ -0,0 +1,779 @@
+use crate::arrays::Array;
+use crate::error::Error;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+
+/// Error returned when an element is added to a full [`ArrayVec`].
+///
+/// The rejected element is handed back so it isn't lost. It converts into the
+/// crate-wide [`Error`] when the element is no longer needed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T> {
+    element: T,
+    capacity: usize,
+}
+
+impl<T> CapacityError<T> {
+    /// Get the capacity of the collection that was full
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get a reference to the element that didn't fit
+    pub fn element(&self) -> &T {
+        &self.element
+    }
+
+    /// Take back the element that didn't fit
+    pub fn into_element(self) -> T {
+        self.element
+    }
+}
+
+impl<T> core::fmt::Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CapacityError")
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> core::fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Error::from(self).fmt(f)
+    }
+}
+
+impl<T> core::error::Error for CapacityError<T> {}
+
+impl<T> From<&CapacityError<T>> for Error {
+    fn from(err: &CapacityError<T>) -> Self {
+        Error::CapacityExceeded {
+            capacity: err.capacity,
+        }
+    }
+}
+
+impl<T> From<CapacityError<T>> for Error {
+    fn from(err: CapacityError<T>) -> Self {
+        Error::from(&err)
+    }
+}
+
+/// Error returned when an element can't be inserted into an [`ArrayVec`].
+///
+/// Like [`CapacityError`], it hands the rejected element back and converts
+/// into the crate-wide [`Error`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum InsertError<T> {
+    /// The index was past the end of a vector of length `len`
+    OutOfBounds {
+        element: T,
+        index: usize,
+        len: usize,
+    },
+    /// The vector was full
+    Full(CapacityError<T>),
+}
+
+impl<T> InsertError<T> {
+    /// Get a reference to the element that wasn't inserted
+    pub fn element(&self) -> &T {
+        match self {
+            InsertError::OutOfBounds { element, .. } => element,
+            InsertError::Full(err) => err.element(),
+        }
+    }
+
+    /// Take back the element that wasn't inserted
+    pub fn into_element(self) -> T {
+        match self {
+            InsertError::OutOfBounds { element, .. } => element,
+            InsertError::Full(err) => err.into_element(),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for InsertError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InsertError::OutOfBounds { index, len, .. } => f
+                .debug_struct("OutOfBounds")
+                .field("index", index)
+                .field("len", len)
+                .finish_non_exhaustive(),
+            InsertError::Full(err) => f.debug_tuple("Full").field(err).finish(),
+        }
+    }
+}
+
+impl<T> core::fmt::Display for InsertError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Error::from(self).fmt(f)
+    }
+}
+
+impl<T> core::error::Error for InsertError<T> {}
+
+impl<T> From<&InsertError<T>> for Error {
+    fn from(err: &InsertError<T>) -> Self {
+        match err {
+            InsertError::OutOfBounds { index, len, .. } => Error::out_of_bounds(*index, *len),
+            InsertError::Full(err) => Error::from(err),
+        }
+    }
+}
+
+impl<T> From<InsertError<T>> for Error {
+    fn from(err: InsertError<T>) -> Self {
+        Error::from(&err)
+    }
+}
+
+/// A vector of up to `N` elements stored inline, without allocating.
+///
+/// Unlike [`Array`], which is always full, only the first `len` slots hold
+/// elements. Adding to a full vector never panics: [`push`](Self::push) and
+/// [`insert`](Self::insert) return an [`Error`], and the `try_` methods hand
+/// the rejected element back so hot paths can recover it.
+pub struct ArrayVec<T, const N: usize> {
+    data: Array<MaybeUninit<T>, N>,
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    /// Create a new empty array vector
+    pub fn new() -> Self {
+        Self {
+            data: Array::new([const { MaybeUninit::uninit() }; N]),
+            len: 0,
+        }
+    }
+
+    /// Get the length of the array vector
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the array vector is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the capacity of the array vector, which is always `N`
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Check if the array vector is full
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Get how many more elements fit
+    pub fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    /// View the elements as a slice
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `len` slots are initialized.
+        unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+
+    /// View the elements as a mutable slice
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first `len` slots are initialized.
+        unsafe { core::slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+
+    fn as_ptr(&self) -> *const T {
+        self.data.as_slice().as_ptr() as *const T
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.data.as_mut_slice().as_mut_ptr() as *mut T
+    }
+
+    /// Push an element to the end, failing if the array vector is full
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        self.try_push(value).map_err(Error::from)
+    }
+
+    /// Push an element to the end, or hand it back if the array vector is full
+    pub fn try_push(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.len == N {
+            return Err(CapacityError {
+                element: value,
+                capacity: N,
+            });
+        }
+        // SAFETY: the array vector isn't full.
+        unsafe { self.push_unchecked(value) };
+        Ok(())
+    }
+
+    /// Push an element to the end without checking the capacity
+    ///
+    /// # Safety
+    ///
+    /// The array vector must not be full.
+    unsafe fn push_unchecked(&mut self, value: T) {
+        debug_assert!(self.len < N);
+        // SAFETY: `len < N`, so the slot is in bounds and uninitialized.
+        unsafe { self.as_mut_ptr().add(self.len).write(value) };
+        self.len += 1;
+    }
+
+    /// Pop an element from the end
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: the slot was initialized and is no longer counted by
+        // `len`, so it is read exactly once.
+        Some(unsafe { self.as_ptr().add(self.len).read() })
+    }
+
+    /// Get a reference to the element at the given index
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    /// Get a mutable reference to the element at the given index
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+
+    /// Set the element at the given index
+    pub fn set(&mut self, index: usize, value: T) -> Result<(), Error> {
+        match self.get_mut(index) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(Error::out_of_bounds(index, self.len)),
+        }
+    }
+
+    /// Insert an element at the given index, failing if the index is past
+    /// the end or the array vector is full
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), Error> {
+        self.try_insert(index, value).map_err(Error::from)
+    }
+
+    /// Insert an element at the given index, or hand it back if the index is
+    /// past the end or the array vector is full
+    pub fn try_insert(&mut self, index: usize, value: T) -> Result<(), InsertError<T>> {
+        if index > self.len {
+            return Err(InsertError::OutOfBounds {
+                element: value,
+                index,
+                len: self.len,
+            });
+        }
+        if self.len == N {
+            return Err(InsertError::Full(CapacityError {
+                element: value,
+                capacity: N,
+            }));
+        }
+        // SAFETY: `index <= len < N`; the tail is shifted one slot to the
+        // right within the array before the gap is filled.
+        unsafe {
+            let slot = self.as_mut_ptr().add(index);
+            ptr::copy(slot, slot.add(1), self.len - index);
+            slot.write(value);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove an element at the given index
+    pub fn remove(&mut self, index: usize) -> Result<T, Error> {
+        if index >= self.len {
+            return Err(Error::out_of_bounds(index, self.len));
+        }
+        // SAFETY: `index < len`; the element is read out before the tail is
+        // shifted left over its slot, so it is moved exactly once.
+        unsafe {
+            let slot = self.as_mut_ptr().add(index);
+            let value = slot.read();
+            ptr::copy(slot.add(1), slot, self.len - index - 1);
+            self.len -= 1;
+            Ok(value)
+        }
+    }
+
+    /// Remove an element at the given index in O(1) by moving the last
+    /// element into its place
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, Error> {
+        if index >= self.len {
+            return Err(Error::out_of_bounds(index, self.len));
+        }
+        let last = self.len - 1;
+        self.as_mut_slice().swap(index, last);
+        Ok(self.pop().expect("array vector is not empty"))
+    }
+
+    /// Drop every element past the first `len`
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = ptr::slice_from_raw_parts_mut(
+            // SAFETY: `len < self.len <= N`, so the offset is in bounds.
+            unsafe { self.as_mut_ptr().add(len) },
+            self.len - len,
+        );
+        // Forget the elements first so a panicking destructor can't lead to
+        // them being dropped a second time.
+        self.len = len;
+        // SAFETY: the elements were initialized and are no longer reachable.
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    /// Clear the array vector
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Iterate over the array vector
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    /// Iterate mutably over the array vector
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    /// Split the array vector in two at `at`, returning the elements from
+    /// `at` on
+    pub fn split_off(&mut self, at: usize) -> Result<Self, Error> {
+        if at > self.len {
+            return Err(Error::out_of_bounds(at, self.len));
+        }
+        let count = self.len - at;
+        let mut other = Self::new();
+        // SAFETY: the tail moves bit-for-bit into the new vector and this
+        // one forgets it, so each element is owned exactly once.
+        unsafe {
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), count);
+        }
+        self.len = at;
+        other.len = count;
+        Ok(other)
+    }
+
+    /// Convert a full array vector into an [`Array`], or hand it back if it
+    /// isn't full
+    pub fn into_array(self) -> Result<Array<T, N>, Self> {
+        if self.len < N {
+            return Err(self);
+        }
+        let this = core::mem::ManuallyDrop::new(self);
+        // SAFETY: all `N` slots are initialized and `MaybeUninit<T>` has the
+        // same layout as `T`; `this` is never dropped, so the elements are
+        // moved out exactly once.
+        let data = unsafe { ptr::read(this.as_ptr() as *const [T; N]) };
+        Ok(Array::new(data))
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut cloned = Self::new();
+        for value in self.iter() {
+            // SAFETY: `cloned` holds fewer elements than `self`.
+            unsafe { cloned.push_unchecked(value.clone()) };
+        }
+        cloned
+    }
+}
+
+impl<T: core::fmt::Debug, const N: usize> core::fmt::Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ArrayVec")
+            .field("data", &self.as_slice())
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<ArrayVec<U, M>>
+    for ArrayVec<T, N>
+{
+    fn eq(&self, other: &ArrayVec<U, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<[U; M]> for ArrayVec<T, N> {
+    fn eq(&self, other: &[U; M]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayVec<T, N> {}
+
+impl<T, const N: usize> From<Array<T, N>> for ArrayVec<T, N> {
+    fn from(array: Array<T, N>) -> Self {
+        let mut vec = Self::new();
+        for value in array.into_inner() {
+            // SAFETY: the array has exactly `N` elements.
+            unsafe { vec.push_unchecked(value) };
+        }
+        vec
+    }
+}
+
+impl<T: Clone, const N: usize> TryFrom<&[T]> for ArrayVec<T, N> {
+    type Error = Error;
+
+    fn try_from(slice: &[T]) -> Result<Self, Error> {
+        if slice.len() > N {
+            return Err(Error::CapacityExceeded { capacity: N });
+        }
+        let mut vec = Self::new();
+        for value in slice {
+            // SAFETY: the slice has at most `N` elements.
+            unsafe { vec.push_unchecked(value.clone()) };
+        }
+        Ok(vec)
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(mut self) -> IntoIter<T, N> {
+        let end = core::mem::replace(&mut self.len, 0);
+        IntoIter {
+            vec: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An owning iterator over the elements of an [`ArrayVec`]
+pub struct IntoIter<T, const N: usize> {
+    /// Storage with its length zeroed; the slots in `start..end` are owned
+    /// by the iterator
+    vec: ArrayVec<T, N>,
+    start: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        self.start += 1;
+        // SAFETY: the slot is initialized and `start` has moved past it, so
+        // it is read exactly once.
+        Some(unsafe { self.vec.as_ptr().add(self.start - 1).read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        self.end -= 1;
+        // SAFETY: the slot is initialized and now lies past `end`, so it is
+        // read exactly once.
+        Some(unsafe { self.vec.as_ptr().add(self.end).read() })
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let remaining = ptr::slice_from_raw_parts_mut(
+            // SAFETY: `start <= end <= N`, so the offset is in bounds.
+            unsafe { self.vec.as_mut_ptr().add(self.start) },
+            self.end - self.start,
+        );
+        self.start = self.end;
+        // SAFETY: the remaining elements are initialized and were never
+        // yielded.
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct DropCounter<'a>(&'a Cell<usize>);
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let vec: ArrayVec<i32, 4> = ArrayVec::new();
+        assert_eq!(vec.len(), 0);
+        assert!(vec.is_empty());
+        assert_eq!(vec.capacity(), 4);
+        assert_eq!(vec.remaining_capacity(), 4);
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut vec: ArrayVec<i32, 3> = ArrayVec::new();
+        vec.push(1).unwrap();
+        vec.push(2).unwrap();
+        vec.push(3).unwrap();
+        assert!(vec.is_full());
+        assert_eq!(vec.pop(), Some(3));
+        assert_eq!(vec.pop(), Some(2));
+        assert_eq!(vec.pop(), Some(1));
+        assert_eq!(vec.pop(), None);
+    }
+
+    #[test]
+    fn test_try_push() {
+        let mut vec: ArrayVec<String, 1> = ArrayVec::new();
+        assert!(vec.try_push(String::from("a")).is_ok());
+        let err = vec.try_push(String::from("b")).unwrap_err();
+        assert_eq!(err.element(), "b");
+        assert_eq!(err.capacity(), 1);
+        assert_eq!(err.to_string(), "capacity exceeded (capacity: 1)");
+        assert_eq!(Error::from(&err), Error::CapacityExceeded { capacity: 1 });
+        assert_eq!(err.into_element(), "b");
+        assert_eq!(vec, ["a"]);
+    }
+
+    #[test]
+    fn test_push_when_full() {
+        let mut vec: ArrayVec<i32, 1> = ArrayVec::new();
+        vec.push(1).unwrap();
+        assert_eq!(vec.push(2), Err(Error::CapacityExceeded { capacity: 1 }));
+        assert_eq!(vec, [1]);
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let mut vec: ArrayVec<i32, 4> = ArrayVec::new();
+        vec.push(1).unwrap();
+        vec.push(2).unwrap();
+        assert_eq!(vec.get(1), Some(&2));
+        assert_eq!(vec.get(2), None);
+        *vec.get_mut(0).unwrap() = 10;
+        assert!(vec.set(1, 20).is_ok());
+        assert!(vec.set(2, 30).is_err());
+        assert_eq!(vec, [10, 20]);
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut vec: ArrayVec<i32, 3> = ArrayVec::new();
+        vec.push(1).unwrap();
+        vec.push(3).unwrap();
+        vec.insert(1, 2).unwrap();
+        assert_eq!(vec, [1, 2, 3]);
+        let err = vec.try_insert(0, 0).unwrap_err();
+        assert_eq!(Error::from(&err), Error::CapacityExceeded { capacity: 3 });
+        assert_eq!(err.into_element(), 0);
+        assert_eq!(
+            vec.insert(0, 0),
+            Err(Error::CapacityExceeded { capacity: 3 })
+        );
+        assert_eq!(vec, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_insert_out_of_bounds() {
+        let mut vec: ArrayVec<String, 3> = ArrayVec::new();
+        let err = vec.try_insert(1, String::from("a")).unwrap_err();
+        assert_eq!(err.element(), "a");
+        assert_eq!(err.to_string(), "index 1 is out of bounds for length 0");
+        assert_eq!(
+            vec.insert(1, String::from("a")),
+            Err(Error::out_of_bounds(1, 0))
+        );
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut vec: ArrayVec<i32, 4> = ArrayVec::new();
+        for i in 1..=4 {
+            vec.push(i).unwrap();
+        }
+        assert_eq!(vec.remove(1), Ok(2));
+        assert_eq!(vec, [1, 3, 4]);
+        assert_eq!(vec.swap_remove(0), Ok(1));
+        assert_eq!(vec, [4, 3]);
+        assert!(vec.remove(2).is_err());
+        assert!(vec.swap_remove(2).is_err());
+    }
+
+    #[test]
+    fn test_truncate_and_clear() {
+        let drops = Cell::new(0);
+        let mut vec: ArrayVec<DropCounter, 4> = ArrayVec::new();
+        for _ in 0..4 {
+            vec.push(DropCounter(&drops)).unwrap();
+        }
+        vec.truncate(2);
+        assert_eq!(drops.get(), 2);
+        vec.clear();
+        assert_eq!(drops.get(), 4);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_drop() {
+        let drops = Cell::new(0);
+        {
+            let mut vec: ArrayVec<DropCounter, 4> = ArrayVec::new();
+            for _ in 0..3 {
+                vec.push(DropCounter(&drops)).unwrap();
+            }
+        }
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut vec: ArrayVec<i32, 4> = ArrayVec::new();
+        vec.push(1).unwrap();
+        vec.push(2).unwrap();
+        for val in &mut vec {
+            *val *= 10;
+        }
+        assert_eq!(vec.iter().copied().collect::<Vec<_>>(), [10, 20]);
+        assert_eq!(vec[1], 20);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let drops = Cell::new(0);
+        let mut vec: ArrayVec<DropCounter, 4> = ArrayVec::new();
+        for _ in 0..4 {
+            vec.push(DropCounter(&drops)).unwrap();
+        }
+        let mut iter = vec.into_iter();
+        assert_eq!(iter.len(), 4);
+        drop(iter.next());
+        drop(iter.next_back());
+        assert_eq!(drops.get(), 2);
+        drop(iter);
+        assert_eq!(drops.get(), 4);
+    }
+
+    #[test]
+    fn test_array_conversions() {
+        let vec = ArrayVec::from(Array::new([1, 2, 3]));
+        assert!(vec.is_full());
+        let array = vec.into_array().unwrap();
+        assert_eq!(array.get(2), Some(&3));
+
+        let mut partial: ArrayVec<i32, 3> = ArrayVec::new();
+        partial.push(1).unwrap();
+        let partial = partial.into_array().unwrap_err();
+        assert_eq!(partial, [1]);
+    }
+
+    #[test]
+    fn test_try_from_slice() {
+        let vec = ArrayVec::<i32, 3>::try_from(&[1, 2][..]).unwrap();
+        assert_eq!(vec, [1, 2]);
+        assert_eq!(
+            ArrayVec::<i32, 1>::try_from(&[1, 2][..]),
+            Err(Error::CapacityExceeded { capacity: 1 })
+        );
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut vec: ArrayVec<String, 4> = ArrayVec::new();
+        for word in ["a", "b", "c"] {
+            vec.push(word.to_string()).unwrap();
+        }
+        let tail = vec.split_off(1).unwrap();
+        assert_eq!(vec, ["a"]);
+        assert_eq!(tail, ["b", "c"]);
+        assert!(vec.split_off(1).unwrap().is_empty());
+        assert_eq!(vec.split_off(2).unwrap_err(), Error::out_of_bounds(2, 1));
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut vec: ArrayVec<String, 2> = ArrayVec::new();
+        vec.push(String::from("a")).unwrap();
+        let cloned = vec.clone();
+        assert_eq!(cloned, vec);
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut vec: ArrayVec<i32, 0> = ArrayVec::new();
+        assert!(vec.is_full());
+        assert!(vec.try_push(1).is_err());
+    }
+}
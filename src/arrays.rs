@@ -1,3 +1,10 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::ops::{Index, IndexMut};
+use core::slice::SliceIndex;
+
+use crate::error::Error;
+
 /// A fixed-size array implementation
 pub struct Array<T, const N: usize> {
     data: [T; N],
@@ -5,22 +12,37 @@ pub struct Array<T, const N: usize> {
 
 impl<T, const N: usize> Array<T, N> {
     /// Create a new array from a given array
-    pub fn new(data: [T; N]) -> Self {
+    pub const fn new(data: [T; N]) -> Self {
         Self { data }
     }
 
+    /// Create a new array with every element set to `value`
+    pub const fn filled(value: T) -> Self
+    where
+        T: Copy,
+    {
+        Self { data: [value; N] }
+    }
+
+    /// Create a new array whose element at each index is `f(index)`
+    pub fn from_fn(f: impl FnMut(usize) -> T) -> Self {
+        Self {
+            data: core::array::from_fn(f),
+        }
+    }
+
     /// Get the length of the array
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         N
     }
 
     /// Check if the array is empty
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         N == 0
     }
 
     /// Get a reference to the element at the given index
-    pub fn get(&self, index: usize) -> Option<&T> {
+    pub const fn get(&self, index: usize) -> Option<&T> {
         if index < N {
             Some(&self.data[index])
         } else {
@@ -38,30 +60,73 @@ impl<T, const N: usize> Array<T, N> {
     }
 
     /// Set the element at the given index
-    pub fn set(&mut self, index: usize, value: T) -> Result<(), &'static str> {
+    pub fn set(&mut self, index: usize, value: T) -> Result<(), Error> {
         if index < N {
             self.data[index] = value;
             Ok(())
         } else {
-            Err("Index out of bounds")
+            Err(Error::out_of_bounds(index, N))
         }
     }
 
+    /// View the array as a slice
+    pub const fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// View the array as a mutable slice
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Consume the array, returning the underlying built-in array
+    pub fn into_inner(self) -> [T; N] {
+        self.data
+    }
+
     /// Iterate over the array
-    pub fn iter(&self) -> std::slice::Iter<T> {
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
         self.data.iter()
     }
 
     /// Iterate mutably over the array
-    pub fn iter_mut(&mut self) -> std::slice::IterMut<T> {
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
         self.data.iter_mut()
     }
+
+    /// Apply `f` to every element, in order
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Array<U, N> {
+        Array {
+            data: self.data.map(f),
+        }
+    }
+
+    /// Pair up the elements of two arrays of the same length
+    pub fn zip<U>(self, other: Array<U, N>) -> Array<(T, U), N> {
+        let mut other = other.data.into_iter();
+        self.map(|value| {
+            let paired = other.next().expect("arrays have the same length");
+            (value, paired)
+        })
+    }
+
+    /// Split into the elements before `mid` and the rest, or get `None`
+    /// if `mid` is past the end
+    pub fn split_at(&self, mid: usize) -> Option<(&[T], &[T])> {
+        self.data.split_at_checked(mid)
+    }
+
+    /// Iterate over `size`-element chunks, the last of which may be
+    /// shorter. Panics if `size` is zero.
+    pub fn chunks(&self, size: usize) -> core::slice::Chunks<'_, T> {
+        self.data.chunks(size)
+    }
 }
 
-impl<T: Default + Copy, const N: usize> Array<T, N> {
+impl<T: Default, const N: usize> Default for Array<T, N> {
     /// Create a new array filled with default values
-    pub fn default() -> Self {
-        Self { data: [T::default(); N] }
+    fn default() -> Self {
+        Self::from_fn(|_| T::default())
     }
 }
 
@@ -73,11 +138,124 @@ impl<T: Clone, const N: usize> Clone for Array<T, N> {
     }
 }
 
-impl<T: std::fmt::Debug, const N: usize> std::fmt::Debug for Array<T, N> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Array")
-            .field("data", &self.data)
-            .finish()
+impl<T: core::fmt::Debug, const N: usize> core::fmt::Debug for Array<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Array").field("data", &self.data).finish()
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<Array<U, N>> for Array<T, N> {
+    fn eq(&self, other: &Array<U, N>) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for Array<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for Array<T, N> {
+    /// Compare lexicographically
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.data.partial_cmp(&other.data)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for Array<T, N> {
+    /// Compare lexicographically
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.data.cmp(&other.data)
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for Array<T, N> {
+    /// Hash like the equivalent slice, so `Borrow<[T]>` lookups agree
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+impl<T, const N: usize, I: SliceIndex<[T]>> Index<I> for Array<T, N> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        &self.data[index]
+    }
+}
+
+impl<T, const N: usize, I: SliceIndex<[T]>> IndexMut<I> for Array<T, N> {
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.data[index]
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Array<T, N> {
+    fn from(data: [T; N]) -> Self {
+        Self::new(data)
+    }
+}
+
+impl<T, const N: usize> From<Array<T, N>> for [T; N] {
+    fn from(array: Array<T, N>) -> Self {
+        array.into_inner()
+    }
+}
+
+impl<T: Clone, const N: usize> TryFrom<&[T]> for Array<T, N> {
+    type Error = Error;
+
+    /// Clone the elements of a slice of exactly `N` elements
+    fn try_from(slice: &[T]) -> Result<Self, Error> {
+        if slice.len() != N {
+            return Err(Error::LengthMismatch {
+                expected: N,
+                found: slice.len(),
+            });
+        }
+        Ok(Self::from_fn(|index| slice[index].clone()))
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for Array<T, N> {
+    fn as_ref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for Array<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+}
+
+impl<T, const N: usize> core::borrow::Borrow<[T]> for Array<T, N> {
+    fn borrow(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T, const N: usize> IntoIterator for Array<T, N> {
+    type Item = T;
+    type IntoIter = core::array::IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a Array<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut Array<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
@@ -146,12 +324,22 @@ mod tests {
         assert_eq!(arr.get(2), Some(&13));
     }
 
+    #[test]
+    fn test_as_slice() {
+        let mut arr: Array<i32, 3> = Array::new([1, 2, 3]);
+        assert_eq!(arr.as_slice(), &[1, 2, 3]);
+        arr.as_mut_slice()[0] = 10;
+        assert_eq!(arr.into_inner(), [10, 2, 3]);
+    }
+
     #[test]
     fn test_default() {
         let arr: Array<i32, 3> = Array::default();
         assert_eq!(arr.get(0), Some(&0));
         assert_eq!(arr.get(1), Some(&0));
         assert_eq!(arr.get(2), Some(&0));
+        let strings: Array<String, 2> = Default::default();
+        assert_eq!(strings, Array::new([String::new(), String::new()]));
     }
 
     #[test]
@@ -162,4 +350,97 @@ mod tests {
         assert_eq!(cloned.get(1), Some(&2));
         assert_eq!(cloned.get(2), Some(&3));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_const_constructors() {
+        const ZEROS: Array<u8, 4> = Array::filled(0);
+        const PRIMES: Array<u32, 3> = Array::new([2, 3, 5]);
+        const SECOND: Option<&u32> = PRIMES.get(1);
+        const LEN: usize = PRIMES.len();
+        assert_eq!(ZEROS.as_slice(), [0; 4]);
+        assert_eq!(SECOND, Some(&3));
+        assert_eq!(LEN, 3);
+    }
+
+    #[test]
+    fn test_from_fn_map_and_zip() {
+        let squares: Array<usize, 4> = Array::from_fn(|i| i * i);
+        assert_eq!(squares.into_inner(), [0, 1, 4, 9]);
+        let words = Array::new(["a", "bb", "ccc"]).map(str::len);
+        assert_eq!(words, Array::new([1, 2, 3]));
+        let pairs = words.zip(Array::new(['x', 'y', 'z']));
+        assert_eq!(pairs.into_inner(), [(1, 'x'), (2, 'y'), (3, 'z')]);
+    }
+
+    #[test]
+    fn test_split_at_and_chunks() {
+        let arr = Array::new([1, 2, 3, 4, 5]);
+        assert_eq!(arr.split_at(2), Some((&[1, 2][..], &[3, 4, 5][..])));
+        assert_eq!(arr.split_at(5), Some((&[1, 2, 3, 4, 5][..], &[][..])));
+        assert_eq!(arr.split_at(6), None);
+        let chunks: Vec<&[i32]> = arr.chunks(2).collect();
+        assert_eq!(chunks, [&[1, 2][..], &[3, 4], &[5]]);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut arr = Array::new([1, 2, 3, 4]);
+        assert_eq!(arr[2], 3);
+        assert_eq!(arr[1..3], [2, 3]);
+        arr[0] = 10;
+        arr[2..].copy_from_slice(&[30, 40]);
+        assert_eq!(arr.into_inner(), [10, 2, 30, 40]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds() {
+        let arr = Array::new([1, 2, 3]);
+        let _ = arr[3];
+    }
+
+    #[test]
+    fn test_conversions() {
+        let arr: Array<i32, 3> = [1, 2, 3].into();
+        let raw: [i32; 3] = arr.into();
+        assert_eq!(raw, [1, 2, 3]);
+        let from_slice = Array::<i32, 2>::try_from(&raw[1..]).unwrap();
+        assert_eq!(from_slice, Array::new([2, 3]));
+        assert_eq!(
+            Array::<i32, 2>::try_from(&raw[..]),
+            Err(Error::LengthMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(from_slice.as_ref(), [2, 3]);
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let mut arr = Array::new([String::from("a"), String::from("b")]);
+        for value in &mut arr {
+            value.push('!');
+        }
+        assert_eq!((&arr).into_iter().count(), 2);
+        let owned: Vec<String> = arr.into_iter().collect();
+        assert_eq!(owned, ["a!", "b!"]);
+    }
+
+    #[test]
+    fn test_ordering_and_hash() {
+        use std::collections::HashSet;
+
+        let mut arrays = vec![Array::new([2, 1]), Array::new([1, 9]), Array::new([1, 2])];
+        arrays.sort();
+        assert_eq!(
+            arrays,
+            [Array::new([1, 2]), Array::new([1, 9]), Array::new([2, 1])]
+        );
+        assert!(Array::new([0.5, 1.0]) < Array::new([0.5, 2.0]));
+
+        let set: HashSet<Array<i32, 2>> = arrays.into_iter().collect();
+        assert!(set.contains(&[1, 9][..]));
+        assert!(!set.contains(&[9, 1][..]));
+    }
+}
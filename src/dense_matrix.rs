@@ -0,0 +1,1115 @@
+// Dense Matrix implementation in Rust
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut, Range};
+
+use crate::dynamic_arrays::DynamicArray;
+use crate::error::Error;
+use crate::numeric::{Field, Numeric};
+
+/// Side length of the square tiles that blocked multiplication works on
+const BLOCK: usize = 32;
+
+/// Where each element of a matrix or view sits in its flat storage: the
+/// element at (row, col) is at `row * row_stride + col * col_stride`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Layout {
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+impl Layout {
+    /// Lay out a row-major matrix with no gaps
+    fn contiguous(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            row_stride: cols,
+            col_stride: 1,
+        }
+    }
+
+    /// Get the storage index of (row, col) if it is in bounds
+    fn index(&self, row: usize, col: usize) -> Option<usize> {
+        (row < self.rows && col < self.cols).then(|| row * self.row_stride + col * self.col_stride)
+    }
+
+    /// Swap the roles of rows and columns
+    fn transpose(self) -> Self {
+        Self {
+            rows: self.cols,
+            cols: self.rows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+        }
+    }
+
+    /// Narrow to a block, returning its layout and the storage index its
+    /// first element starts at. A range that starts after it ends is out of
+    /// bounds at its start.
+    fn block(self, rows: Range<usize>, cols: Range<usize>) -> Result<(Self, usize), Error> {
+        if rows.start > rows.end {
+            return Err(Error::out_of_bounds(rows.start, rows.end));
+        }
+        if cols.start > cols.end {
+            return Err(Error::out_of_bounds(cols.start, cols.end));
+        }
+        if rows.end > self.rows {
+            return Err(Error::out_of_bounds(rows.end, self.rows));
+        }
+        if cols.end > self.cols {
+            return Err(Error::out_of_bounds(cols.end, self.cols));
+        }
+        let block = Self {
+            rows: rows.len(),
+            cols: cols.len(),
+            ..self
+        };
+        let start = match block.index(0, 0) {
+            Some(_) => rows.start * self.row_stride + cols.start * self.col_stride,
+            None => 0,
+        };
+        Ok((block, start))
+    }
+}
+
+/// A runtime-sized matrix stored row-major in one flat [`DynamicArray`].
+///
+/// Views borrow the storage with their own strides, so sub-matrices,
+/// transposes, rows and columns never copy.
+pub struct DenseMatrix<T> {
+    data: DynamicArray<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> DenseMatrix<T> {
+    /// Create a new matrix whose entry at (row, col) is `f(row, col)`
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let mut data = DynamicArray::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                data.push(f(row, col));
+            }
+        }
+        Self { data, rows, cols }
+    }
+
+    /// Create a new matrix with every entry set to `value`
+    pub fn from_elem(rows: usize, cols: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(rows, cols, |_, _| value.clone())
+    }
+
+    /// Create a new matrix from row-major data, which must hold exactly
+    /// `rows * cols` elements
+    pub fn from_data(rows: usize, cols: usize, data: DynamicArray<T>) -> Result<Self, Error> {
+        if rows.checked_mul(cols) != Some(data.len()) {
+            return Err(Error::ShapeMismatch {
+                left: (rows, cols),
+                right: (data.len(), 1),
+            });
+        }
+        Ok(Self { data, rows, cols })
+    }
+
+    /// Get the number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Get the number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Get the number of rows and columns
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Get a reference to the entry at (row, col)
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        let index = self.layout().index(row, col)?;
+        self.data.get(index)
+    }
+
+    /// Get a mutable reference to the entry at (row, col)
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        let index = self.layout().index(row, col)?;
+        self.data.get_mut(index)
+    }
+
+    /// Get a row as a slice
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.rows).then(|| &self.data[row * self.cols..(row + 1) * self.cols])
+    }
+
+    /// Get a row as a mutable slice
+    pub fn row_mut(&mut self, row: usize) -> Option<&mut [T]> {
+        let cols = self.cols;
+        (row < self.rows).then(|| &mut self.data[row * cols..(row + 1) * cols])
+    }
+
+    /// Swap two rows in O(cols)
+    pub fn swap_rows(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        for row in [a, b] {
+            if row >= self.rows {
+                return Err(Error::out_of_bounds(row, self.rows));
+            }
+        }
+        for col in 0..self.cols {
+            self.data.swap(a * self.cols + col, b * self.cols + col);
+        }
+        Ok(())
+    }
+
+    /// View the entries in row-major order
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// View the entries mutably in row-major order
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Consume the matrix, returning its row-major storage
+    pub fn into_data(self) -> DynamicArray<T> {
+        self.data
+    }
+
+    /// Change the shape in place, keeping the entries in row-major order.
+    /// The new shape must hold the same number of entries. O(1).
+    pub fn reshape(&mut self, rows: usize, cols: usize) -> Result<(), Error> {
+        if rows.checked_mul(cols) != Some(self.data.len()) {
+            return Err(Error::ShapeMismatch {
+                left: self.shape(),
+                right: (rows, cols),
+            });
+        }
+        self.rows = rows;
+        self.cols = cols;
+        Ok(())
+    }
+
+    /// View the whole matrix
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView {
+            data: &self.data,
+            layout: self.layout(),
+        }
+    }
+
+    /// View the whole matrix mutably
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_, T> {
+        let layout = self.layout();
+        MatrixViewMut {
+            data: &mut self.data,
+            layout,
+        }
+    }
+
+    /// View the block covering `rows` and `cols` without copying
+    pub fn submatrix(
+        &self,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Result<MatrixView<'_, T>, Error> {
+        self.view().submatrix(rows, cols)
+    }
+
+    /// View the transpose without copying
+    pub fn transpose_view(&self) -> MatrixView<'_, T> {
+        self.view().transpose()
+    }
+
+    /// View one column as an n×1 matrix without copying
+    pub fn column(&self, col: usize) -> Option<MatrixView<'_, T>> {
+        self.view().column(col)
+    }
+
+    fn layout(&self) -> Layout {
+        Layout::contiguous(self.rows, self.cols)
+    }
+}
+
+impl<T: Clone> DenseMatrix<T> {
+    /// Get the transpose as a new matrix
+    pub fn transpose(&self) -> Self {
+        self.transpose_view().to_matrix()
+    }
+}
+
+impl<T: Numeric> DenseMatrix<T> {
+    /// Create a new matrix of zeros
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self::from_elem(rows, cols, T::zero())
+    }
+
+    /// Create a new n×n identity matrix
+    pub fn identity(n: usize) -> Self {
+        Self::from_fn(
+            n,
+            n,
+            |row, col| if row == col { T::one() } else { T::zero() },
+        )
+    }
+
+    /// Multiply by `rhs`, as [`MatrixView::matmul`]
+    pub fn matmul(&self, rhs: &Self) -> Result<Self, Error> {
+        self.view().matmul(&rhs.view())
+    }
+}
+
+impl<T: Field> DenseMatrix<T> {
+    /// Factor a square matrix into `P·A = L·U` with partial pivoting, or
+    /// get [`Error::SingularMatrix`] if it has no inverse. O(n³).
+    pub fn lu(&self) -> Result<Lu<T>, Error> {
+        let n = self.square()?;
+        let mut factors = self.clone();
+        let mut permutation: DynamicArray<usize> = (0..n).collect();
+        let mut odd = false;
+        for k in 0..n {
+            let pivot = pivot_row(&factors, k, k);
+            if factors[(pivot, k)] == T::zero() {
+                return Err(Error::SingularMatrix);
+            }
+            if pivot != k {
+                factors.swap_rows(k, pivot)?;
+                permutation.swap(k, pivot);
+                odd = !odd;
+            }
+            for i in k + 1..n {
+                let factor = factors[(i, k)] / factors[(k, k)];
+                factors[(i, k)] = factor;
+                for j in k + 1..n {
+                    factors[(i, j)] = factors[(i, j)] - factor * factors[(k, j)];
+                }
+            }
+        }
+        Ok(Lu {
+            factors,
+            permutation,
+            odd,
+        })
+    }
+
+    /// Solve `A·x = b` by Gaussian elimination with partial pivoting.
+    /// O(n³).
+    pub fn solve(&self, b: &[T]) -> Result<DynamicArray<T>, Error> {
+        let n = self.square()?;
+        if b.len() != n {
+            return Err(Error::ShapeMismatch {
+                left: self.shape(),
+                right: (b.len(), 1),
+            });
+        }
+        // Eliminate on the augmented matrix [A | b].
+        let mut augmented =
+            Self::from_fn(
+                n,
+                n + 1,
+                |row, col| {
+                    if col < n {
+                        self[(row, col)]
+                    } else {
+                        b[row]
+                    }
+                },
+            );
+        for k in 0..n {
+            let pivot = pivot_row(&augmented, k, k);
+            if augmented[(pivot, k)] == T::zero() {
+                return Err(Error::SingularMatrix);
+            }
+            augmented.swap_rows(k, pivot)?;
+            for i in k + 1..n {
+                let factor = augmented[(i, k)] / augmented[(k, k)];
+                for j in k..=n {
+                    augmented[(i, j)] = augmented[(i, j)] - factor * augmented[(k, j)];
+                }
+            }
+        }
+
+        let mut x = DynamicArray::from(vec![T::zero(); n]);
+        for i in (0..n).rev() {
+            let sum = (i + 1..n).fold(augmented[(i, n)], |sum, j| sum - augmented[(i, j)] * x[j]);
+            x[i] = sum / augmented[(i, i)];
+        }
+        Ok(x)
+    }
+
+    /// Get the rank by reducing a copy to row echelon form. O(rows·cols·min).
+    ///
+    /// Float entries only count as zero when they are exactly zero.
+    pub fn rank(&self) -> usize {
+        let mut m = self.clone();
+        let mut rank = 0;
+        for col in 0..self.cols {
+            if rank == self.rows {
+                break;
+            }
+            let pivot = pivot_row(&m, rank, col);
+            if m[(pivot, col)] == T::zero() {
+                continue;
+            }
+            m.swap_rows(rank, pivot).expect("pivot rows are in bounds");
+            for i in rank + 1..self.rows {
+                let factor = m[(i, col)] / m[(rank, col)];
+                for j in col..self.cols {
+                    m[(i, j)] = m[(i, j)] - factor * m[(rank, j)];
+                }
+            }
+            rank += 1;
+        }
+        rank
+    }
+
+    /// Get the determinant of a square matrix from its LU decomposition
+    pub fn determinant(&self) -> Result<T, Error> {
+        match self.lu() {
+            Ok(lu) => Ok(lu.determinant()),
+            Err(Error::SingularMatrix) => Ok(T::zero()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get the side length, or [`Error::NotSquare`]
+    fn square(&self) -> Result<usize, Error> {
+        if self.rows != self.cols {
+            return Err(Error::NotSquare {
+                rows: self.rows,
+                cols: self.cols,
+            });
+        }
+        Ok(self.rows)
+    }
+}
+
+/// Find the best pivot for `col` among the rows from `start` down
+fn pivot_row<T: Field>(m: &DenseMatrix<T>, start: usize, col: usize) -> usize {
+    (start + 1..m.rows).fold(start, |best, row| {
+        if m[(row, col)].better_pivot(m[(best, col)]) {
+            row
+        } else {
+            best
+        }
+    })
+}
+
+impl<T> Index<(usize, usize)> for DenseMatrix<T> {
+    type Output = T;
+
+    /// Get the entry at (row, col), panicking if it is out of bounds
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        match self.get(row, col) {
+            Some(value) => value,
+            None => panic!(
+                "index ({row}, {col}) is out of bounds for a {}×{} matrix",
+                self.rows, self.cols
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for DenseMatrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let (rows, cols) = self.shape();
+        match self.get_mut(row, col) {
+            Some(value) => value,
+            None => panic!("index ({row}, {col}) is out of bounds for a {rows}×{cols} matrix"),
+        }
+    }
+}
+
+impl<T: Clone> Clone for DenseMatrix<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for DenseMatrix<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape() == other.shape() && self.data == other.data
+    }
+}
+
+impl<T: Eq> Eq for DenseMatrix<T> {}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for DenseMatrix<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.view().fmt(f)
+    }
+}
+
+/// A borrowed, possibly strided view of a [`DenseMatrix`]
+pub struct MatrixView<'a, T> {
+    data: &'a [T],
+    layout: Layout,
+}
+
+impl<'a, T> MatrixView<'a, T> {
+    /// Get the number of rows
+    pub fn rows(&self) -> usize {
+        self.layout.rows
+    }
+
+    /// Get the number of columns
+    pub fn cols(&self) -> usize {
+        self.layout.cols
+    }
+
+    /// Get the number of rows and columns
+    pub fn shape(&self) -> (usize, usize) {
+        (self.layout.rows, self.layout.cols)
+    }
+
+    /// Get a reference to the entry at (row, col)
+    pub fn get(&self, row: usize, col: usize) -> Option<&'a T> {
+        Some(&self.data[self.layout.index(row, col)?])
+    }
+
+    /// View the block covering `rows` and `cols` of this view
+    pub fn submatrix(&self, rows: Range<usize>, cols: Range<usize>) -> Result<Self, Error> {
+        let (layout, start) = self.layout.block(rows, cols)?;
+        Ok(Self {
+            data: &self.data[start..],
+            layout,
+        })
+    }
+
+    /// View the transpose of this view
+    pub fn transpose(&self) -> Self {
+        Self {
+            data: self.data,
+            layout: self.layout.transpose(),
+        }
+    }
+
+    /// View one row as a 1×n matrix
+    pub fn row(&self, row: usize) -> Option<Self> {
+        (row < self.rows()).then(|| self.submatrix(row..row + 1, 0..self.cols()).unwrap())
+    }
+
+    /// View one column as an n×1 matrix
+    pub fn column(&self, col: usize) -> Option<Self> {
+        (col < self.cols()).then(|| self.submatrix(0..self.rows(), col..col + 1).unwrap())
+    }
+
+    /// Iterate over the entries in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let (data, layout) = (self.data, self.layout);
+        (0..layout.rows).flat_map(move |row| {
+            (0..layout.cols)
+                .map(move |col| &data[row * layout.row_stride + col * layout.col_stride])
+        })
+    }
+
+    /// Copy the viewed entries into a new contiguous matrix
+    pub fn to_matrix(&self) -> DenseMatrix<T>
+    where
+        T: Clone,
+    {
+        DenseMatrix::from_fn(self.rows(), self.cols(), |row, col| {
+            self[(row, col)].clone()
+        })
+    }
+
+    /// Multiply by `rhs`, working tile by tile so both operands stay in
+    /// cache. O(n·k·m) for an n×k by k×m product.
+    pub fn matmul(&self, rhs: &MatrixView<'_, T>) -> Result<DenseMatrix<T>, Error>
+    where
+        T: Numeric,
+    {
+        if self.cols() != rhs.rows() {
+            return Err(Error::ShapeMismatch {
+                left: self.shape(),
+                right: rhs.shape(),
+            });
+        }
+        let (n, inner, m) = (self.rows(), self.cols(), rhs.cols());
+        let mut product = DenseMatrix::zeros(n, m);
+        for i0 in (0..n).step_by(BLOCK) {
+            for k0 in (0..inner).step_by(BLOCK) {
+                for j0 in (0..m).step_by(BLOCK) {
+                    let j_end = (j0 + BLOCK).min(m);
+                    for i in i0..(i0 + BLOCK).min(n) {
+                        let out = &mut product.data[i * m + j0..i * m + j_end];
+                        for k in k0..(k0 + BLOCK).min(inner) {
+                            let a = self[(i, k)];
+                            for (j, value) in (j0..).zip(out.iter_mut()) {
+                                *value = *value + a * rhs[(k, j)];
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(product)
+    }
+}
+
+impl<T> Clone for MatrixView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MatrixView<'_, T> {}
+
+impl<T> Index<(usize, usize)> for MatrixView<'_, T> {
+    type Output = T;
+
+    /// Get the entry at (row, col), panicking if it is out of bounds
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        match self.get(row, col) {
+            Some(value) => value,
+            None => panic!(
+                "index ({row}, {col}) is out of bounds for a {}×{} view",
+                self.rows(),
+                self.cols()
+            ),
+        }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for MatrixView<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries((0..self.rows()).map(|row| self.row(row).unwrap().iter().collect::<Vec<_>>()))
+            .finish()
+    }
+}
+
+/// A mutably borrowed, possibly strided view of a [`DenseMatrix`]
+pub struct MatrixViewMut<'a, T> {
+    data: &'a mut [T],
+    layout: Layout,
+}
+
+impl<'a, T> MatrixViewMut<'a, T> {
+    /// Get the number of rows
+    pub fn rows(&self) -> usize {
+        self.layout.rows
+    }
+
+    /// Get the number of columns
+    pub fn cols(&self) -> usize {
+        self.layout.cols
+    }
+
+    /// Get the number of rows and columns
+    pub fn shape(&self) -> (usize, usize) {
+        (self.layout.rows, self.layout.cols)
+    }
+
+    /// Get a reference to the entry at (row, col)
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        Some(&self.data[self.layout.index(row, col)?])
+    }
+
+    /// Get a mutable reference to the entry at (row, col)
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        Some(&mut self.data[self.layout.index(row, col)?])
+    }
+
+    /// Borrow as a shared view
+    pub fn as_view(&self) -> MatrixView<'_, T> {
+        MatrixView {
+            data: self.data,
+            layout: self.layout,
+        }
+    }
+
+    /// Borrow as a shorter-lived mutable view, so this one can be narrowed
+    /// and still used afterwards
+    pub fn reborrow(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut {
+            data: self.data,
+            layout: self.layout,
+        }
+    }
+
+    /// Narrow to the block covering `rows` and `cols`
+    pub fn submatrix(self, rows: Range<usize>, cols: Range<usize>) -> Result<Self, Error> {
+        let (layout, start) = self.layout.block(rows, cols)?;
+        Ok(Self {
+            data: &mut self.data[start..],
+            layout,
+        })
+    }
+
+    /// Turn into a view of the transpose
+    pub fn transpose(self) -> Self {
+        Self {
+            data: self.data,
+            layout: self.layout.transpose(),
+        }
+    }
+
+    /// Narrow to one row, as a 1×n matrix
+    pub fn row(self, row: usize) -> Option<Self> {
+        let cols = self.cols();
+        (row < self.rows()).then(|| self.submatrix(row..row + 1, 0..cols).unwrap())
+    }
+
+    /// Narrow to one column, as an n×1 matrix
+    pub fn column(self, col: usize) -> Option<Self> {
+        let rows = self.rows();
+        (col < self.cols()).then(|| self.submatrix(0..rows, col..col + 1).unwrap())
+    }
+
+    /// Set every viewed entry to `value`
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for row in 0..self.rows() {
+            for col in 0..self.cols() {
+                self[(row, col)] = value.clone();
+            }
+        }
+    }
+
+    /// Copy every entry of `source`, which must have the same shape
+    pub fn copy_from(&mut self, source: &MatrixView<'_, T>) -> Result<(), Error>
+    where
+        T: Clone,
+    {
+        if self.shape() != source.shape() {
+            return Err(Error::ShapeMismatch {
+                left: self.shape(),
+                right: source.shape(),
+            });
+        }
+        for row in 0..self.rows() {
+            for col in 0..self.cols() {
+                self[(row, col)] = source[(row, col)].clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> Index<(usize, usize)> for MatrixViewMut<'_, T> {
+    type Output = T;
+
+    /// Get the entry at (row, col), panicking if it is out of bounds
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        match self.get(row, col) {
+            Some(value) => value,
+            None => panic!(
+                "index ({row}, {col}) is out of bounds for a {}×{} view",
+                self.rows(),
+                self.cols()
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for MatrixViewMut<'_, T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let (rows, cols) = self.shape();
+        match self.get_mut(row, col) {
+            Some(value) => value,
+            None => panic!("index ({row}, {col}) is out of bounds for a {rows}×{cols} view"),
+        }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for MatrixViewMut<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_view().fmt(f)
+    }
+}
+
+/// An LU decomposition with partial pivoting, `P·A = L·U`, from
+/// [`DenseMatrix::lu`]
+pub struct Lu<T> {
+    /// L below the diagonal, with its unit diagonal left implicit, and U on
+    /// and above it
+    factors: DenseMatrix<T>,
+    /// Row `i` of `P·A` is row `permutation[i]` of `A`
+    permutation: DynamicArray<usize>,
+    /// Whether `P` swaps an odd number of rows
+    odd: bool,
+}
+
+impl<T: Field> Lu<T> {
+    /// Get the unit lower triangular factor `L`
+    pub fn l(&self) -> DenseMatrix<T> {
+        let n = self.factors.rows();
+        DenseMatrix::from_fn(n, n, |row, col| match row.cmp(&col) {
+            core::cmp::Ordering::Greater => self.factors[(row, col)],
+            core::cmp::Ordering::Equal => T::one(),
+            core::cmp::Ordering::Less => T::zero(),
+        })
+    }
+
+    /// Get the upper triangular factor `U`
+    pub fn u(&self) -> DenseMatrix<T> {
+        let n = self.factors.rows();
+        DenseMatrix::from_fn(n, n, |row, col| {
+            if row <= col {
+                self.factors[(row, col)]
+            } else {
+                T::zero()
+            }
+        })
+    }
+
+    /// Get the row permutation: row `i` of `P·A` is row `permutation()[i]`
+    /// of `A`
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// Get the determinant of `A`, the signed product of `U`'s diagonal.
+    /// O(n).
+    pub fn determinant(&self) -> T {
+        let product =
+            (0..self.factors.rows()).fold(T::one(), |product, i| product * self.factors[(i, i)]);
+        if self.odd {
+            -product
+        } else {
+            product
+        }
+    }
+
+    /// Solve `A·x = b` by forward and back substitution. O(n²), so
+    /// factoring once pays off when solving for many right-hand sides.
+    pub fn solve(&self, b: &[T]) -> Result<DynamicArray<T>, Error> {
+        let n = self.factors.rows();
+        if b.len() != n {
+            return Err(Error::ShapeMismatch {
+                left: self.factors.shape(),
+                right: (b.len(), 1),
+            });
+        }
+        // L·y = P·b
+        let mut x: DynamicArray<T> = self.permutation.iter().map(|&row| b[row]).collect();
+        for i in 0..n {
+            x[i] = (0..i).fold(x[i], |sum, j| sum - self.factors[(i, j)] * x[j]);
+        }
+        // U·x = y
+        for i in (0..n).rev() {
+            let sum = (i + 1..n).fold(x[i], |sum, j| sum - self.factors[(i, j)] * x[j]);
+            x[i] = sum / self.factors[(i, i)];
+        }
+        Ok(x)
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for Lu<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Lu")
+            .field("factors", &self.factors)
+            .field("permutation", &self.permutation.as_slice())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numeric::ModInt;
+
+    type Mod7 = ModInt<7>;
+
+    fn matrix(rows: usize, cols: usize, data: &[i64]) -> DenseMatrix<i64> {
+        DenseMatrix::from_data(rows, cols, DynamicArray::from(data.to_vec())).unwrap()
+    }
+
+    fn floats(rows: usize, cols: usize, data: &[f64]) -> DenseMatrix<f64> {
+        DenseMatrix::from_data(rows, cols, DynamicArray::from(data.to_vec())).unwrap()
+    }
+
+    fn assert_close(a: &DenseMatrix<f64>, b: &DenseMatrix<f64>) {
+        assert_eq!(a.shape(), b.shape());
+        let close = a
+            .as_slice()
+            .iter()
+            .zip(b.as_slice())
+            .all(|(x, y)| (x - y).abs() < 1e-9);
+        assert!(close, "{a:?} != {b:?}");
+    }
+
+    fn naive_matmul(a: &DenseMatrix<i64>, b: &DenseMatrix<i64>) -> DenseMatrix<i64> {
+        DenseMatrix::from_fn(a.rows(), b.cols(), |i, j| {
+            (0..a.cols()).map(|k| a[(i, k)] * b[(k, j)]).sum()
+        })
+    }
+
+    #[test]
+    fn test_construction_and_indexing() {
+        let mut m = DenseMatrix::from_fn(2, 3, |row, col| row * 10 + col);
+        assert_eq!(m.shape(), (2, 3));
+        assert_eq!(m[(1, 2)], 12);
+        assert_eq!(m.get(2, 0), None);
+        assert_eq!(m.get(0, 3), None);
+        assert_eq!(m.row(1), Some(&[10, 11, 12][..]));
+        assert_eq!(m.row(2), None);
+        m[(0, 0)] = 99;
+        m.row_mut(1).unwrap()[0] = 77;
+        assert_eq!(m.as_slice(), [99, 1, 2, 77, 11, 12]);
+        assert_eq!(DenseMatrix::from_elem(2, 2, 'x').as_slice(), ['x'; 4]);
+        assert_eq!(
+            DenseMatrix::from_data(2, 2, DynamicArray::from([1, 2, 3])),
+            Err(Error::ShapeMismatch {
+                left: (2, 2),
+                right: (3, 1)
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "index (2, 0) is out of bounds for a 2×2 matrix")]
+    fn test_index_out_of_bounds() {
+        let m = DenseMatrix::<i32>::zeros(2, 2);
+        let _ = m[(2, 0)];
+    }
+
+    #[test]
+    fn test_reshape() {
+        let mut m = matrix(2, 3, &[1, 2, 3, 4, 5, 6]);
+        m.reshape(3, 2).unwrap();
+        assert_eq!(m.row(2), Some(&[5, 6][..]));
+        assert_eq!(
+            m.reshape(4, 2),
+            Err(Error::ShapeMismatch {
+                left: (3, 2),
+                right: (4, 2)
+            })
+        );
+        m.reshape(6, 1).unwrap();
+        assert_eq!(m[(4, 0)], 5);
+    }
+
+    #[test]
+    fn test_swap_rows() {
+        let mut m = matrix(3, 2, &[1, 2, 3, 4, 5, 6]);
+        m.swap_rows(0, 2).unwrap();
+        assert_eq!(m.as_slice(), [5, 6, 3, 4, 1, 2]);
+        assert_eq!(m.swap_rows(0, 3), Err(Error::out_of_bounds(3, 3)));
+    }
+
+    #[test]
+    fn test_views_do_not_copy() {
+        let m = matrix(3, 4, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+        let block = m.submatrix(1..3, 1..3).unwrap();
+        assert_eq!(block.shape(), (2, 2));
+        assert_eq!(block.iter().copied().collect::<Vec<_>>(), [6, 7, 10, 11]);
+        assert!(std::ptr::eq(&block[(0, 0)], &m[(1, 1)]));
+
+        let transposed = m.transpose_view();
+        assert_eq!(transposed.shape(), (4, 3));
+        assert_eq!(transposed[(3, 1)], 8);
+        assert_eq!(transposed.to_matrix(), m.transpose());
+
+        let column = m.column(2).unwrap();
+        assert_eq!(column.iter().copied().collect::<Vec<_>>(), [3, 7, 11]);
+        let row = m.view().row(1).unwrap();
+        assert_eq!(row.iter().copied().collect::<Vec<_>>(), [5, 6, 7, 8]);
+        assert!(m.column(4).is_none());
+        assert!(m.view().row(3).is_none());
+
+        // Views compose: a block of a transpose is a transposed block.
+        let nested = transposed.submatrix(1..3, 0..2).unwrap();
+        assert_eq!(
+            nested.to_matrix(),
+            m.submatrix(0..2, 1..3).unwrap().transpose().to_matrix()
+        );
+        assert_eq!(format!("{nested:?}"), "[[2, 6], [3, 7]]");
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_view_bounds() {
+        let mut m = matrix(2, 2, &[1, 2, 3, 4]);
+        assert_eq!(
+            m.submatrix(0..3, 0..1).unwrap_err(),
+            Error::out_of_bounds(3, 2)
+        );
+        assert_eq!(
+            m.submatrix(0..1, 1..4).unwrap_err(),
+            Error::out_of_bounds(4, 2)
+        );
+        assert_eq!(
+            m.submatrix(2..1, 0..1).unwrap_err(),
+            Error::out_of_bounds(2, 1)
+        );
+        assert_eq!(
+            m.view_mut().submatrix(0..1, 1..0).unwrap_err(),
+            Error::out_of_bounds(1, 0)
+        );
+        let empty = m.submatrix(2..2, 0..2).unwrap();
+        assert_eq!(empty.shape(), (0, 2));
+        assert_eq!(empty.iter().count(), 0);
+        assert_eq!(empty.get(0, 0), None);
+    }
+
+    #[test]
+    fn test_mutable_views() {
+        let mut m = DenseMatrix::<i32>::zeros(3, 3);
+        m.view_mut().submatrix(0..2, 1..3).unwrap().fill(5);
+        m.view_mut().column(0).unwrap().fill(1);
+        assert_eq!(m.as_slice(), [1, 5, 5, 1, 5, 5, 1, 0, 0]);
+
+        // Copy a row into a column through a transposed view.
+        let source = DenseMatrix::from_data(1, 3, DynamicArray::from([7, 8, 9])).unwrap();
+        let mut view = m.view_mut();
+        view.reborrow()
+            .transpose()
+            .row(2)
+            .unwrap()
+            .copy_from(&source.view())
+            .unwrap();
+        view[(0, 0)] = -1;
+        assert_eq!(m.as_slice(), [-1, 5, 7, 1, 5, 8, 1, 0, 9]);
+
+        let mut view = m.view_mut();
+        assert_eq!(
+            view.copy_from(&source.view()),
+            Err(Error::ShapeMismatch {
+                left: (3, 3),
+                right: (1, 3)
+            })
+        );
+    }
+
+    #[test]
+    fn test_matmul_matches_naive_across_blocks() {
+        let a = DenseMatrix::from_fn(70, 45, |i, j| ((i * 7 + j * 3) % 11) as i64 - 5);
+        let b = DenseMatrix::from_fn(45, 33, |i, j| ((i * 5 + j * 2) % 13) as i64 - 6);
+        let product = a.matmul(&b).unwrap();
+        assert_eq!(product.shape(), (70, 33));
+        assert_eq!(product, naive_matmul(&a, &b));
+    }
+
+    #[test]
+    fn test_matmul_with_views() {
+        let a = matrix(2, 3, &[1, 2, 3, 4, 5, 6]);
+        // Aᵀ·A through a transposed view, without materializing Aᵀ.
+        let gram = a.transpose_view().matmul(&a.view()).unwrap();
+        assert_eq!(gram, naive_matmul(&a.transpose(), &a));
+        assert_eq!(
+            a.matmul(&a),
+            Err(Error::ShapeMismatch {
+                left: (2, 3),
+                right: (2, 3)
+            })
+        );
+        let identity = DenseMatrix::identity(3);
+        assert_eq!(a.matmul(&identity).unwrap(), a);
+    }
+
+    #[test]
+    fn test_lu() {
+        let a = floats(3, 3, &[2.0, 1.0, 1.0, 4.0, -6.0, 0.0, -2.0, 7.0, 2.0]);
+        let lu = a.lu().unwrap();
+        // Partial pivoting moves the largest first-column entry up.
+        assert_eq!(lu.permutation()[0], 1);
+        let permuted = DenseMatrix::from_fn(3, 3, |row, col| a[(lu.permutation()[row], col)]);
+        assert_close(&lu.l().matmul(&lu.u()).unwrap(), &permuted);
+        assert!((lu.determinant() - -16.0).abs() < 1e-9);
+
+        let x = lu.solve(&[5.0, -2.0, 9.0]).unwrap();
+        let expected = [1.0, 1.0, 2.0];
+        assert!(
+            x.iter().zip(expected).all(|(x, e)| (x - e).abs() < 1e-9),
+            "{x:?}"
+        );
+    }
+
+    #[test]
+    fn test_lu_errors() {
+        assert_eq!(
+            floats(2, 3, &[0.0; 6]).lu().unwrap_err(),
+            Error::NotSquare { rows: 2, cols: 3 }
+        );
+        let singular = floats(2, 2, &[1.0, 2.0, 2.0, 4.0]);
+        assert_eq!(singular.lu().unwrap_err(), Error::SingularMatrix);
+        assert_eq!(singular.determinant(), Ok(0.0));
+        let lu = DenseMatrix::<f64>::identity(2).lu().unwrap();
+        assert_eq!(
+            lu.solve(&[1.0]).unwrap_err(),
+            Error::ShapeMismatch {
+                left: (2, 2),
+                right: (1, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_gaussian_elimination() {
+        // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3
+        let a = floats(3, 3, &[2.0, 1.0, -1.0, -3.0, -1.0, 2.0, -2.0, 1.0, 2.0]);
+        let x = a.solve(&[8.0, -11.0, -3.0]).unwrap();
+        let expected = [2.0, 3.0, -1.0];
+        assert!(
+            x.iter().zip(expected).all(|(x, e)| (x - e).abs() < 1e-9),
+            "{x:?}"
+        );
+        assert_eq!(
+            floats(2, 2, &[1.0, 1.0, 2.0, 2.0]).solve(&[1.0, 2.0]),
+            Err(Error::SingularMatrix)
+        );
+        assert!((a.determinant().unwrap() - -1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_exact_arithmetic_mod_p() {
+        let entries = [0, 1, 2, 1, 0, 3, 4, 3, 1].map(Mod7::new);
+        let a = DenseMatrix::from_data(3, 3, DynamicArray::from(entries)).unwrap();
+        let b = [Mod7::new(1), Mod7::new(2), Mod7::new(3)];
+        let x = a.solve(&b).unwrap();
+        let lu_x = a.lu().unwrap().solve(&b).unwrap();
+        assert_eq!(x, lu_x);
+        let column = DenseMatrix::from_data(3, 1, x).unwrap();
+        assert_eq!(a.matmul(&column).unwrap().as_slice(), b);
+    }
+
+    #[test]
+    fn test_rank() {
+        assert_eq!(
+            floats(3, 3, &[1.0, 2.0, 3.0, 2.0, 4.0, 6.0, 1.0, 1.0, 1.0]).rank(),
+            2
+        );
+        let counting = DenseMatrix::from_fn(3, 3, |row, col| Mod7::new((row * 3 + col + 1) as u64));
+        assert_eq!(counting.rank(), 2);
+        assert_eq!(
+            floats(2, 4, &[1.0, 0.0, 2.0, 0.0, 2.0, 0.0, 4.0, 0.0]).rank(),
+            1
+        );
+        assert_eq!(DenseMatrix::<f64>::identity(4).rank(), 4);
+        assert_eq!(DenseMatrix::<f64>::zeros(3, 2).rank(), 0);
+        assert_eq!(floats(3, 2, &[0.0, 1.0, 0.0, 2.0, 1.0, 0.0]).rank(), 2);
+    }
+
+    #[test]
+    fn test_dp_table() {
+        // Longest common subsequence, the kind of 2D table DP code builds.
+        let (a, b) = (b"AGGTAB", b"GXTXAYB");
+        let mut table = DenseMatrix::from_elem(a.len() + 1, b.len() + 1, 0usize);
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                table[(i, j)] = if a[i - 1] == b[j - 1] {
+                    table[(i - 1, j - 1)] + 1
+                } else {
+                    table[(i - 1, j)].max(table[(i, j - 1)])
+                };
+            }
+        }
+        assert_eq!(table[(a.len(), b.len())], 4);
+    }
+}
@@ -0,0 +1,1309 @@
+// Doubly Linked List implementation in Rust
+
+use crate::allocator::{self, Allocator, Global};
+use crate::error::Error;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// A link to a neighbouring node
+type Link<T> = Option<NonNull<Node<T>>>;
+
+/// A node in the doubly linked list
+struct Node<T> {
+    value: T,
+    prev: Link<T>,
+    next: Link<T>,
+}
+
+impl<T> Node<T> {
+    /// Allocate a detached node from `alloc`
+    fn alloc<A: Allocator>(alloc: &A, value: T) -> NonNull<Node<T>> {
+        allocator::alloc_value(
+            alloc,
+            Node {
+                value,
+                prev: None,
+                next: None,
+            },
+        )
+    }
+
+    /// Free a node that has been unlinked, returning its value
+    ///
+    /// # Safety
+    ///
+    /// `node` must come from [`Node::alloc`] with `alloc` or an allocator
+    /// it is the same as, and no link may still point at it.
+    unsafe fn free<A: Allocator>(alloc: &A, node: NonNull<Node<T>>) -> T {
+        allocator::free_value(alloc, node).value
+    }
+}
+
+/// A doubly linked list with O(1) insertion and removal at both ends.
+///
+/// Every node links to both neighbours, so cursors can move in either
+/// direction and whole lists can be spliced in or split off at a cursor in
+/// O(1). The list owns every node reachable from `head`; `tail` points at the
+/// last of them. Nodes are allocated from `A`, the global heap by default.
+pub struct DoublyLinkedList<T, A: Allocator = Global> {
+    head: Link<T>,
+    tail: Link<T>,
+    length: usize,
+    alloc: A,
+    _marker: PhantomData<Node<T>>,
+}
+
+// SAFETY: the list owns its nodes like a `Box<Node<T>>` chain would.
+unsafe impl<T: Send, A: Allocator + Send> Send for DoublyLinkedList<T, A> {}
+// SAFETY: shared access only hands out `&T`.
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for DoublyLinkedList<T, A> {}
+
+impl<T, A: Allocator + Default> Default for DoublyLinkedList<T, A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<T> DoublyLinkedList<T> {
+    /// Create a new empty doubly linked list
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A: Allocator> DoublyLinkedList<T, A> {
+    /// Create a new empty doubly linked list whose nodes come from `alloc`
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            head: None,
+            tail: None,
+            length: 0,
+            alloc,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the allocator the nodes come from
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Get the length of the doubly linked list
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Check if the doubly linked list is empty
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Insert at head in O(1)
+    pub fn insert_at_head(&mut self, value: T) {
+        let node = Node::alloc(&self.alloc, value);
+        // SAFETY: `node` is fresh and `head` is a live node of this list.
+        unsafe { self.link_between(node, None, self.head) };
+    }
+
+    /// Insert at tail in O(1)
+    pub fn insert_at_tail(&mut self, value: T) {
+        let node = Node::alloc(&self.alloc, value);
+        // SAFETY: `node` is fresh and `tail` is a live node of this list.
+        unsafe { self.link_between(node, self.tail, None) };
+    }
+
+    /// Remove from head in O(1)
+    pub fn remove_from_head(&mut self) -> Option<T> {
+        let head = self.head?;
+        // SAFETY: `head` is a live node of this list.
+        Some(unsafe { self.unlink(head) })
+    }
+
+    /// Remove from tail in O(1)
+    pub fn remove_from_tail(&mut self) -> Option<T> {
+        let tail = self.tail?;
+        // SAFETY: `tail` is a live node of this list.
+        Some(unsafe { self.unlink(tail) })
+    }
+
+    /// Get a reference to the first value
+    pub fn peek_front(&self) -> Option<&T> {
+        // SAFETY: the node is live for as long as `self` is borrowed.
+        self.head.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Get a mutable reference to the first value
+    pub fn peek_front_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is live and uniquely borrowed through `self`.
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Get a reference to the last value
+    pub fn peek_back(&self) -> Option<&T> {
+        // SAFETY: the node is live for as long as `self` is borrowed.
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Get a mutable reference to the last value
+    pub fn peek_back_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is live and uniquely borrowed through `self`.
+        self.tail.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Get a reference to the value at index, walking from the nearer end
+    pub fn get(&self, index: usize) -> Result<&T, Error> {
+        match self.node_at(index) {
+            // SAFETY: the node is live for as long as `self` is borrowed.
+            Some(node) => Ok(unsafe { &(*node.as_ptr()).value }),
+            None => Err(Error::out_of_bounds(index, self.length)),
+        }
+    }
+
+    /// Get a mutable reference to the value at index, walking from the
+    /// nearer end
+    pub fn get_mut(&mut self, index: usize) -> Result<&mut T, Error> {
+        match self.node_at(index) {
+            // SAFETY: the node is live and uniquely borrowed through `self`.
+            Some(node) => Ok(unsafe { &mut (*node.as_ptr()).value }),
+            None => Err(Error::out_of_bounds(index, self.length)),
+        }
+    }
+
+    /// Check if the doubly linked list contains a value
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|candidate| candidate == value)
+    }
+
+    /// Move every value of `other` to the end of this list, in O(1) when
+    /// both lists use the same allocator
+    pub fn append(&mut self, other: &mut Self) {
+        let (Some(first), Some(last), length) = other.take_nodes_for(&self.alloc) else {
+            return;
+        };
+        // SAFETY: the chain taken from `other` belongs to this list's
+        // allocator and is linked in after its tail, which is live if there
+        // is one.
+        unsafe {
+            (*first.as_ptr()).prev = self.tail;
+            match self.tail {
+                Some(tail) => (*tail.as_ptr()).next = Some(first),
+                None => self.head = Some(first),
+            }
+        }
+        self.tail = Some(last);
+        self.length += length;
+    }
+
+    /// Split the list in two at `at`, returning the values from `at` on
+    pub fn split_off(&mut self, at: usize) -> Result<Self, Error>
+    where
+        A: Clone,
+    {
+        if at > self.length {
+            return Err(Error::out_of_bounds(at, self.length));
+        }
+        if at == self.length {
+            return Ok(Self::new_in(self.alloc.clone()));
+        }
+        let mut cursor = self.cursor_at_mut(at).expect("index is in bounds");
+        let mut front = cursor.split_before();
+        core::mem::swap(self, &mut front);
+        Ok(front)
+    }
+
+    /// Remove every value from the doubly linked list
+    pub fn clear(&mut self) {
+        while self.remove_from_head().is_some() {}
+    }
+
+    /// Iterate over the values from head to tail
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.head,
+            back: self.tail,
+            remaining: self.length,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Iterate mutably over the values from head to tail
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.head,
+            back: self.tail,
+            remaining: self.length,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get a cursor positioned on the first value
+    pub fn cursor_front(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self,
+            current: self.head,
+            index: 0,
+        }
+    }
+
+    /// Get a cursor positioned on the last value
+    pub fn cursor_back(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self,
+            current: self.tail,
+            index: self.length.saturating_sub(1),
+        }
+    }
+
+    /// Get a mutable cursor positioned on the first value
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            current: self.head,
+            index: 0,
+            list: self,
+        }
+    }
+
+    /// Get a mutable cursor positioned on the last value
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            current: self.tail,
+            index: self.length.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    /// Get a mutable cursor positioned on the value at index
+    pub fn cursor_at_mut(&mut self, index: usize) -> Result<CursorMut<'_, T, A>, Error> {
+        match self.node_at(index) {
+            Some(node) => Ok(CursorMut {
+                current: Some(node),
+                index,
+                list: self,
+            }),
+            None => Err(Error::out_of_bounds(index, self.length)),
+        }
+    }
+
+    /// Walk to the node at `index` from whichever end is nearer
+    fn node_at(&self, index: usize) -> Link<T> {
+        if index >= self.length {
+            return None;
+        }
+        if index < self.length / 2 {
+            let mut current = self.head;
+            for _ in 0..index {
+                // SAFETY: every node before `index` is live and has a successor.
+                current = unsafe { (*current?.as_ptr()).next };
+            }
+            current
+        } else {
+            let mut current = self.tail;
+            for _ in index + 1..self.length {
+                // SAFETY: every node after `index` is live and has a predecessor.
+                current = unsafe { (*current?.as_ptr()).prev };
+            }
+            current
+        }
+    }
+
+    /// Link a detached node between two adjacent positions
+    ///
+    /// # Safety
+    ///
+    /// `node` must be detached and owned by the caller. `prev` and `next`
+    /// must be adjacent nodes of this list, with `None` standing for the
+    /// list's ends.
+    unsafe fn link_between(&mut self, node: NonNull<Node<T>>, prev: Link<T>, next: Link<T>) {
+        (*node.as_ptr()).prev = prev;
+        (*node.as_ptr()).next = next;
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = Some(node),
+            None => self.head = Some(node),
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = Some(node),
+            None => self.tail = Some(node),
+        }
+        self.length += 1;
+    }
+
+    /// Unlink and free a node, returning its value
+    ///
+    /// # Safety
+    ///
+    /// `node` must be a live node of this list.
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> T {
+        let Node { prev, next, .. } = *node.as_ptr();
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = prev,
+            None => self.tail = prev,
+        }
+        self.length -= 1;
+        Node::free(&self.alloc, node)
+    }
+
+    /// Give up ownership of the nodes, returning `(head, tail, length)` of a
+    /// chain that `alloc` can free. The nodes are moved into new ones from
+    /// `alloc` unless it is the same as the list's allocator.
+    fn take_nodes_for(&mut self, alloc: &A) -> (Link<T>, Link<T>, usize) {
+        if self.alloc.same_as(alloc) {
+            let parts = (self.head.take(), self.tail.take(), self.length);
+            self.length = 0;
+            return parts;
+        }
+        let (mut head, mut tail, mut length): (Link<T>, Link<T>, _) = (None, None, 0);
+        while let Some(value) = self.remove_from_head() {
+            let node = Node::alloc(alloc, value);
+            // SAFETY: `node` is new and `tail` is the live end of the chain
+            // built so far.
+            unsafe {
+                (*node.as_ptr()).prev = tail;
+                match tail {
+                    Some(tail) => (*tail.as_ptr()).next = Some(node),
+                    None => head = Some(node),
+                }
+            }
+            tail = Some(node);
+            length += 1;
+        }
+        (head, tail, length)
+    }
+}
+
+impl<T, A: Allocator> Drop for DoublyLinkedList<T, A> {
+    fn drop(&mut self) {
+        // Free nodes one at a time so long lists don't drop recursively.
+        self.clear();
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for DoublyLinkedList<T, A> {
+    fn clone(&self) -> Self {
+        let mut list = Self::new_in(self.alloc.clone());
+        list.extend(self.iter().cloned());
+        list
+    }
+}
+
+impl<T: core::fmt::Debug, A: Allocator> core::fmt::Debug for DoublyLinkedList<T, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq for DoublyLinkedList<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for DoublyLinkedList<T, A> {}
+
+impl<T: core::hash::Hash, A: Allocator> core::hash::Hash for DoublyLinkedList<T, A> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(self.length);
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
+impl<T, A: Allocator + Default> FromIterator<T> for DoublyLinkedList<T, A> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::default();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for DoublyLinkedList<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert_at_tail(value);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator> Extend<&'a T> for DoublyLinkedList<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for DoublyLinkedList<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a DoublyLinkedList<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut DoublyLinkedList<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// A read-only cursor over a [`DoublyLinkedList`].
+///
+/// The cursor rests either on a value or on the "ghost" position between
+/// the tail and the head, where [`current`](Cursor::current) returns `None`.
+pub struct Cursor<'a, T, A: Allocator = Global> {
+    list: &'a DoublyLinkedList<T, A>,
+    current: Link<T>,
+    index: usize,
+}
+
+impl<'a, T, A: Allocator> Cursor<'a, T, A> {
+    /// Get the index of the current value, or `None` on the ghost position
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Get the current value
+    pub fn current(&self) -> Option<&'a T> {
+        // SAFETY: the list is borrowed for `'a`, so the node stays live.
+        self.current.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Get the value after the current one without moving
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            // SAFETY: the list is borrowed for `'a`, so the node stays live.
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+        // SAFETY: as above.
+        next.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Get the value before the current one without moving
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let prev = match self.current {
+            // SAFETY: the list is borrowed for `'a`, so the node stays live.
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        };
+        // SAFETY: as above.
+        prev.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Move towards the tail, passing through the ghost position
+    pub fn move_next(&mut self) {
+        let (current, index) = step_next(self.list, self.current, self.index);
+        self.current = current;
+        self.index = index;
+    }
+
+    /// Move towards the head, passing through the ghost position
+    pub fn move_prev(&mut self) {
+        let (current, index) = step_prev(self.list, self.current, self.index);
+        self.current = current;
+        self.index = index;
+    }
+}
+
+impl<T, A: Allocator> Clone for Cursor<'_, T, A> {
+    fn clone(&self) -> Self {
+        Self {
+            list: self.list,
+            current: self.current,
+            index: self.index,
+        }
+    }
+}
+
+/// A cursor over a [`DoublyLinkedList`] that can edit the list around it.
+///
+/// Every edit is O(1), including splicing in a whole list and splitting the
+/// list in two at the cursor.
+pub struct CursorMut<'a, T, A: Allocator = Global> {
+    list: &'a mut DoublyLinkedList<T, A>,
+    current: Link<T>,
+    /// Index of `current`, or the list length on the ghost position
+    index: usize,
+}
+
+impl<T, A: Allocator> CursorMut<'_, T, A> {
+    /// Get the index of the current value, or `None` on the ghost position
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Get the current value
+    pub fn current(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is live and uniquely borrowed through the cursor.
+        self.current
+            .map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Get the value after the current one without moving
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = self.next_node();
+        // SAFETY: the node is live and uniquely borrowed through the cursor.
+        next.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Get the value before the current one without moving
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = self.prev_node();
+        // SAFETY: the node is live and uniquely borrowed through the cursor.
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Move towards the tail, passing through the ghost position
+    pub fn move_next(&mut self) {
+        let (current, index) = step_next(self.list, self.current, self.index);
+        self.current = current;
+        self.index = index;
+    }
+
+    /// Move towards the head, passing through the ghost position
+    pub fn move_prev(&mut self) {
+        let (current, index) = step_prev(self.list, self.current, self.index);
+        self.current = current;
+        self.index = index;
+    }
+
+    /// Get a read-only cursor at the same position
+    pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self.list,
+            current: self.current,
+            index: self.index,
+        }
+    }
+
+    /// Insert a value after the current one. On the ghost position this
+    /// prepends to the list.
+    pub fn insert_after(&mut self, value: T) {
+        let node = Node::alloc(&self.list.alloc, value);
+        let next = self.next_node();
+        // SAFETY: `current` and `next` are adjacent (the ghost stands for the
+        // ends) and `node` is fresh.
+        unsafe { self.list.link_between(node, self.current, next) };
+        if self.current.is_none() {
+            self.index += 1;
+        }
+    }
+
+    /// Insert a value before the current one. On the ghost position this
+    /// appends to the list.
+    pub fn insert_before(&mut self, value: T) {
+        let node = Node::alloc(&self.list.alloc, value);
+        let prev = self.prev_node();
+        // SAFETY: `prev` and `current` are adjacent (the ghost stands for the
+        // ends) and `node` is fresh.
+        unsafe { self.list.link_between(node, prev, self.current) };
+        self.index += 1;
+    }
+
+    /// Remove the current value, moving the cursor to the next one.
+    ///
+    /// Returns `None`, leaving the list untouched, on the ghost position.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        // SAFETY: `node` is a live node of the list and the cursor moves off
+        // it before it is freed.
+        unsafe {
+            self.current = (*node.as_ptr()).next;
+            Some(self.list.unlink(node))
+        }
+    }
+
+    /// Move every value of `other` in after the current one, in O(1) when
+    /// both lists use the same allocator. On the ghost position they go to
+    /// the front of the list.
+    pub fn splice_after(&mut self, other: DoublyLinkedList<T, A>) {
+        let next = self.next_node();
+        let inserted = other.len();
+        // SAFETY: `current` and `next` are adjacent and the spliced nodes now
+        // belong to this list.
+        unsafe { self.splice_between(other, self.current, next) };
+        if self.current.is_none() {
+            self.index += inserted;
+        }
+    }
+
+    /// Move every value of `other` in before the current one, in O(1) when
+    /// both lists use the same allocator. On the ghost position they go to
+    /// the back of the list.
+    pub fn splice_before(&mut self, other: DoublyLinkedList<T, A>) {
+        let prev = self.prev_node();
+        let inserted = other.len();
+        // SAFETY: `prev` and `current` are adjacent and the spliced nodes now
+        // belong to this list.
+        unsafe { self.splice_between(other, prev, self.current) };
+        self.index += inserted;
+    }
+
+    /// Split off every value after the current one into a new list in O(1).
+    /// On the ghost position the whole list is moved.
+    pub fn split_after(&mut self) -> DoublyLinkedList<T, A>
+    where
+        A: Clone,
+    {
+        let Some(current) = self.current else {
+            return self.take_list();
+        };
+        let moved = self.list.length - self.index - 1;
+        // SAFETY: `current` is a live node of the list; the chain after it
+        // is detached and handed to the new list together with its length.
+        unsafe {
+            let Some(first) = (*current.as_ptr()).next.take() else {
+                return DoublyLinkedList::new_in(self.list.alloc.clone());
+            };
+            (*first.as_ptr()).prev = None;
+            let last = self.list.tail.replace(current);
+            self.list.length -= moved;
+            DoublyLinkedList {
+                head: Some(first),
+                tail: last,
+                length: moved,
+                alloc: self.list.alloc.clone(),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// Split off every value before the current one into a new list in
+    /// O(1). On the ghost position the whole list is moved.
+    pub fn split_before(&mut self) -> DoublyLinkedList<T, A>
+    where
+        A: Clone,
+    {
+        let Some(current) = self.current else {
+            self.index = 0;
+            return self.take_list();
+        };
+        let moved = self.index;
+        // SAFETY: `current` is a live node of the list; the chain before it
+        // is detached and handed to the new list together with its length.
+        unsafe {
+            let Some(last) = (*current.as_ptr()).prev.take() else {
+                return DoublyLinkedList::new_in(self.list.alloc.clone());
+            };
+            (*last.as_ptr()).next = None;
+            let first = self.list.head.replace(current);
+            self.list.length -= moved;
+            self.index = 0;
+            DoublyLinkedList {
+                head: first,
+                tail: Some(last),
+                length: moved,
+                alloc: self.list.alloc.clone(),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// Move the whole list out, leaving an empty one with the same allocator
+    fn take_list(&mut self) -> DoublyLinkedList<T, A>
+    where
+        A: Clone,
+    {
+        let empty = DoublyLinkedList::new_in(self.list.alloc.clone());
+        core::mem::replace(self.list, empty)
+    }
+
+    fn next_node(&self) -> Link<T> {
+        match self.current {
+            // SAFETY: the node is live while the list is borrowed.
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        }
+    }
+
+    fn prev_node(&self) -> Link<T> {
+        match self.current {
+            // SAFETY: the node is live while the list is borrowed.
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        }
+    }
+
+    /// Link all of `other`'s nodes between two adjacent positions
+    ///
+    /// # Safety
+    ///
+    /// `prev` and `next` must be adjacent nodes of the cursor's list, with
+    /// `None` standing for the list's ends.
+    unsafe fn splice_between(
+        &mut self,
+        mut other: DoublyLinkedList<T, A>,
+        prev: Link<T>,
+        next: Link<T>,
+    ) {
+        let (Some(first), Some(last), length) = other.take_nodes_for(&self.list.alloc) else {
+            return;
+        };
+        (*first.as_ptr()).prev = prev;
+        (*last.as_ptr()).next = next;
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = Some(first),
+            None => self.list.head = Some(first),
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = Some(last),
+            None => self.list.tail = Some(last),
+        }
+        self.list.length += length;
+    }
+}
+
+/// Step a cursor position towards the tail
+fn step_next<T, A: Allocator>(
+    list: &DoublyLinkedList<T, A>,
+    current: Link<T>,
+    index: usize,
+) -> (Link<T>, usize) {
+    match current {
+        // SAFETY: the node is live while the list is borrowed.
+        Some(node) => (unsafe { (*node.as_ptr()).next }, index + 1),
+        None => (list.head, 0),
+    }
+}
+
+/// Step a cursor position towards the head
+fn step_prev<T, A: Allocator>(
+    list: &DoublyLinkedList<T, A>,
+    current: Link<T>,
+    index: usize,
+) -> (Link<T>, usize) {
+    match current {
+        // SAFETY: the node is live while the list is borrowed.
+        Some(node) => match unsafe { (*node.as_ptr()).prev } {
+            Some(prev) => (Some(prev), index - 1),
+            None => (None, list.length),
+        },
+        None => (list.tail, list.length.saturating_sub(1)),
+    }
+}
+
+/// A borrowing iterator over the values of a [`DoublyLinkedList`]
+pub struct Iter<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    remaining: usize,
+    _marker: PhantomData<&'a Node<T>>,
+}
+
+// SAFETY: the iterator only hands out `&T`, like `&DoublyLinkedList<T>`.
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+// SAFETY: as above.
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // SAFETY: `remaining > 0`, so `front` is a live node of the borrowed
+        // list that hasn't been yielded from either end.
+        let node = unsafe { &*self.front?.as_ptr() };
+        self.front = node.next;
+        self.remaining -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // SAFETY: `remaining > 0`, so `back` is a live node of the borrowed
+        // list that hasn't been yielded from either end.
+        let node = unsafe { &*self.back?.as_ptr() };
+        self.back = node.prev;
+        self.remaining -= 1;
+        Some(&node.value)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> core::iter::FusedIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            front: self.front,
+            back: self.back,
+            remaining: self.remaining,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A mutable iterator over the values of a [`DoublyLinkedList`]
+pub struct IterMut<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    remaining: usize,
+    _marker: PhantomData<&'a mut Node<T>>,
+}
+
+// SAFETY: the iterator hands out `&mut T`, like `&mut DoublyLinkedList<T>`.
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+// SAFETY: shared access to the iterator gives no access to the values.
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // SAFETY: `remaining > 0`, so `front` is a live node that hasn't
+        // been yielded from either end; each node is yielded once.
+        let node = unsafe { &mut *self.front?.as_ptr() };
+        self.front = node.next;
+        self.remaining -= 1;
+        Some(&mut node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // SAFETY: `remaining > 0`, so `back` is a live node that hasn't been
+        // yielded from either end; each node is yielded once.
+        let node = unsafe { &mut *self.back?.as_ptr() };
+        self.back = node.prev;
+        self.remaining -= 1;
+        Some(&mut node.value)
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> core::iter::FusedIterator for IterMut<'_, T> {}
+
+/// An owning iterator over the values of a [`DoublyLinkedList`]
+pub struct IntoIter<T, A: Allocator = Global> {
+    list: DoublyLinkedList<T, A>,
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.remove_from_head()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.length, Some(self.list.length))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.remove_from_tail()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> core::iter::FusedIterator for IntoIter<T, A> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::{Bump, Counting};
+
+    fn list_of(values: &[i32]) -> DoublyLinkedList<i32> {
+        values.iter().copied().collect()
+    }
+
+    /// Collect the values both forwards and backwards, checking the `prev`
+    /// links agree with the `next` links
+    fn to_vec<A: Allocator>(list: &DoublyLinkedList<i32, A>) -> Vec<i32> {
+        let forward: Vec<i32> = list.iter().copied().collect();
+        let mut backward: Vec<i32> = list.iter().rev().copied().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(forward.len(), list.len());
+        forward
+    }
+
+    // Every test here goes through the raw `prev`/`next` links and is meant
+    // to be run under Miri as well: `cargo +nightly miri test`.
+
+    #[test]
+    fn test_new() {
+        let list: DoublyLinkedList<i32> = DoublyLinkedList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.peek_front(), None);
+        assert_eq!(list.peek_back(), None);
+    }
+
+    #[test]
+    fn test_insert_and_remove_at_both_ends() {
+        let mut list = DoublyLinkedList::new();
+        list.insert_at_head(2);
+        list.insert_at_tail(3);
+        list.insert_at_head(1);
+        assert_eq!(to_vec(&list), [1, 2, 3]);
+        assert_eq!(list.remove_from_tail(), Some(3));
+        assert_eq!(list.remove_from_head(), Some(1));
+        assert_eq!(list.remove_from_tail(), Some(2));
+        assert_eq!(list.remove_from_head(), None);
+        assert_eq!(list.remove_from_tail(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut list = list_of(&[1, 2, 3]);
+        assert_eq!(list.peek_front(), Some(&1));
+        assert_eq!(list.peek_back(), Some(&3));
+        *list.peek_front_mut().unwrap() = 10;
+        *list.peek_back_mut().unwrap() = 30;
+        assert_eq!(to_vec(&list), [10, 2, 30]);
+    }
+
+    #[test]
+    fn test_get() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        assert_eq!(list.get(0), Ok(&1));
+        assert_eq!(list.get(4), Ok(&5));
+        assert_eq!(list.get(5), Err(Error::out_of_bounds(5, 5)));
+        *list.get_mut(3).unwrap() = 40;
+        assert_eq!(list.get(3), Ok(&40));
+        assert!(list.contains(&40));
+        assert!(!list.contains(&4));
+    }
+
+    #[test]
+    fn test_iter_both_ways() {
+        let mut list = list_of(&[1, 2, 3, 4]);
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+        for value in list.iter_mut().rev() {
+            *value *= 10;
+        }
+        assert_eq!(to_vec(&list), [10, 20, 30, 40]);
+        let mut owned = list.into_iter();
+        assert_eq!(owned.next_back(), Some(40));
+        assert_eq!(owned.collect::<Vec<_>>(), [10, 20, 30]);
+    }
+
+    #[test]
+    fn test_traits() {
+        let list = list_of(&[1, 2, 3]);
+        let mut cloned = list.clone();
+        assert_eq!(list, cloned);
+        cloned.extend(&[4]);
+        assert_ne!(list, cloned);
+        assert_eq!(format!("{list:?}"), "[1, 2, 3]");
+        let default: DoublyLinkedList<i32> = Default::default();
+        assert!(default.is_empty());
+    }
+
+    #[test]
+    fn test_cursor_moves_both_ways() {
+        let list = list_of(&[1, 2, 3]);
+        let mut cursor = list.cursor_back();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&3));
+        assert_eq!(cursor.peek_prev(), Some(&2));
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&1));
+        assert_eq!(cursor.peek_prev(), Some(&3));
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&3));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&2));
+        assert_eq!(cursor.index(), Some(1));
+    }
+
+    #[test]
+    fn test_cursor_insert() {
+        let mut list = list_of(&[2, 4]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.insert_before(1);
+        assert_eq!(cursor.index(), Some(1));
+        cursor.insert_after(3);
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        cursor.insert_before(5);
+        cursor.insert_after(0);
+        assert_eq!(cursor.as_cursor().peek_prev(), Some(&5));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(5));
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_cursor_remove_current() {
+        let mut list = list_of(&[1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert!(list.is_empty());
+        assert_eq!(list.peek_back(), None);
+    }
+
+    #[test]
+    fn test_cursor_splice() {
+        let mut list = list_of(&[1, 5]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.splice_after(list_of(&[2, 3, 4]));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.splice_before(list_of(&[]));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.splice_before(list_of(&[6, 7]));
+        cursor.splice_after(list_of(&[-1, 0]));
+        assert_eq!(cursor.as_cursor().peek_next(), Some(&-1));
+        assert_eq!(cursor.as_cursor().peek_prev(), Some(&7));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(8));
+        assert_eq!(to_vec(&list), [-1, 0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_cursor_split() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        let mut cursor = list.cursor_at_mut(2).unwrap();
+        let after = cursor.split_after();
+        assert_eq!(cursor.index(), Some(2));
+        let before = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(to_vec(&list), [3]);
+        assert_eq!(to_vec(&before), [1, 2]);
+        assert_eq!(to_vec(&after), [4, 5]);
+
+        let mut cursor = list.cursor_front_mut();
+        assert!(cursor.split_before().is_empty());
+        assert!(cursor.split_after().is_empty());
+        cursor.move_next();
+        let everything = cursor.split_after();
+        assert_eq!(to_vec(&everything), [3]);
+        assert!(list.is_empty());
+        assert!(list_of(&[]).cursor_at_mut(0).is_err());
+    }
+
+    #[test]
+    fn test_append_and_split_off() {
+        let mut list = list_of(&[1, 2]);
+        let mut other = list_of(&[3, 4, 5]);
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(to_vec(&list), [1, 2, 3, 4, 5]);
+        let tail = list.split_off(3).unwrap();
+        assert_eq!(to_vec(&list), [1, 2, 3]);
+        assert_eq!(to_vec(&tail), [4, 5]);
+        assert!(list.split_off(3).unwrap().is_empty());
+        let all = list.split_off(0).unwrap();
+        assert!(list.is_empty());
+        assert_eq!(to_vec(&all), [1, 2, 3]);
+        assert!(list.split_off(1).is_err());
+    }
+
+    #[test]
+    fn test_lru_order() {
+        // Move an accessed key to the front, evict from the back.
+        let mut lru = list_of(&[1, 2, 3, 4]);
+        let mut cursor = lru.cursor_at_mut(2).unwrap();
+        let key = cursor.remove_current().unwrap();
+        lru.insert_at_head(key);
+        assert_eq!(lru.remove_from_tail(), Some(4));
+        assert_eq!(to_vec(&lru), [3, 1, 2]);
+    }
+
+    #[test]
+    fn test_drop_long_list() {
+        std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(|| {
+                let list: DoublyLinkedList<usize> = (0..1_000_000).collect();
+                let cloned = list.clone();
+                assert!(cloned == list);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_owned_values_are_dropped() {
+        use std::rc::Rc;
+
+        let tracker = Rc::new(());
+        let mut list = DoublyLinkedList::new();
+        for _ in 0..6 {
+            list.insert_at_tail(Rc::clone(&tracker));
+        }
+        let tail = list.split_off(4).unwrap();
+        let mut cursor = list.cursor_front_mut();
+        cursor.remove_current();
+        assert_eq!(Rc::strong_count(&tracker), 6);
+        drop(list);
+        drop(tail);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn test_allocation_stats() {
+        let counting = Counting::new(Global);
+        let mut list = DoublyLinkedList::new_in(&counting);
+        list.extend(0..6);
+        let node_size = core::mem::size_of::<Node<i32>>();
+        assert_eq!(counting.stats().allocations, 6);
+        assert_eq!(counting.stats().bytes_in_use, 6 * node_size);
+
+        // Splitting and splicing only relink nodes
+        let mut tail = list.split_off(3).unwrap();
+        list.cursor_front_mut()
+            .splice_after(DoublyLinkedList::new_in(&counting));
+        list.append(&mut tail);
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(counting.stats().allocations, 6);
+
+        let mut cursor = list.cursor_back_mut();
+        cursor.insert_before(9);
+        cursor.remove_current();
+        assert_eq!(counting.stats().allocations, 7);
+        assert_eq!(counting.stats().deallocations, 1);
+        drop(list);
+        assert_eq!(counting.stats().live(), 0);
+        assert_eq!(counting.stats().bytes_in_use, 0);
+    }
+
+    #[test]
+    fn test_owned_counting_allocator() {
+        let mut list = DoublyLinkedList::new_in(Counting::new(Global));
+        list.insert_at_head(1);
+        list.insert_at_tail(2);
+        assert_eq!(list.remove_from_head(), Some(1));
+        let stats = list.allocator().stats();
+        assert_eq!((stats.allocations, stats.live()), (2, 1));
+    }
+
+    #[test]
+    fn test_bump_allocator() {
+        let bump = Bump::new();
+        let mut list = DoublyLinkedList::new_in(&bump);
+        list.extend((0..500).map(|i| i.to_string()));
+        let mut back = list.split_off(250).unwrap();
+        back.cursor_front_mut().splice_before(list.clone());
+        assert_eq!(back.len(), 500);
+        assert_eq!(back.iter().nth(250).map(String::as_str), Some("250"));
+    }
+
+    #[test]
+    fn test_append_across_allocators() {
+        let first = Bump::new();
+        let second = Bump::new();
+        let mut list = DoublyLinkedList::new_in(&first);
+        let mut other = DoublyLinkedList::new_in(&second);
+        list.extend((0..100).map(|i| i.to_string()));
+        other.extend((100..200).map(|i| i.to_string()));
+        list.append(&mut other);
+        drop(other);
+        assert_eq!(list.len(), 200);
+        assert_eq!(list.peek_back().map(String::as_str), Some("199"));
+        list.cursor_back_mut()
+            .splice_after(DoublyLinkedList::new_in(&second));
+        list.clear();
+
+        let (own, moved) = (Counting::new(Global), Counting::new(Global));
+        let mut list = DoublyLinkedList::new_in(&own);
+        let mut other = DoublyLinkedList::new_in(&moved);
+        list.extend([1, 2]);
+        other.extend([3, 4, 5]);
+        list.append(&mut other);
+        drop(other);
+        let stats = moved.stats();
+        assert_eq!((stats.allocations, stats.deallocations), (3, 3));
+        assert_eq!(stats.bytes_in_use, 0);
+        assert_eq!(to_vec(&list), [1, 2, 3, 4, 5]);
+
+        for _ in 0..3 {
+            list.remove_from_tail();
+        }
+        assert_eq!(own.stats().live(), 2);
+        let mut spliced = DoublyLinkedList::new_in(&moved);
+        spliced.extend([6, 7]);
+        list.cursor_front_mut().splice_after(spliced);
+        assert_eq!(to_vec(&list), [1, 6, 7, 2]);
+        assert_eq!(moved.stats().live(), 0);
+        drop(list);
+        let stats = own.stats();
+        assert_eq!((stats.allocations, stats.deallocations), (7, 7));
+        assert_eq!(stats.bytes_in_use, 0);
+    }
+
+    #[test]
+    fn test_append_same_allocator() {
+        let counter = Counting::new(Global);
+        let mut list = DoublyLinkedList::new_in(&counter);
+        let mut other = DoublyLinkedList::new_in(&counter);
+        list.extend([1, 2]);
+        other.extend([3, 4, 5]);
+        list.append(&mut other);
+        assert_eq!(to_vec(&list), [1, 2, 3, 4, 5]);
+        let stats = counter.stats();
+        assert_eq!((stats.allocations, stats.deallocations), (5, 0));
+    }
+}
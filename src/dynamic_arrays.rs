@@ -1,136 +1,1246 @@
-/// A dynamic array implementation using Vec
-pub struct DynamicArray<T> {
-    data: Vec<T>,
+use crate::allocator::{AllocError, Allocator, Global};
+use crate::error::Error;
+use alloc::alloc::handle_alloc_error;
+use alloc::vec::Vec;
+use core::alloc::Layout;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ops::{Bound, Deref, DerefMut, Index, IndexMut, RangeBounds};
+use core::ptr::{self, NonNull};
+use core::slice::SliceIndex;
+
+/// Create a [`DynamicArray`] from a list of elements, like `vec!`
+///
+/// ```
+/// use dsa_rs::dsa_vec;
+///
+/// let arr = dsa_vec![1, 2, 3];
+/// assert_eq!(arr, [1, 2, 3]);
+/// let zeros = dsa_vec![0u8; 4];
+/// assert_eq!(zeros.len(), 4);
+/// ```
+#[macro_export]
+macro_rules! dsa_vec {
+    () => {
+        $crate::dynamic_arrays::DynamicArray::new()
+    };
+    ($elem:expr; $n:expr) => {
+        <$crate::dynamic_arrays::DynamicArray<_> as ::core::iter::FromIterator<_>>::from_iter(
+            ::core::iter::repeat_n($elem, $n),
+        )
+    };
+    ($($x:expr),+ $(,)?) => {
+        $crate::dynamic_arrays::DynamicArray::from([$($x),+])
+    };
+}
+
+/// Factor the capacity is multiplied by under the [`Doubling`] policy.
+///
+/// Doubling keeps `push` amortized O(1): pushing n elements reallocates at
+/// most log2(n) times and moves fewer than 2n elements in total.
+const GROWTH_FACTOR: usize = 2;
+
+/// Capacity of the first allocation made by a push into an empty array
+const MIN_NON_ZERO_CAP: usize = 4;
+
+/// Decides how far a [`DynamicArray`] grows when it runs out of room
+pub trait GrowthPolicy {
+    /// Return the new capacity for a buffer of `capacity` slots that must
+    /// hold at least `required` elements.
+    ///
+    /// Results smaller than `required` are raised to `required`.
+    fn grow(&self, capacity: usize, required: usize) -> usize;
+}
+
+/// Double the capacity on every reallocation (the default policy)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Doubling;
+
+impl GrowthPolicy for Doubling {
+    fn grow(&self, capacity: usize, _required: usize) -> usize {
+        capacity.saturating_mul(GROWTH_FACTOR).max(MIN_NON_ZERO_CAP)
+    }
+}
+
+/// Grow the capacity by half of itself, trading more reallocations for less
+/// unused memory
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OneAndAHalf;
+
+impl GrowthPolicy for OneAndAHalf {
+    fn grow(&self, capacity: usize, _required: usize) -> usize {
+        capacity.saturating_add(capacity / 2).max(MIN_NON_ZERO_CAP)
+    }
+}
+
+/// Grow the capacity by a fixed number of slots.
+///
+/// Memory overhead is bounded by the increment, but pushes become O(n)
+/// amortized, so this suits arrays whose final size is roughly known.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedIncrement(pub usize);
+
+impl GrowthPolicy for FixedIncrement {
+    fn grow(&self, capacity: usize, _required: usize) -> usize {
+        capacity.saturating_add(self.0)
+    }
+}
+
+/// Any `Fn(capacity, required) -> new_capacity` closure is a growth policy
+impl<F: Fn(usize, usize) -> usize> GrowthPolicy for F {
+    fn grow(&self, capacity: usize, required: usize) -> usize {
+        self(capacity, required)
+    }
+}
+
+/// Gives capacity back once an array has become sparsely populated.
+///
+/// After a `pop` or `remove`, if `len * divisor <= capacity` the capacity is
+/// halved, but never below `min_capacity` or the current length. A divisor of
+/// four (the default) leaves room for the array to grow again before the next
+/// reallocation, so alternating pushes and pops can't thrash the allocator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShrinkPolicy {
+    /// Shrink once the array is at most `1 / divisor` full
+    pub divisor: usize,
+    /// Capacity below which the array is never shrunk
+    pub min_capacity: usize,
+}
+
+impl Default for ShrinkPolicy {
+    fn default() -> Self {
+        Self {
+            divisor: 4,
+            min_capacity: MIN_NON_ZERO_CAP,
+        }
+    }
+}
+
+/// How many times a [`DynamicArray`] has moved to a differently sized buffer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReallocStats {
+    /// Reallocations that increased the capacity
+    pub grows: usize,
+    /// Reallocations that decreased the capacity
+    pub shrinks: usize,
+}
+
+impl ReallocStats {
+    /// Total number of reallocations
+    pub fn reallocations(&self) -> usize {
+        self.grows + self.shrinks
+    }
+}
+
+/// An owned buffer of `cap` possibly uninitialized slots from allocator `A`.
+///
+/// The buffer only manages memory: it never reads, writes or drops elements.
+/// Zero-sized types never allocate and report a capacity of `usize::MAX`.
+struct RawBuffer<T, A: Allocator = Global> {
+    ptr: NonNull<T>,
+    cap: usize,
+    alloc: A,
+    _marker: PhantomData<T>,
+}
+
+impl<T, A: Allocator> RawBuffer<T, A> {
+    const IS_ZST: bool = mem::size_of::<T>() == 0;
+
+    fn new_in(alloc: A) -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            cap: if Self::IS_ZST { usize::MAX } else { 0 },
+            alloc,
+            _marker: PhantomData,
+        }
+    }
+
+    fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        let mut buf = Self::new_in(alloc);
+        if capacity > buf.cap {
+            buf.resize(capacity);
+        }
+        buf
+    }
+
+    fn layout(cap: usize) -> Layout {
+        Layout::array::<T>(cap).expect("capacity overflow")
+    }
+
+    /// Reallocate the buffer to exactly `new_cap` slots.
+    ///
+    /// Callers must ensure no initialized element lives at or past `new_cap`.
+    fn resize(&mut self, new_cap: usize) {
+        if Self::IS_ZST || new_cap == self.cap {
+            return;
+        }
+        if new_cap == 0 {
+            self.release();
+            return;
+        }
+        let new_layout = Self::layout(new_cap);
+        let block = if self.cap == 0 {
+            self.alloc.allocate(new_layout)
+        } else {
+            let old_ptr = self.ptr.cast::<u8>();
+            let old_layout = Self::layout(self.cap);
+            // SAFETY: `ptr` was allocated by `alloc` with the layout for
+            // `cap` slots, and the new layout is larger or smaller as called.
+            unsafe {
+                if new_cap > self.cap {
+                    self.alloc.grow(old_ptr, old_layout, new_layout)
+                } else {
+                    self.alloc.shrink(old_ptr, old_layout, new_layout)
+                }
+            }
+        };
+        self.ptr = match block {
+            Ok(block) => block.cast::<T>(),
+            Err(AllocError) => handle_alloc_error(new_layout),
+        };
+        self.cap = new_cap;
+    }
+
+    /// Free the allocation, leaving an empty buffer behind
+    fn release(&mut self) {
+        if !Self::IS_ZST && self.cap != 0 {
+            // SAFETY: `ptr` was allocated by `alloc` with the layout for `cap`
+            // slots.
+            unsafe {
+                self.alloc
+                    .deallocate(self.ptr.cast::<u8>(), Self::layout(self.cap))
+            };
+        }
+        self.ptr = NonNull::dangling();
+        self.cap = if Self::IS_ZST { usize::MAX } else { 0 };
+    }
+}
+
+impl<T, A: Allocator> Drop for RawBuffer<T, A> {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// A dynamic array backed by its own allocation.
+///
+/// Elements live in a single contiguous buffer; the first `len` slots are
+/// initialized. When a push finds the buffer full, the growth policy `G`
+/// picks the new capacity; the default [`Doubling`] policy keeps pushes
+/// amortized O(1). The buffer comes from the allocator `A`, which is the
+/// global heap unless the array is built with one of the `_in` constructors.
+pub struct DynamicArray<T, G = Doubling, A: Allocator = Global> {
+    buf: RawBuffer<T, A>,
+    len: usize,
+    growth: G,
+    shrink: Option<ShrinkPolicy>,
+    stats: ReallocStats,
 }
 
+// SAFETY: `DynamicArray<T>` owns its elements just like `Vec<T>` does.
+unsafe impl<T: Send, G: Send, A: Allocator + Send> Send for DynamicArray<T, G, A> {}
+// SAFETY: shared access only hands out `&T` and `&G`.
+unsafe impl<T: Sync, G: Sync, A: Allocator + Sync> Sync for DynamicArray<T, G, A> {}
+
 impl<T> DynamicArray<T> {
     /// Create a new empty dynamic array
     pub fn new() -> Self {
-        Self { data: Vec::new() }
+        Self::with_growth(Doubling)
     }
 
     /// Create a new dynamic array with a given capacity
     pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_growth(capacity, Doubling)
+    }
+}
+
+impl<T, G: GrowthPolicy> DynamicArray<T, G> {
+    /// Create a new empty dynamic array that grows according to `growth`
+    pub fn with_growth(growth: G) -> Self {
+        Self::with_capacity_and_growth(0, growth)
+    }
+
+    /// Create a new dynamic array with a given capacity and growth policy
+    pub fn with_capacity_and_growth(capacity: usize, growth: G) -> Self {
+        Self::with_capacity_and_growth_in(capacity, growth, Global)
+    }
+}
+
+impl<T, A: Allocator> DynamicArray<T, Doubling, A> {
+    /// Create a new empty dynamic array that allocates from `alloc`
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    /// Create a new dynamic array with a given capacity, allocated from
+    /// `alloc`
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::with_capacity_and_growth_in(capacity, Doubling, alloc)
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> DynamicArray<T, G, A> {
+    /// Create a new dynamic array with a given capacity and growth policy,
+    /// allocated from `alloc`
+    pub fn with_capacity_and_growth_in(capacity: usize, growth: G, alloc: A) -> Self {
         Self {
-            data: Vec::with_capacity(capacity),
+            buf: RawBuffer::with_capacity_in(capacity, alloc),
+            len: 0,
+            growth,
+            shrink: None,
+            stats: ReallocStats::default(),
         }
     }
 
+    /// Get the allocator the buffer comes from
+    pub fn allocator(&self) -> &A {
+        &self.buf.alloc
+    }
+
+    /// Get the growth policy
+    pub fn growth_policy(&self) -> &G {
+        &self.growth
+    }
+
+    /// Get the shrink policy, if any
+    pub fn shrink_policy(&self) -> Option<ShrinkPolicy> {
+        self.shrink
+    }
+
+    /// Set or clear the policy applied after `pop` and `remove`
+    pub fn set_shrink_policy(&mut self, policy: Option<ShrinkPolicy>) {
+        self.shrink = policy;
+    }
+
+    /// Get the number of reallocations performed so far
+    pub fn realloc_stats(&self) -> ReallocStats {
+        self.stats
+    }
+
     /// Get the length of the dynamic array
     pub fn len(&self) -> usize {
-        self.data.len()
+        self.len
     }
 
     /// Check if the dynamic array is empty
     pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+        self.len == 0
     }
 
     /// Get the capacity of the dynamic array
     pub fn capacity(&self) -> usize {
-        self.data.capacity()
+        self.buf.cap
+    }
+
+    /// View the initialized elements as a slice
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `len` slots are initialized.
+        unsafe { core::slice::from_raw_parts(self.buf.ptr.as_ptr(), self.len) }
+    }
+
+    /// View the initialized elements as a mutable slice
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first `len` slots are initialized.
+        unsafe { core::slice::from_raw_parts_mut(self.buf.ptr.as_ptr(), self.len) }
     }
 
     /// Push an element to the end
     pub fn push(&mut self, value: T) {
-        self.data.push(value);
+        if self.len == self.capacity() {
+            self.grow_for(1);
+        }
+        // SAFETY: `len < cap`, so the slot is in bounds and uninitialized.
+        unsafe { self.buf.ptr.as_ptr().add(self.len).write(value) };
+        self.len += 1;
     }
 
     /// Pop an element from the end
     pub fn pop(&mut self) -> Option<T> {
-        self.data.pop()
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: the slot at the old last index is initialized and is no
+        // longer counted by `len`, so it is read exactly once.
+        let value = unsafe { self.buf.ptr.as_ptr().add(self.len).read() };
+        self.apply_shrink_policy();
+        Some(value)
     }
 
     /// Get a reference to the element at the given index
     pub fn get(&self, index: usize) -> Option<&T> {
-        self.data.get(index)
+        self.as_slice().get(index)
     }
 
     /// Get a mutable reference to the element at the given index
     pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
-        self.data.get_mut(index)
+        self.as_mut_slice().get_mut(index)
     }
 
     /// Set the element at the given index
-    pub fn set(&mut self, index: usize, value: T) -> Result<(), &'static str> {
-        if index < self.len() {
-            self.data[index] = value;
-            Ok(())
-        } else {
-            Err("Index out of bounds")
+    pub fn set(&mut self, index: usize, value: T) -> Result<(), Error> {
+        match self.get_mut(index) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(Error::out_of_bounds(index, self.len)),
         }
     }
 
     /// Insert an element at the given index
-    pub fn insert(&mut self, index: usize, value: T) -> Result<(), &'static str> {
-        if index <= self.len() {
-            self.data.insert(index, value);
-            Ok(())
-        } else {
-            Err("Index out of bounds")
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), Error> {
+        if index > self.len {
+            return Err(Error::out_of_bounds(index, self.len));
+        }
+        if self.len == self.capacity() {
+            self.grow_for(1);
         }
+        // SAFETY: `index <= len < cap`; the tail is shifted one slot to the
+        // right within the allocation before the gap is filled.
+        unsafe {
+            let slot = self.buf.ptr.as_ptr().add(index);
+            ptr::copy(slot, slot.add(1), self.len - index);
+            slot.write(value);
+        }
+        self.len += 1;
+        Ok(())
     }
 
     /// Remove an element at the given index
-    pub fn remove(&mut self, index: usize) -> Result<T, &'static str> {
-        if index < self.len() {
-            Ok(self.data.remove(index))
-        } else {
-            Err("Index out of bounds")
+    pub fn remove(&mut self, index: usize) -> Result<T, Error> {
+        if index >= self.len {
+            return Err(Error::out_of_bounds(index, self.len));
+        }
+        // SAFETY: `index < len`; the element is read out before the tail is
+        // shifted left over its slot, so it is moved exactly once.
+        unsafe {
+            let slot = self.buf.ptr.as_ptr().add(index);
+            let value = slot.read();
+            ptr::copy(slot.add(1), slot, self.len - index - 1);
+            self.len -= 1;
+            self.apply_shrink_policy();
+            Ok(value)
         }
     }
 
     /// Clear the dynamic array
     pub fn clear(&mut self) {
-        self.data.clear();
+        self.truncate(0);
+    }
+
+    /// Drop every element past the first `len`, keeping the capacity
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = ptr::slice_from_raw_parts_mut(
+            // SAFETY: `len < self.len`, so the offset is in bounds.
+            unsafe { self.buf.ptr.as_ptr().add(len) },
+            self.len - len,
+        );
+        // Forget the elements first so a panicking destructor can't lead to
+        // them being dropped a second time.
+        self.len = len;
+        // SAFETY: the elements were initialized and are no longer reachable.
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    /// Remove an element at the given index in O(1) by moving the last
+    /// element into its place
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, Error> {
+        if index >= self.len {
+            return Err(Error::out_of_bounds(index, self.len));
+        }
+        let last = self.len - 1;
+        // SAFETY: both indices are in bounds; the removed element is read
+        // out before the last one is moved over it.
+        let value = unsafe {
+            let base = self.buf.ptr.as_ptr();
+            let value = base.add(index).read();
+            ptr::copy(base.add(last), base.add(index), 1);
+            value
+        };
+        self.len = last;
+        self.apply_shrink_policy();
+        Ok(value)
+    }
+
+    /// Move every element of `other` to the end of this array, leaving
+    /// `other` empty
+    pub fn append<H: GrowthPolicy, B: Allocator>(&mut self, other: &mut DynamicArray<T, H, B>) {
+        let count = other.len;
+        self.reserve(count);
+        // SAFETY: the elements are moved bit-for-bit into reserved space and
+        // `other` forgets them, so each is still owned exactly once.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                other.buf.ptr.as_ptr(),
+                self.buf.ptr.as_ptr().add(self.len),
+                count,
+            );
+        }
+        other.len = 0;
+        self.len += count;
+    }
+
+    /// Remove the elements in `range`, returning them as an iterator.
+    ///
+    /// The elements after the range are moved down once, when the iterator
+    /// is dropped. Elements the iterator did not yield are dropped then too.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past the end.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, G, A> {
+        let (start, end) = slice_range(range, self.len);
+        let tail_len = self.len - end;
+        // Until the drain is dropped only the prefix is considered
+        // initialized, so leaking the drain can't cause a double drop.
+        self.len = start;
+        Drain {
+            arr: self,
+            next: start,
+            end,
+            tail_start: end,
+            tail_len,
+        }
+    }
+
+    /// Replace the elements in `range` with the items of `replace_with`,
+    /// returning the removed elements.
+    ///
+    /// Unlike `Vec::splice`, the edit happens eagerly: `replace_with` is
+    /// collected first, then the tail is moved exactly once to its final
+    /// position. A panic inside `replace_with` leaves the array untouched.
+    /// Both the replacement and the removed elements are buffered in memory
+    /// from a clone of the array's allocator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past the end.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> IntoIter<T, A>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+        A: Clone,
+    {
+        let (start, end) = slice_range(range, self.len);
+        let mut replacement = DynamicArray::new_in(self.buf.alloc.clone());
+        replacement.extend(replace_with);
+        let removed_len = end - start;
+        let mut removed = DynamicArray::with_capacity_in(removed_len, self.buf.alloc.clone());
+        let new_len = self.len - removed_len + replacement.len;
+        if new_len > self.capacity() {
+            self.grow_for(new_len - self.len);
+        }
+        // SAFETY: all ranges are in bounds of their buffers. The removed
+        // elements move to `removed`, the tail moves once to make room, and
+        // the replacement moves into the gap; `replacement` forgets its
+        // elements so each one is owned exactly once.
+        unsafe {
+            let base = self.buf.ptr.as_ptr();
+            ptr::copy_nonoverlapping(base.add(start), removed.buf.ptr.as_ptr(), removed_len);
+            removed.len = removed_len;
+            ptr::copy(
+                base.add(end),
+                base.add(start + replacement.len),
+                self.len - end,
+            );
+            ptr::copy_nonoverlapping(
+                replacement.buf.ptr.as_ptr(),
+                base.add(start),
+                replacement.len,
+            );
+            replacement.len = 0;
+        }
+        self.len = new_len;
+        removed.into_iter()
+    }
+
+    /// Keep only the elements for which `keep` returns `true`
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut keep: F) {
+        self.retain_mut(|value| keep(value));
+    }
+
+    /// Keep only the elements for which `keep` returns `true`, letting the
+    /// predicate modify them.
+    ///
+    /// Kept elements are shifted down as the array is scanned, so the whole
+    /// operation is a single pass. If `keep` panics, the elements not yet
+    /// visited are kept and nothing is dropped twice.
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, keep: F) {
+        // SAFETY: the first `len` slots of the buffer are initialized.
+        unsafe { retain_raw(self.buf.ptr.as_ptr(), &mut self.len, keep) };
+    }
+
+    /// Remove consecutive elements that map to the same key
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Remove consecutive elements for which `same_bucket(current, previous)`
+    /// returns `true`, in a single pass
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, same_bucket: F) {
+        // SAFETY: the first `len` slots of the buffer are initialized.
+        unsafe { dedup_raw(self.buf.ptr.as_ptr(), &mut self.len, same_bucket) };
+    }
+
+    /// Remove consecutive equal elements
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Split the array in two at `at`, returning the elements from `at` on
+    pub fn split_off(&mut self, at: usize) -> Result<Self, Error>
+    where
+        G: Clone,
+        A: Clone,
+    {
+        if at > self.len {
+            return Err(Error::out_of_bounds(at, self.len));
+        }
+        let count = self.len - at;
+        let mut other =
+            Self::with_capacity_and_growth_in(count, self.growth.clone(), self.buf.alloc.clone());
+        other.shrink = self.shrink;
+        // SAFETY: the tail moves bit-for-bit into the new buffer and this
+        // array forgets it, so each element is owned exactly once.
+        unsafe {
+            ptr::copy_nonoverlapping(self.buf.ptr.as_ptr().add(at), other.buf.ptr.as_ptr(), count);
+        }
+        self.len = at;
+        other.len = count;
+        Ok(other)
+    }
+
+    /// Clone every element of `other` onto the end of the array
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(other.len());
+        for value in other {
+            // `push` bumps the length one element at a time, so a panicking
+            // `clone` leaves every element pushed so far owned by the array.
+            self.push(value.clone());
+        }
     }
 
     /// Iterate over the dynamic array
-    pub fn iter(&self) -> std::slice::Iter<T> {
-        self.data.iter()
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.as_slice().iter()
     }
 
     /// Iterate mutably over the dynamic array
-    pub fn iter_mut(&mut self) -> std::slice::IterMut<T> {
-        self.data.iter_mut()
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
     }
 
     /// Shrink the capacity to fit the current length
     pub fn shrink_to_fit(&mut self) {
-        self.data.shrink_to_fit();
+        self.resize_buffer(self.len);
     }
 
     /// Reserve additional capacity
     pub fn reserve(&mut self, additional: usize) {
-        self.data.reserve(additional);
+        if self.capacity() - self.len < additional {
+            self.grow_for(additional);
+        }
+    }
+
+    /// Grow the buffer so that at least `additional` more elements fit
+    fn grow_for(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        let new_cap = self.growth.grow(self.capacity(), required).max(required);
+        self.resize_buffer(new_cap);
+    }
+
+    /// Halve the capacity if the shrink policy says the array is too sparse
+    fn apply_shrink_policy(&mut self) {
+        let Some(policy) = self.shrink else {
+            return;
+        };
+        let cap = self.capacity();
+        if cap > policy.min_capacity && self.len.saturating_mul(policy.divisor) <= cap {
+            self.resize_buffer((cap / 2).max(policy.min_capacity).max(self.len));
+        }
+    }
+
+    /// Move the elements into a buffer of `new_cap` slots, counting the move
+    fn resize_buffer(&mut self, new_cap: usize) {
+        debug_assert!(new_cap >= self.len);
+        let old_cap = self.capacity();
+        self.buf.resize(new_cap);
+        let new_cap = self.capacity();
+        if new_cap > old_cap {
+            self.stats.grows += 1;
+        } else if new_cap < old_cap {
+            self.stats.shrinks += 1;
+        }
     }
 }
 
-impl<T: Clone> Clone for DynamicArray<T> {
-    fn clone(&self) -> Self {
+/// Resolve `range` against a sequence of length `len`, panicking like slice
+/// indexing does when it is out of bounds
+pub(crate) fn slice_range<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).expect("range start overflows"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).expect("range end overflows"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end, "range starts at {start} but ends at {end}");
+    assert!(
+        end <= len,
+        "range end {end} is out of bounds for length {len}"
+    );
+    (start, end)
+}
+
+/// Keep the first `*len` elements at `base` for which `keep` returns `true`,
+/// shifting the kept ones down in a single pass
+///
+/// # Safety
+///
+/// The first `*len` slots at `base` must be initialized and owned through
+/// `len`.
+pub(crate) unsafe fn retain_raw<T, F: FnMut(&mut T) -> bool>(
+    base: *mut T,
+    len: &mut usize,
+    mut keep: F,
+) {
+    let mut guard = Compactor::new(base, len, 0);
+    while !guard.is_done() {
+        // SAFETY: `read` is initialized and has not been visited yet.
+        let current = unsafe { &mut *guard.base.add(guard.read) };
+        if keep(current) {
+            guard.keep_current();
+        } else {
+            guard.remove_current();
+        }
+    }
+}
+
+/// Remove consecutive elements among the first `*len` at `base` for which
+/// `same_bucket(current, previous)` returns `true`, in a single pass
+///
+/// # Safety
+///
+/// The first `*len` slots at `base` must be initialized and owned through
+/// `len`.
+pub(crate) unsafe fn dedup_raw<T, F: FnMut(&mut T, &mut T) -> bool>(
+    base: *mut T,
+    len: &mut usize,
+    mut same_bucket: F,
+) {
+    if *len <= 1 {
+        return;
+    }
+    let mut guard = Compactor::new(base, len, 1);
+    while !guard.is_done() {
+        // SAFETY: `read` is initialized and unvisited, and `write - 1` is the
+        // last kept element; they never alias since `write <= read`.
+        let (current, previous) = unsafe {
+            (
+                &mut *guard.base.add(guard.read),
+                &mut *guard.base.add(guard.write - 1),
+            )
+        };
+        if same_bucket(current, previous) {
+            guard.remove_current();
+        } else {
+            guard.keep_current();
+        }
+    }
+}
+
+/// Moves kept elements down over removed ones during a single-pass filter.
+///
+/// Slots below `write` hold kept elements and slots from `read` on are still
+/// unvisited. On drop, including during a panic, the unvisited elements are
+/// moved down behind the kept ones and the array length is restored.
+struct Compactor<'a, T> {
+    base: *mut T,
+    len: &'a mut usize,
+    original_len: usize,
+    read: usize,
+    write: usize,
+}
+
+impl<'a, T> Compactor<'a, T> {
+    /// Start compacting, treating the first `skip` elements as already kept.
+    ///
+    /// The array length is zeroed until the guard is dropped.
+    fn new(base: *mut T, len: &'a mut usize, skip: usize) -> Self {
+        let original_len = mem::replace(len, 0);
         Self {
-            data: self.data.clone(),
+            base,
+            len,
+            original_len,
+            read: skip,
+            write: skip,
+        }
+    }
+
+    /// Check whether every element has been visited
+    fn is_done(&self) -> bool {
+        self.read == self.original_len
+    }
+
+    /// Keep the element at `read`, moving it down to `write`
+    fn keep_current(&mut self) {
+        if self.read != self.write {
+            // SAFETY: `write < read`, both are in bounds, and the slot at
+            // `write` held an element that was already dropped or moved.
+            unsafe {
+                ptr::copy_nonoverlapping(self.base.add(self.read), self.base.add(self.write), 1)
+            };
+        }
+        self.read += 1;
+        self.write += 1;
+    }
+
+    /// Drop the element at `read`
+    fn remove_current(&mut self) {
+        let current = self.read;
+        // Count the element as consumed first so that a panicking destructor
+        // can't make the guard move it again.
+        self.read += 1;
+        // SAFETY: the slot is initialized and is never touched again.
+        unsafe { ptr::drop_in_place(self.base.add(current)) };
+    }
+}
+
+impl<T> Drop for Compactor<'_, T> {
+    fn drop(&mut self) {
+        let unvisited = self.original_len - self.read;
+        if unvisited > 0 && self.read != self.write {
+            // SAFETY: the unvisited elements are initialized and are moved
+            // into the gap left by removed ones.
+            unsafe {
+                ptr::copy(
+                    self.base.add(self.read),
+                    self.base.add(self.write),
+                    unvisited,
+                )
+            };
+        }
+        *self.len = self.write + unvisited;
+    }
+}
+
+/// A draining iterator over a range of a [`DynamicArray`]
+pub struct Drain<'a, T, G: GrowthPolicy = Doubling, A: Allocator = Global> {
+    arr: &'a mut DynamicArray<T, G, A>,
+    next: usize,
+    end: usize,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> Drain<'_, T, G, A> {
+    /// View the drained elements that have not been yielded yet
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the slots in `next..end` are initialized and unread.
+        unsafe {
+            core::slice::from_raw_parts(
+                self.arr.buf.ptr.as_ptr().add(self.next),
+                self.end - self.next,
+            )
+        }
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> Iterator for Drain<'_, T, G, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.next == self.end {
+            return None;
+        }
+        // SAFETY: the slot is initialized and `next` moves past it, so it is
+        // read exactly once.
+        let value = unsafe { self.arr.buf.ptr.as_ptr().add(self.next).read() };
+        self.next += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> DoubleEndedIterator for Drain<'_, T, G, A> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.next == self.end {
+            return None;
+        }
+        self.end -= 1;
+        // SAFETY: the slot is initialized and now lies past `end`, so it is
+        // read exactly once.
+        Some(unsafe { self.arr.buf.ptr.as_ptr().add(self.end).read() })
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> ExactSizeIterator for Drain<'_, T, G, A> {}
+
+impl<T, G: GrowthPolicy, A: Allocator> Drop for Drain<'_, T, G, A> {
+    fn drop(&mut self) {
+        /// Moves the tail back into place even if an element's destructor
+        /// panics
+        struct MoveTail<'r, 'a, T, G: GrowthPolicy, A: Allocator>(&'r mut Drain<'a, T, G, A>);
+
+        impl<T, G: GrowthPolicy, A: Allocator> Drop for MoveTail<'_, '_, T, G, A> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let arr = &mut *drain.arr;
+                // SAFETY: the tail lies past the drained range and is moved
+                // down to directly follow the kept prefix.
+                unsafe {
+                    let base = arr.buf.ptr.as_ptr();
+                    ptr::copy(
+                        base.add(drain.tail_start),
+                        base.add(arr.len),
+                        drain.tail_len,
+                    );
+                }
+                arr.len += drain.tail_len;
+            }
         }
+
+        let remaining = ptr::slice_from_raw_parts_mut(
+            // SAFETY: `next <= end <= cap`, so the offset is in bounds.
+            unsafe { self.arr.buf.ptr.as_ptr().add(self.next) },
+            self.end - self.next,
+        );
+        self.next = self.end;
+        let guard = MoveTail(self);
+        // SAFETY: the remaining drained elements are initialized and were
+        // never yielded.
+        unsafe { ptr::drop_in_place(remaining) };
+        drop(guard);
     }
 }
 
-impl<T: std::fmt::Debug> std::fmt::Debug for DynamicArray<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T, G, A: Allocator> Drop for DynamicArray<T, G, A> {
+    fn drop(&mut self) {
+        let elements = ptr::slice_from_raw_parts_mut(self.buf.ptr.as_ptr(), self.len);
+        // SAFETY: the first `len` slots are initialized. Dropping a slice in
+        // place keeps going past a panicking element, and `buf` frees the
+        // allocation afterwards either way.
+        unsafe { ptr::drop_in_place(elements) };
+    }
+}
+
+impl<T: Clone, G: GrowthPolicy + Clone, A: Allocator + Clone> Clone for DynamicArray<T, G, A> {
+    fn clone(&self) -> Self {
+        let mut cloned = Self::with_capacity_and_growth_in(
+            self.len,
+            self.growth.clone(),
+            self.buf.alloc.clone(),
+        );
+        cloned.shrink = self.shrink;
+        for value in self.iter() {
+            cloned.push(value.clone());
+        }
+        cloned
+    }
+}
+
+impl<T: core::fmt::Debug, G: GrowthPolicy, A: Allocator> core::fmt::Debug
+    for DynamicArray<T, G, A>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("DynamicArray")
-            .field("data", &self.data)
+            .field("data", &self.as_slice())
             .finish()
     }
 }
 
-impl<T> Default for DynamicArray<T> {
+impl<T, G: GrowthPolicy + Default, A: Allocator + Default> Default for DynamicArray<T, G, A> {
     fn default() -> Self {
-        Self::new()
+        Self::with_capacity_and_growth_in(0, G::default(), A::default())
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> Deref for DynamicArray<T, G, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> DerefMut for DynamicArray<T, G, A> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> AsRef<[T]> for DynamicArray<T, G, A> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> AsMut<[T]> for DynamicArray<T, G, A> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator, I: SliceIndex<[T]>> Index<I> for DynamicArray<T, G, A> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        &self.as_slice()[index]
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator, I: SliceIndex<[T]>> IndexMut<I> for DynamicArray<T, G, A> {
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.as_mut_slice()[index]
+    }
+}
+
+impl<T, G: GrowthPolicy + Default, A: Allocator + Default> FromIterator<T>
+    for DynamicArray<T, G, A>
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut arr = Self::default();
+        arr.extend(iter);
+        arr
+    }
+}
+
+impl<T, G: GrowthPolicy, A: Allocator> Extend<T> for DynamicArray<T, G, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, G: GrowthPolicy, A: Allocator> Extend<&'a T> for DynamicArray<T, G, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T> From<Vec<T>> for DynamicArray<T> {
+    fn from(vec: Vec<T>) -> Self {
+        let mut vec = ManuallyDrop::new(vec);
+        let mut arr = Self::with_capacity(vec.len());
+        // SAFETY: the elements are moved bit-for-bit into the new buffer and
+        // the vector's length is zeroed so it only frees its allocation.
+        unsafe {
+            ptr::copy_nonoverlapping(vec.as_ptr(), arr.buf.ptr.as_ptr(), vec.len());
+            arr.len = vec.len();
+            vec.set_len(0);
+            ManuallyDrop::drop(&mut vec);
+        }
+        arr
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for DynamicArray<T> {
+    fn from(array: [T; N]) -> Self {
+        let mut arr = Self::with_capacity(N);
+        arr.extend(array);
+        arr
+    }
+}
+
+impl<T, U, G, H, A, B> PartialEq<DynamicArray<U, H, B>> for DynamicArray<T, G, A>
+where
+    T: PartialEq<U>,
+    G: GrowthPolicy,
+    H: GrowthPolicy,
+    A: Allocator,
+    B: Allocator,
+{
+    fn eq(&self, other: &DynamicArray<U, H, B>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: PartialEq<U>, U, G: GrowthPolicy, A: Allocator> PartialEq<[U]> for DynamicArray<T, G, A> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq<U>, U, G: GrowthPolicy, A: Allocator, const N: usize> PartialEq<[U; N]>
+    for DynamicArray<T, G, A>
+{
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: Eq, G: GrowthPolicy, A: Allocator> Eq for DynamicArray<T, G, A> {}
+
+impl<T: PartialOrd, G: GrowthPolicy, A: Allocator> PartialOrd for DynamicArray<T, G, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T: Ord, G: GrowthPolicy, A: Allocator> Ord for DynamicArray<T, G, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T: Hash, G: GrowthPolicy, A: Allocator> Hash for DynamicArray<T, G, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+impl<T, G, A: Allocator> IntoIterator for DynamicArray<T, G, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again, so the buffer is
+        // moved out exactly once and the growth policy is dropped once.
+        let buf = unsafe {
+            ptr::drop_in_place(&mut this.growth);
+            ptr::read(&this.buf)
+        };
+        IntoIter {
+            buf,
+            start: 0,
+            end: this.len,
+        }
+    }
+}
+
+impl<'a, T, G: GrowthPolicy, A: Allocator> IntoIterator for &'a DynamicArray<T, G, A> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, G: GrowthPolicy, A: Allocator> IntoIterator for &'a mut DynamicArray<T, G, A> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An owning iterator over the elements of a [`DynamicArray`]
+pub struct IntoIter<T, A: Allocator = Global> {
+    buf: RawBuffer<T, A>,
+    start: usize,
+    end: usize,
+}
+
+// SAFETY: the iterator owns the remaining elements.
+unsafe impl<T: Send, A: Allocator + Send> Send for IntoIter<T, A> {}
+// SAFETY: shared access only hands out `&T`.
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for IntoIter<T, A> {}
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    /// View the elements that have not been yielded yet
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the slots in `start..end` are initialized and unread.
+        unsafe {
+            core::slice::from_raw_parts(
+                self.buf.ptr.as_ptr().add(self.start),
+                self.end - self.start,
+            )
+        }
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        // SAFETY: the slot is initialized and `start` moves past it, so it
+        // is read exactly once.
+        let value = unsafe { self.buf.ptr.as_ptr().add(self.start).read() };
+        self.start += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        self.end -= 1;
+        // SAFETY: the slot is initialized and now lies past `end`, so it is
+        // read exactly once.
+        Some(unsafe { self.buf.ptr.as_ptr().add(self.end).read() })
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        let remaining = ptr::slice_from_raw_parts_mut(
+            // SAFETY: `start <= end <= cap`, so the offset is in bounds.
+            unsafe { self.buf.ptr.as_ptr().add(self.start) },
+            self.end - self.start,
+        );
+        // SAFETY: the remaining elements are initialized and never yielded.
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+impl<T: core::fmt::Debug, A: Allocator> core::fmt::Debug for IntoIter<T, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::allocator::{AllocStats, Bump, Counting};
+    use std::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+
+    /// Counts how many times values of this type have been dropped
+    struct DropCounter<'a>(&'a Cell<usize>);
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
 
     #[test]
     fn test_new() {
@@ -267,4 +1377,686 @@ mod tests {
         assert_eq!(arr.len(), 0);
         assert!(arr.is_empty());
     }
-}
\ No newline at end of file
+
+    // The tests below exercise the unsafe buffer management directly and are
+    // meant to be run under Miri as well: `cargo +nightly miri test`.
+
+    #[test]
+    fn test_growth_factor() {
+        let mut arr = DynamicArray::new();
+        assert_eq!(arr.capacity(), 0);
+        arr.push(0);
+        assert_eq!(arr.capacity(), MIN_NON_ZERO_CAP);
+        for i in 1..=MIN_NON_ZERO_CAP {
+            arr.push(i);
+        }
+        assert_eq!(arr.capacity(), MIN_NON_ZERO_CAP * GROWTH_FACTOR);
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut arr = DynamicArray::new();
+        arr.push(1);
+        arr.reserve(100);
+        assert!(arr.capacity() >= 101);
+        let cap = arr.capacity();
+        arr.reserve(cap - 1);
+        assert_eq!(arr.capacity(), cap);
+        assert_eq!(arr.get(0), Some(&1));
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut arr = DynamicArray::with_capacity(16);
+        arr.push(String::from("a"));
+        arr.push(String::from("b"));
+        arr.shrink_to_fit();
+        assert_eq!(arr.capacity(), 2);
+        assert_eq!(arr.get(1).map(String::as_str), Some("b"));
+        arr.clear();
+        arr.shrink_to_fit();
+        assert_eq!(arr.capacity(), 0);
+        arr.push(String::from("c"));
+        assert_eq!(arr.get(0).map(String::as_str), Some("c"));
+    }
+
+    #[test]
+    fn test_owned_elements_move_correctly() {
+        let mut arr = DynamicArray::new();
+        for i in 0..20 {
+            arr.push(i.to_string());
+        }
+        assert_eq!(arr.remove(0).unwrap(), "0");
+        arr.insert(5, String::from("x")).unwrap();
+        assert_eq!(arr.pop().unwrap(), "19");
+        let collected: Vec<&str> = arr.iter().map(String::as_str).collect();
+        assert_eq!(collected[..7], ["1", "2", "3", "4", "5", "x", "6"]);
+        assert_eq!(arr.len(), 19);
+    }
+
+    #[test]
+    fn test_zero_sized_types() {
+        let mut arr = DynamicArray::new();
+        assert_eq!(arr.capacity(), usize::MAX);
+        for _ in 0..1000 {
+            arr.push(());
+        }
+        assert_eq!(arr.len(), 1000);
+        arr.insert(500, ()).unwrap();
+        assert_eq!(arr.remove(0), Ok(()));
+        assert_eq!(arr.pop(), Some(()));
+        assert_eq!(arr.len(), 999);
+        arr.shrink_to_fit();
+        assert_eq!(arr.capacity(), usize::MAX);
+    }
+
+    #[test]
+    fn test_zero_sized_drops() {
+        let drops = Cell::new(0);
+        {
+            let mut arr = DynamicArray::new();
+            for _ in 0..10 {
+                arr.push(DropCounter(&drops));
+            }
+            drop(arr.pop());
+            assert_eq!(drops.get(), 1);
+        }
+        assert_eq!(drops.get(), 10);
+    }
+
+    #[test]
+    fn test_drop_runs_for_every_element() {
+        let drops = Cell::new(0);
+        {
+            let mut arr = DynamicArray::new();
+            for _ in 0..10 {
+                arr.push(DropCounter(&drops));
+            }
+            drop(arr.remove(3));
+            assert_eq!(drops.get(), 1);
+            arr.clear();
+            assert_eq!(drops.get(), 10);
+            arr.push(DropCounter(&drops));
+        }
+        assert_eq!(drops.get(), 11);
+    }
+
+    #[test]
+    fn test_panicking_destructor_drops_the_rest() {
+        struct PanicOnDrop<'a>(bool, &'a Cell<usize>);
+
+        impl Drop for PanicOnDrop<'_> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+                if self.0 {
+                    panic!("boom");
+                }
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut arr = DynamicArray::new();
+        for i in 0..5 {
+            arr.push(PanicOnDrop(i == 1, &drops));
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(move || drop(arr)));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn test_clone_panic_does_not_double_drop() {
+        struct PanicOnClone<'a>(usize, &'a Cell<usize>);
+
+        impl Clone for PanicOnClone<'_> {
+            fn clone(&self) -> Self {
+                if self.0 == 3 {
+                    panic!("clone failed");
+                }
+                PanicOnClone(self.0, self.1)
+            }
+        }
+
+        impl Drop for PanicOnClone<'_> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut arr = DynamicArray::new();
+        for i in 0..5 {
+            arr.push(PanicOnClone(i, &drops));
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| arr.clone()));
+        assert!(result.is_err());
+        // Only the three clones made before the panic were dropped.
+        assert_eq!(drops.get(), 3);
+        drop(arr);
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[test]
+    fn test_one_and_a_half_growth() {
+        let mut arr = DynamicArray::with_growth(OneAndAHalf);
+        let mut capacities = Vec::new();
+        for i in 0..20 {
+            arr.push(i);
+            if capacities.last() != Some(&arr.capacity()) {
+                capacities.push(arr.capacity());
+            }
+        }
+        assert_eq!(capacities, [4, 6, 9, 13, 19, 28]);
+    }
+
+    #[test]
+    fn test_fixed_increment_growth() {
+        let mut arr = DynamicArray::with_growth(FixedIncrement(8));
+        for i in 0..20 {
+            arr.push(i);
+        }
+        assert_eq!(arr.capacity(), 24);
+        assert_eq!(arr.realloc_stats().grows, 3);
+    }
+
+    #[test]
+    fn test_closure_growth() {
+        let mut arr = DynamicArray::with_growth(|cap: usize, _required: usize| cap + 3);
+        for i in 0..7 {
+            arr.push(i);
+        }
+        assert_eq!(arr.capacity(), 9);
+        assert_eq!(arr.get(6), Some(&6));
+    }
+
+    #[test]
+    fn test_growth_never_below_required() {
+        let mut arr = DynamicArray::with_growth(|_cap: usize, _required: usize| 0);
+        arr.push(1);
+        arr.push(2);
+        assert_eq!(arr.capacity(), 2);
+        arr.reserve(10);
+        assert_eq!(arr.capacity(), 12);
+        let mut arr = DynamicArray::with_growth(FixedIncrement(0));
+        arr.push(1);
+        assert_eq!(arr.capacity(), 1);
+    }
+
+    #[test]
+    fn test_realloc_stats() {
+        let mut doubling = DynamicArray::new();
+        let mut fixed = DynamicArray::with_growth(FixedIncrement(16));
+        for i in 0..1000 {
+            doubling.push(i);
+            fixed.push(i);
+        }
+        assert_eq!(doubling.realloc_stats().grows, 9);
+        assert_eq!(fixed.realloc_stats().grows, 63);
+        doubling.shrink_to_fit();
+        doubling.shrink_to_fit();
+        let stats = doubling.realloc_stats();
+        assert_eq!(stats.shrinks, 1);
+        assert_eq!(stats.reallocations(), 10);
+    }
+
+    #[test]
+    fn test_shrink_policy_after_pop() {
+        let mut arr = DynamicArray::new();
+        arr.set_shrink_policy(Some(ShrinkPolicy::default()));
+        for i in 0..64 {
+            arr.push(i);
+        }
+        assert_eq!(arr.capacity(), 64);
+        while arr.len() > 16 {
+            arr.pop();
+        }
+        assert_eq!(arr.capacity(), 32);
+        while arr.len() > 1 {
+            arr.pop();
+        }
+        assert_eq!(arr.capacity(), ShrinkPolicy::default().min_capacity);
+        assert_eq!(arr.realloc_stats().shrinks, 4);
+        assert_eq!(arr.get(0), Some(&0));
+    }
+
+    #[test]
+    fn test_shrink_policy_after_remove() {
+        let mut arr = DynamicArray::with_capacity(32);
+        arr.set_shrink_policy(Some(ShrinkPolicy {
+            divisor: 2,
+            min_capacity: 8,
+        }));
+        for i in 0..17 {
+            arr.push(i);
+        }
+        assert_eq!(arr.capacity(), 32);
+        assert_eq!(arr.remove(0), Ok(0));
+        assert_eq!(arr.capacity(), 16);
+        while !arr.is_empty() {
+            arr.remove(0).unwrap();
+        }
+        assert_eq!(arr.capacity(), 8);
+    }
+
+    #[test]
+    fn test_no_shrink_without_policy() {
+        let mut arr = DynamicArray::new();
+        for i in 0..64 {
+            arr.push(i);
+        }
+        while arr.pop().is_some() {}
+        assert_eq!(arr.capacity(), 64);
+        assert_eq!(arr.shrink_policy(), None);
+        assert_eq!(arr.realloc_stats().shrinks, 0);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut arr = dsa_vec![1, 2, 3, 4];
+        assert_eq!(arr[0], 1);
+        assert_eq!(arr[1..3], [2, 3]);
+        arr[2] = 30;
+        arr[..2].copy_from_slice(&[10, 20]);
+        assert_eq!(arr, [10, 20, 30, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds() {
+        let arr = dsa_vec![1, 2, 3];
+        let _ = arr[3];
+    }
+
+    #[test]
+    fn test_deref_to_slice() {
+        let mut arr = dsa_vec![3, 1, 2];
+        arr.sort();
+        assert_eq!(arr.first(), Some(&1));
+        assert!(arr.contains(&3));
+        assert_eq!(arr.as_ref(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_into_iter_owned() {
+        let arr = dsa_vec![String::from("a"), String::from("b"), String::from("c")];
+        let mut iter = arr.into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back().as_deref(), Some("c"));
+        assert_eq!(iter.as_slice(), ["a", "b"]);
+        assert_eq!(iter.next().as_deref(), Some("a"));
+        assert_eq!(iter.next().as_deref(), Some("b"));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_into_iter_drops_remaining() {
+        let drops = Cell::new(0);
+        let mut arr = DynamicArray::new();
+        for _ in 0..5 {
+            arr.push(DropCounter(&drops));
+        }
+        let mut iter = arr.into_iter();
+        drop(iter.next());
+        assert_eq!(drops.get(), 1);
+        drop(iter);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn test_into_iter_by_ref_and_mut() {
+        let mut arr = dsa_vec![1, 2, 3];
+        for val in &mut arr {
+            *val *= 2;
+        }
+        let mut sum = 0;
+        for val in &arr {
+            sum += val;
+        }
+        assert_eq!(sum, 12);
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let arr: DynamicArray<i32> = (1..=5).collect();
+        assert_eq!(arr, [1, 2, 3, 4, 5]);
+        let doubled = arr.iter().map(|x| x * 2).collect::<DynamicArray<_>>();
+        assert_eq!(doubled, [2, 4, 6, 8, 10]);
+        let custom: DynamicArray<i32, OneAndAHalf> = (0..10).collect();
+        assert_eq!(custom.len(), 10);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut arr = dsa_vec![1];
+        arr.extend(vec![2, 3]);
+        arr.extend(&[4, 5]);
+        assert_eq!(arr, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_eq_and_ord() {
+        let a = dsa_vec![1, 2, 3];
+        let mut b = DynamicArray::with_growth(FixedIncrement(1));
+        b.extend([1, 2, 3]);
+        assert_eq!(a, b);
+        assert_ne!(a, dsa_vec![1, 2]);
+        assert!(dsa_vec![1, 2] < a);
+        assert!(dsa_vec![1, 3] > a);
+        assert_eq!(a.cmp(&dsa_vec![1, 2, 3]), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashSet;
+
+        fn hash_of<H: Hash + ?Sized>(value: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        assert_eq!(hash_of(&dsa_vec![1, 2, 3]), hash_of(&[1, 2, 3][..]));
+        let set: HashSet<DynamicArray<i32>> = [dsa_vec![1], dsa_vec![1], dsa_vec![2]]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let arr = DynamicArray::from(vec![String::from("x"), String::from("y")]);
+        assert_eq!(arr.len(), 2);
+        assert_eq!(arr[1], "y");
+        let empty = DynamicArray::<()>::from(vec![(); 3]);
+        assert_eq!(empty.len(), 3);
+    }
+
+    #[test]
+    fn test_from_array() {
+        let arr = DynamicArray::from([1, 2, 3]);
+        assert_eq!(arr.capacity(), 3);
+        assert_eq!(arr, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_dsa_vec_macro() {
+        let empty: DynamicArray<i32> = dsa_vec![];
+        assert!(empty.is_empty());
+        assert_eq!(dsa_vec![1, 2, 3,], [1, 2, 3]);
+        let repeated = dsa_vec![String::from("ab"); 3];
+        assert_eq!(repeated, ["ab", "ab", "ab"]);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let drops = Cell::new(0);
+        let mut arr = DynamicArray::new();
+        for _ in 0..5 {
+            arr.push(DropCounter(&drops));
+        }
+        arr.truncate(7);
+        assert_eq!(arr.len(), 5);
+        arr.truncate(2);
+        assert_eq!(arr.len(), 2);
+        assert_eq!(drops.get(), 3);
+        assert_eq!(arr.capacity(), 8);
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        let mut arr = dsa_vec![1, 2, 3, 4];
+        assert_eq!(arr.swap_remove(0), Ok(1));
+        assert_eq!(arr, [4, 2, 3]);
+        assert_eq!(arr.swap_remove(2), Ok(3));
+        assert_eq!(arr, [4, 2]);
+        assert!(arr.swap_remove(2).is_err());
+    }
+
+    #[test]
+    fn test_append() {
+        let mut a = dsa_vec![String::from("a")];
+        let mut b = dsa_vec![String::from("b"), String::from("c")];
+        a.append(&mut b);
+        assert_eq!(a, ["a", "b", "c"]);
+        assert!(b.is_empty());
+        b.push(String::from("d"));
+        assert_eq!(b, ["d"]);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut arr: DynamicArray<i32> = (0..10).collect();
+        let drained: Vec<i32> = arr.drain(2..5).collect();
+        assert_eq!(drained, [2, 3, 4]);
+        assert_eq!(arr, [0, 1, 5, 6, 7, 8, 9]);
+        let mut drain = arr.drain(..=1);
+        assert_eq!(drain.next_back(), Some(1));
+        assert_eq!(drain.as_slice(), [0]);
+        drop(drain);
+        assert_eq!(arr, [5, 6, 7, 8, 9]);
+        arr.drain(..);
+        assert!(arr.is_empty());
+    }
+
+    #[test]
+    fn test_drain_drops_unyielded_elements() {
+        let drops = Cell::new(0);
+        let mut arr = DynamicArray::new();
+        for _ in 0..6 {
+            arr.push(DropCounter(&drops));
+        }
+        let mut drain = arr.drain(1..4);
+        drop(drain.next());
+        assert_eq!(drops.get(), 1);
+        drop(drain);
+        assert_eq!(drops.get(), 3);
+        assert_eq!(arr.len(), 3);
+    }
+
+    #[test]
+    fn test_leaked_drain_does_not_double_drop() {
+        let drops = Cell::new(0);
+        let mut arr = DynamicArray::new();
+        for _ in 0..4 {
+            arr.push(DropCounter(&drops));
+        }
+        mem::forget(arr.drain(1..));
+        assert_eq!(arr.len(), 1);
+        drop(arr);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drain_out_of_bounds() {
+        let mut arr = dsa_vec![1, 2, 3];
+        arr.drain(1..4);
+    }
+
+    #[test]
+    fn test_splice() {
+        let mut arr = dsa_vec![1, 2, 3, 4, 5];
+        let removed: Vec<i32> = arr.splice(1..3, [20, 30, 40]).collect();
+        assert_eq!(removed, [2, 3]);
+        assert_eq!(arr, [1, 20, 30, 40, 4, 5]);
+        let removed: Vec<i32> = arr.splice(..4, []).collect();
+        assert_eq!(removed, [1, 20, 30, 40]);
+        assert_eq!(arr, [4, 5]);
+        arr.splice(2.., 6..9);
+        assert_eq!(arr, [4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_splice_panicking_iterator_leaves_array_intact() {
+        let mut arr = dsa_vec![String::from("a"), String::from("b")];
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            arr.splice(
+                ..1,
+                (0..3).map(|i| {
+                    if i == 2 {
+                        panic!("boom")
+                    } else {
+                        i.to_string()
+                    }
+                }),
+            );
+        }));
+        assert!(result.is_err());
+        assert_eq!(arr, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut arr: DynamicArray<i32> = (0..10).collect();
+        arr.retain(|x| x % 3 != 0);
+        assert_eq!(arr, [1, 2, 4, 5, 7, 8]);
+        arr.retain_mut(|x| {
+            *x *= 10;
+            *x > 40
+        });
+        assert_eq!(arr, [50, 70, 80]);
+    }
+
+    #[test]
+    fn test_retain_panic_does_not_double_drop() {
+        let drops = Cell::new(0);
+        let mut arr = DynamicArray::new();
+        for i in 0..6 {
+            arr.push((i, DropCounter(&drops)));
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            arr.retain(|(i, _)| {
+                if *i == 4 {
+                    panic!("predicate failed");
+                }
+                i % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+        // 1 and 3 were removed; 4 and 5 were never judged and are kept.
+        assert_eq!(drops.get(), 2);
+        let kept: Vec<i32> = arr.iter().map(|(i, _)| *i).collect();
+        assert_eq!(kept, [0, 2, 4, 5]);
+        drop(arr);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut arr = dsa_vec![1, 1, 2, 3, 3, 3, 1, 4, 4];
+        arr.dedup();
+        assert_eq!(arr, [1, 2, 3, 1, 4]);
+        let mut arr = dsa_vec![10, 11, 20, 25, 31, 12];
+        arr.dedup_by_key(|x| *x / 10);
+        assert_eq!(arr, [10, 20, 31, 12]);
+        let mut arr = dsa_vec!["a", "A", "b", "B", "b"];
+        arr.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        assert_eq!(arr, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_dedup_panic_does_not_double_drop() {
+        let drops = Cell::new(0);
+        let mut arr = DynamicArray::new();
+        for i in [0, 0, 1, 1, 2, 2] {
+            arr.push((i, DropCounter(&drops)));
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            arr.dedup_by_key(|(i, _)| {
+                if *i == 2 {
+                    panic!("key failed");
+                }
+                *i
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        let kept: Vec<i32> = arr.iter().map(|(i, _)| *i).collect();
+        assert_eq!(kept, [0, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut arr = DynamicArray::with_growth(FixedIncrement(2));
+        arr.extend(1..=5);
+        let tail = arr.split_off(3).unwrap();
+        assert_eq!(arr, [1, 2, 3]);
+        assert_eq!(tail, [4, 5]);
+        assert_eq!(tail.growth_policy(), &FixedIncrement(2));
+        assert!(arr.split_off(4).is_err());
+        assert!(arr.split_off(3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let mut arr = dsa_vec![String::from("a")];
+        arr.extend_from_slice(&[String::from("b"), String::from("c")]);
+        assert_eq!(arr, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_allocation_stats() {
+        let counting = Counting::new(Global);
+        let mut arr = DynamicArray::new_in(&counting);
+        for i in 0..100u64 {
+            arr.push(i);
+        }
+        let stats = counting.stats();
+        assert_eq!(stats.allocations, 1);
+        assert_eq!(stats.reallocations, arr.realloc_stats().grows - 1);
+        assert_eq!(stats.bytes_in_use, arr.capacity() * 8);
+
+        arr.truncate(10);
+        arr.shrink_to_fit();
+        assert_eq!(counting.stats().bytes_in_use, 80);
+        assert_eq!(counting.stats().peak_bytes, 128 * 8);
+
+        let tail = arr.split_off(5).unwrap();
+        assert_eq!(counting.stats().live(), 2);
+        drop(tail);
+        drop(arr);
+        assert_eq!(counting.stats().live(), 0);
+        assert_eq!(counting.stats().bytes_in_use, 0);
+    }
+
+    #[test]
+    fn test_owned_counting_allocator() {
+        let mut arr = DynamicArray::with_capacity_in(4, Counting::new(Global));
+        arr.extend([1, 2, 3, 4]);
+        assert_eq!(arr.allocator().stats().allocations, 1);
+        assert_eq!(arr.allocator().stats().reallocations, 0);
+        arr.push(5);
+        assert_eq!(arr.allocator().stats().reallocations, 1);
+        let values: Vec<i32> = arr.into_iter().collect();
+        assert_eq!(values, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_zero_sized_elements_never_allocate() {
+        let counting = Counting::new(Global);
+        let mut arr = DynamicArray::new_in(&counting);
+        for _ in 0..1000 {
+            arr.push(());
+        }
+        assert_eq!(arr.len(), 1000);
+        assert_eq!(counting.stats(), AllocStats::default());
+    }
+
+    #[test]
+    fn test_bump_allocator() {
+        let bump = Bump::new();
+        let mut arr = DynamicArray::new_in(&bump);
+        arr.extend((0..1000).map(|i| i.to_string()));
+        assert_eq!(arr[999], "999");
+
+        let removed: Vec<String> = arr.splice(1..999, ["x".to_string()]).collect();
+        assert_eq!(removed.len(), 998);
+        assert_eq!(arr, ["0", "x", "999"]);
+        let cloned = arr.clone();
+        assert_eq!(cloned, arr);
+        let mut other = DynamicArray::new();
+        other.append(&mut arr);
+        assert_eq!(other, cloned);
+    }
+}
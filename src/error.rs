@@ -0,0 +1,141 @@
+use core::fmt;
+
+/// Errors returned by the collections in this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// An index was past the end of a collection of length `len`
+    IndexOutOfBounds { index: usize, len: usize },
+    /// The operation needs a non-empty collection
+    Empty,
+    /// A fixed-capacity collection had no room for another element
+    CapacityExceeded { capacity: usize },
+    /// A handle referred to a node that was removed or never existed
+    InvalidHandle,
+    /// A collection of length `expected` was built from `found` elements
+    LengthMismatch { expected: usize, found: usize },
+    /// A matrix had no inverse because its determinant is zero
+    SingularMatrix,
+    /// An operation needed a square matrix
+    NotSquare { rows: usize, cols: usize },
+    /// Two shapes that an operation combines don't fit together
+    ShapeMismatch {
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+}
+
+impl Error {
+    /// Build an [`Error::IndexOutOfBounds`] for `index` in a collection of
+    /// length `len`
+    pub(crate) fn out_of_bounds(index: usize, len: usize) -> Self {
+        Self::IndexOutOfBounds { index, len }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IndexOutOfBounds { index, len } => {
+                write!(f, "index {index} is out of bounds for length {len}")
+            }
+            Error::Empty => f.write_str("collection is empty"),
+            Error::CapacityExceeded { capacity } => {
+                write!(f, "capacity exceeded (capacity: {capacity})")
+            }
+            Error::InvalidHandle => f.write_str("handle does not refer to a live node"),
+            Error::LengthMismatch { expected, found } => {
+                write!(f, "expected {expected} elements, found {found}")
+            }
+            Error::SingularMatrix => f.write_str("matrix is singular"),
+            Error::NotSquare { rows, cols } => write!(f, "matrix is {rows}×{cols}, not square"),
+            Error::ShapeMismatch { left, right } => write!(
+                f,
+                "shapes {}×{} and {}×{} are incompatible",
+                left.0, left.1, right.0, right.1
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::Array;
+    #[cfg(feature = "alloc")]
+    use crate::dynamic_arrays::DynamicArray;
+    #[cfg(feature = "alloc")]
+    use crate::linked_lists::LinkedList;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::out_of_bounds(5, 3).to_string(),
+            "index 5 is out of bounds for length 3"
+        );
+        assert_eq!(Error::Empty.to_string(), "collection is empty");
+        assert_eq!(
+            Error::CapacityExceeded { capacity: 4 }.to_string(),
+            "capacity exceeded (capacity: 4)"
+        );
+        assert_eq!(
+            Error::InvalidHandle.to_string(),
+            "handle does not refer to a live node"
+        );
+        assert_eq!(
+            Error::LengthMismatch {
+                expected: 3,
+                found: 2
+            }
+            .to_string(),
+            "expected 3 elements, found 2"
+        );
+        assert_eq!(Error::SingularMatrix.to_string(), "matrix is singular");
+        assert_eq!(
+            Error::NotSquare { rows: 2, cols: 3 }.to_string(),
+            "matrix is 2×3, not square"
+        );
+        assert_eq!(
+            Error::ShapeMismatch {
+                left: (2, 3),
+                right: (2, 3)
+            }
+            .to_string(),
+            "shapes 2×3 and 2×3 are incompatible"
+        );
+    }
+
+    #[test]
+    fn test_errors_carry_index_and_length() {
+        let mut arr: Array<i32, 3> = Array::new([1, 2, 3]);
+        assert_eq!(arr.set(7, 0), Err(Error::out_of_bounds(7, 3)));
+        #[cfg(feature = "alloc")]
+        {
+            let mut dynamic: DynamicArray<i32> = DynamicArray::new();
+            assert_eq!(dynamic.remove(0), Err(Error::out_of_bounds(0, 0)));
+            let mut list: LinkedList<i32> = LinkedList::new();
+            list.insert_at_head(1);
+            assert_eq!(list.insert_at_index(3, 2), Err(Error::out_of_bounds(3, 1)));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_question_mark_into_boxed_error() {
+        fn first_two(list: &mut LinkedList<i32>) -> Result<i32, Box<dyn std::error::Error>> {
+            let a = list.remove_from_index(0)?;
+            let b = list.remove_from_index(0)?;
+            Ok(a + b)
+        }
+
+        let mut list = LinkedList::new();
+        list.insert_at_head(1);
+        let err = first_two(&mut list).unwrap_err();
+        assert_eq!(err.to_string(), "index 0 is out of bounds for length 0");
+        assert_eq!(
+            err.downcast_ref::<Error>(),
+            Some(&Error::out_of_bounds(0, 0))
+        );
+    }
+}
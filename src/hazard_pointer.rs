@@ -0,0 +1,243 @@
+// Hazard Pointer implementation in Rust
+
+//! Memory reclamation for the lock-free collections.
+//!
+//! A thread that wants to read a shared node publishes its address in a
+//! hazard record first. Nodes unlinked from a structure are retired rather
+//! than freed, and a retired node is only freed once a scan finds no hazard
+//! record pointing at it.
+//!
+//! Every atomic access here uses `SeqCst`: a hazard must be visible before
+//! the reader re-checks the source, and before a retiring thread scans, which
+//! needs store-to-load ordering that weaker orderings don't give.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ptr;
+
+#[cfg(not(loom))]
+pub(crate) use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use Ordering::SeqCst;
+
+/// How many retired nodes per hazard record to collect before scanning
+const SCAN_FACTOR: usize = 2;
+
+/// A slot where one thread publishes the node it is reading
+struct Record {
+    hazard: AtomicPtr<()>,
+    active: AtomicBool,
+    /// Records are never unlinked while the domain lives, so this is fixed
+    /// once the record is published
+    next: *mut Record,
+}
+
+/// A node waiting for no hazard to point at it
+struct Retired {
+    ptr: *mut (),
+    free: unsafe fn(*mut ()),
+    next: *mut Retired,
+}
+
+/// Free a retired `Box<T>`
+///
+/// # Safety
+///
+/// `ptr` must come from `Box::<T>::into_raw` and be unreachable.
+unsafe fn free_box<T>(ptr: *mut ()) {
+    drop(Box::from_raw(ptr.cast::<T>()));
+}
+
+/// The hazard records and retired nodes of one lock-free structure
+pub(crate) struct Domain {
+    records: AtomicPtr<Record>,
+    record_count: AtomicUsize,
+    retired: AtomicPtr<Retired>,
+    retired_count: AtomicUsize,
+}
+
+impl Domain {
+    /// Create a new domain with no records
+    pub(crate) fn new() -> Self {
+        Self {
+            records: AtomicPtr::new(ptr::null_mut()),
+            record_count: AtomicUsize::new(0),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            retired_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Claim a hazard record, reusing an inactive one if there is one
+    pub(crate) fn hazard(&self) -> Hazard<'_> {
+        let mut current = self.records.load(SeqCst);
+        while !current.is_null() {
+            // SAFETY: records live until the domain is dropped.
+            let record = unsafe { &*current };
+            if record
+                .active
+                .compare_exchange(false, true, SeqCst, SeqCst)
+                .is_ok()
+            {
+                return Hazard { record };
+            }
+            current = record.next;
+        }
+
+        let record = Box::into_raw(Box::new(Record {
+            hazard: AtomicPtr::new(ptr::null_mut()),
+            active: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.records.load(SeqCst);
+        loop {
+            // SAFETY: the record is not published yet, so this thread owns it.
+            unsafe { (*record).next = head };
+            match self.records.compare_exchange(head, record, SeqCst, SeqCst) {
+                Ok(_) => break,
+                Err(actual) => head = actual,
+            }
+        }
+        self.record_count.fetch_add(1, SeqCst);
+        // SAFETY: records live until the domain is dropped.
+        Hazard {
+            record: unsafe { &*record },
+        }
+    }
+
+    /// Retire a node that has been unlinked, freeing it once no hazard
+    /// points at it
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must come from `Box::<T>::into_raw`, must no longer be reachable
+    /// from the structure, and must be retired only once. Dropping the box
+    /// may happen on any thread.
+    pub(crate) unsafe fn retire<T>(&self, ptr: *mut T) {
+        // Count the entry before publishing it, so a concurrent scan that
+        // frees it can't take the count below zero.
+        let retired = self.retired_count.fetch_add(1, SeqCst) + 1;
+        self.push_retired(Box::into_raw(Box::new(Retired {
+            ptr: ptr.cast(),
+            free: free_box::<T>,
+            next: ptr::null_mut(),
+        })));
+        if retired > SCAN_FACTOR * self.record_count.load(SeqCst) {
+            self.scan();
+        }
+    }
+
+    /// Push an entry onto the retired stack
+    fn push_retired(&self, entry: *mut Retired) {
+        let mut head = self.retired.load(SeqCst);
+        loop {
+            // SAFETY: the entry is off the stack, so this thread owns it.
+            unsafe { (*entry).next = head };
+            match self.retired.compare_exchange(head, entry, SeqCst, SeqCst) {
+                Ok(_) => return,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    /// Free every retired node that no hazard points at
+    fn scan(&self) {
+        // Taking the whole stack gives this thread its own batch, so
+        // concurrent scans never see the same entry.
+        let mut entry = self.retired.swap(ptr::null_mut(), SeqCst);
+        if entry.is_null() {
+            return;
+        }
+        let mut hazards = Vec::new();
+        let mut record = self.records.load(SeqCst);
+        while !record.is_null() {
+            // SAFETY: records live until the domain is dropped.
+            let current = unsafe { &*record };
+            let hazard = current.hazard.load(SeqCst);
+            if !hazard.is_null() {
+                hazards.push(hazard);
+            }
+            record = current.next;
+        }
+
+        while !entry.is_null() {
+            // SAFETY: the batch was taken off the shared stack, so this
+            // thread owns every entry in it.
+            let (next, node) = unsafe { ((*entry).next, (*entry).ptr) };
+            if hazards.contains(&node) {
+                self.push_retired(entry);
+            } else {
+                // SAFETY: the node was unlinked before it was retired, and
+                // no hazard published before the scan points at it, so no
+                // thread can reach it any more.
+                unsafe {
+                    let entry = Box::from_raw(entry);
+                    (entry.free)(node);
+                }
+                self.retired_count.fetch_sub(1, SeqCst);
+            }
+            entry = next;
+        }
+    }
+}
+
+impl Drop for Domain {
+    fn drop(&mut self) {
+        // No hazard can outlive its borrow of the domain, so every retired
+        // node is free to go.
+        let mut entry = self.retired.load(SeqCst);
+        while !entry.is_null() {
+            // SAFETY: the domain owns its retired entries and records, and
+            // each is freed exactly once.
+            unsafe {
+                let current = Box::from_raw(entry);
+                (current.free)(current.ptr);
+                entry = current.next;
+            }
+        }
+        let mut record = self.records.load(SeqCst);
+        while !record.is_null() {
+            // SAFETY: as above.
+            unsafe {
+                let current = Box::from_raw(record);
+                record = current.next;
+            }
+        }
+    }
+}
+
+/// A claimed hazard record, released when dropped
+pub(crate) struct Hazard<'a> {
+    record: &'a Record,
+}
+
+impl Hazard<'_> {
+    /// Load `source` and protect the node it points at, so it can't be
+    /// freed until the hazard is reset or dropped
+    pub(crate) fn protect<T>(&self, source: &AtomicPtr<T>) -> *mut T {
+        let mut ptr = source.load(SeqCst);
+        loop {
+            self.record.hazard.store(ptr.cast(), SeqCst);
+            // If the source still holds the node after the hazard is
+            // visible, any later retire will see the hazard.
+            let current = source.load(SeqCst);
+            if current == ptr {
+                return ptr;
+            }
+            ptr = current;
+        }
+    }
+
+    /// Stop protecting the current node
+    pub(crate) fn reset(&self) {
+        self.record.hazard.store(ptr::null_mut(), SeqCst);
+    }
+}
+
+impl Drop for Hazard<'_> {
+    fn drop(&mut self) {
+        self.reset();
+        self.record.active.store(false, SeqCst);
+    }
+}
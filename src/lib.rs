@@ -1,3 +1,36 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod allocator;
+#[cfg(feature = "alloc")]
+pub mod arena_list;
+pub mod array_vec;
 pub mod arrays;
+#[cfg(feature = "alloc")]
+pub mod dense_matrix;
+#[cfg(feature = "alloc")]
+pub mod doubly_linked_list;
+#[cfg(feature = "alloc")]
 pub mod dynamic_arrays;
-pub mod linked_lists;
\ No newline at end of file
+pub mod error;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+mod hazard_pointer;
+#[cfg(feature = "alloc")]
+pub mod linked_lists;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub mod lock_free;
+pub mod matrix;
+pub mod numeric;
+#[cfg(feature = "alloc")]
+pub mod persistent_list;
+#[cfg(feature = "alloc")]
+pub mod skip_list;
+#[cfg(feature = "alloc")]
+pub mod small_array;
+#[cfg(feature = "alloc")]
+pub mod unrolled_list;
+
+pub use error::Error;
@@ -1,139 +1,2106 @@
 // Single Linked List implementation in Rust
 
+use crate::allocator::{self, Allocator, Global};
+use crate::error::Error;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// A link to the next node, owned by the list
+type Link<T> = Option<NonNull<Node<T>>>;
+
 /// A node in the linked list
-pub struct Node<T> {
-    pub value: T,
-    pub next: Option<Box<Node<T>>>,
+struct Node<T> {
+    value: T,
+    next: Link<T>,
 }
-/// A singly linked list
-pub struct LinkedList<T> {
-    pub head: Option<Box<Node<T>>>,
-    pub length: usize,
+
+/// A singly linked list with O(1) insertion at both ends.
+///
+/// Nodes are heap allocated and linked by raw pointers, which lets the list
+/// keep a pointer to its last node next to the owning chain from `head`.
+/// Every node is owned by exactly one link, and `tail` points at the node
+/// whose `next` is `None` (or is `None` when the list is empty). Nodes are
+/// allocated from `A`, the global heap by default.
+pub struct LinkedList<T, A: Allocator = Global> {
+    head: Link<T>,
+    tail: Link<T>,
+    length: usize,
+    alloc: A,
+    _marker: PhantomData<Node<T>>,
 }
-// Errors enum
-#[derive(Debug)]
-pub enum LinkedListError {
-    IndexOutOfBounds,
-    EmptyList,
-    InvalidIndex,
+
+// SAFETY: the list owns its nodes like a `Box<Node<T>>` chain would.
+unsafe impl<T: Send, A: Allocator + Send> Send for LinkedList<T, A> {}
+// SAFETY: shared access only hands out `&T`.
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for LinkedList<T, A> {}
+
+impl<T, A: Allocator + Default> Default for LinkedList<T, A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<T> Node<T> {
+    /// Allocate a detached node from `alloc`
+    fn alloc<A: Allocator>(alloc: &A, value: T, next: Link<T>) -> NonNull<Node<T>> {
+        allocator::alloc_value(alloc, Node { value, next })
+    }
+
+    /// Free a node that has been unlinked, returning its value
+    ///
+    /// # Safety
+    ///
+    /// `node` must come from [`Node::alloc`] with `alloc` or an allocator
+    /// it is the same as, and no link may still point at it.
+    unsafe fn free<A: Allocator>(alloc: &A, node: NonNull<Node<T>>) -> T {
+        allocator::free_value(alloc, node).value
+    }
 }
 
 impl<T> LinkedList<T> {
-    // Create a new empty linked list
+    /// Create a new empty linked list
     pub fn new() -> Self {
-        Self { head: None, length: 0 }
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    /// Create a new empty linked list whose nodes come from `alloc`
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            head: None,
+            tail: None,
+            length: 0,
+            alloc,
+            _marker: PhantomData,
+        }
     }
-    // Get the length of the linked list
+
+    /// Get the allocator the nodes come from
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Get the length of the linked list
     pub fn len(&self) -> usize {
         self.length
     }
-    // Check if linked list is empty 
+
+    /// Check if the linked list is empty
     pub fn is_empty(&self) -> bool {
         self.length == 0
     }
-    // Insert at head
+
+    /// Insert at head in O(1)
     pub fn insert_at_head(&mut self, value: T) {
-        let new_node = Box::new(Node {
-            value,
-            next: self.head.take(),
-        });
-        self.head = Some(new_node);
+        let node = Node::alloc(&self.alloc, value, self.head);
+        if self.tail.is_none() {
+            self.tail = Some(node);
+        }
+        self.head = Some(node);
         self.length += 1;
     }
-    // insert at tail
+
+    /// Insert at tail in O(1)
     pub fn insert_at_tail(&mut self, value: T) {
-        let new_node = Box::new(Node { value, next: None });
-        if let Some(mut current) = self.head.as_mut() {
-            while let Some(ref mut next) = current.next {
-                current = next;
-            }
-            current.next = Some(new_node);
-        } else {
-            self.head = Some(new_node);
+        let node = Node::alloc(&self.alloc, value, None);
+        match self.tail {
+            // SAFETY: `tail` points at the live last node of this list.
+            Some(tail) => unsafe { (*tail.as_ptr()).next = Some(node) },
+            None => self.head = Some(node),
         }
+        self.tail = Some(node);
         self.length += 1;
     }
-    // insert at index
-    pub fn insert_at_index(&mut self, index: usize, value: T) -> Result<(), LinkedListError> {
+
+    /// Insert at index
+    pub fn insert_at_index(&mut self, index: usize, value: T) -> Result<(), Error> {
         if index > self.length {
-            return Err(LinkedListError::IndexOutOfBounds);
+            return Err(Error::out_of_bounds(index, self.length));
         }
         if index == 0 {
             self.insert_at_head(value);
             return Ok(());
         }
-        let mut current = self.head.as_mut();
-        for _ in 0..index - 1 {
-            if let Some(node) = current {
-                current = node.next.as_mut();
-            }
+        if index == self.length {
+            self.insert_at_tail(value);
+            return Ok(());
         }
-        if let Some(node) = current {
-            let new_node = Box::new(Node {
-                value,
-                next: node.next.take(),
-            });
-            node.next = Some(new_node);
-            self.length += 1;
-            Ok(())
-        } else {
-            Err(LinkedListError::InvalidIndex)
+        let prev = self.node_at(index - 1).expect("index is in bounds");
+        // SAFETY: `prev` is a live node of this list and is not the tail, so
+        // the new node is linked into the middle of the chain.
+        unsafe {
+            let node = Node::alloc(&self.alloc, value, (*prev.as_ptr()).next);
+            (*prev.as_ptr()).next = Some(node);
         }
+        self.length += 1;
+        Ok(())
     }
-    
-    // Remove from head
+
+    /// Remove from head in O(1)
     pub fn remove_from_head(&mut self) -> Option<T> {
-        if let Some(node) = self.head.take() {
-            self.head = node.next;
+        let head = self.head?;
+        // SAFETY: `head` is a live node owned by this list; it is unlinked
+        // before being freed.
+        unsafe {
+            self.head = (*head.as_ptr()).next;
+            if self.head.is_none() {
+                self.tail = None;
+            }
             self.length -= 1;
-            Some(node.value)
-        } else {
-            None
+            Some(Node::free(&self.alloc, head))
         }
     }
 
-    //remove from tail
+    /// Remove from tail.
+    ///
+    /// A singly linked node doesn't know its predecessor, so this walks the
+    /// list to find the new tail and takes O(n).
     pub fn remove_from_tail(&mut self) -> Option<T> {
-        if self.head.is_none() {
-            return None;
+        match self.length {
+            0 => None,
+            1 => self.remove_from_head(),
+            len => {
+                let prev = self.node_at(len - 2).expect("list has two nodes");
+                // SAFETY: `prev` is the live second-to-last node, so its
+                // `next` is the tail, which is unlinked before being freed.
+                unsafe {
+                    let tail = (*prev.as_ptr()).next.take().expect("tail follows prev");
+                    self.tail = Some(prev);
+                    self.length -= 1;
+                    Some(Node::free(&self.alloc, tail))
+                }
+            }
+        }
+    }
+
+    /// Remove from index
+    pub fn remove_from_index(&mut self, index: usize) -> Result<T, Error> {
+        if index >= self.length {
+            return Err(Error::out_of_bounds(index, self.length));
+        }
+        if index == 0 {
+            return self.remove_from_head().ok_or(Error::Empty);
+        }
+        let prev = self.node_at(index - 1).expect("index is in bounds");
+        // SAFETY: `prev` is a live node with a successor since
+        // `index < length`; the successor is unlinked before being freed.
+        unsafe {
+            let node = (*prev.as_ptr()).next.expect("index is in bounds");
+            (*prev.as_ptr()).next = (*node.as_ptr()).next;
+            if self.tail == Some(node) {
+                self.tail = Some(prev);
+            }
+            self.length -= 1;
+            Ok(Node::free(&self.alloc, node))
+        }
+    }
+
+    /// Reverse the linked list in place
+    pub fn reverse(&mut self) {
+        let mut prev: Link<T> = None;
+        let mut current = self.head;
+        while let Some(node) = current {
+            // SAFETY: `node` is a live node of this list; each node is
+            // visited once and relinked to its predecessor.
+            unsafe {
+                current = (*node.as_ptr()).next;
+                (*node.as_ptr()).next = prev;
+            }
+            prev = Some(node);
+        }
+        self.tail = self.head;
+        self.head = prev;
+    }
+
+    /// Sort the linked list in place with a stable merge sort.
+    ///
+    /// Runs in O(n log n) time and O(1) extra space: nodes are relinked
+    /// rather than moved or reallocated.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    /// Sort the linked list in place with a stable merge sort, using a
+    /// comparator.
+    ///
+    /// The sort is bottom-up: each pass merges neighbouring sorted runs of
+    /// `width` nodes, doubling `width` until one run is left. If `compare`
+    /// panics, every value is still in the list, in an unspecified order.
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        let length = self.length;
+        let mut relink = Relink::new(self);
+        let mut width = 1;
+        while width < length {
+            relink.rest = relink.done.take();
+            while relink.rest.is_some() {
+                // SAFETY: `rest` is a chain of live nodes owned by `relink`;
+                // each run is cut off before the next one is.
+                unsafe {
+                    relink.left = relink.rest.take();
+                    relink.right = cut_after(relink.left, width);
+                    relink.rest = cut_after(relink.right, width);
+                    relink.merge(&mut compare);
+                }
+            }
+            width *= 2;
+        }
+    }
+
+    /// Sort the linked list in place with a stable merge sort, comparing the
+    /// keys extracted by `key`.
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut key: F) {
+        self.sort_by(|a, b| key(a).cmp(&key(b)));
+    }
+
+    /// Sort the linked list in place with a stable insertion sort.
+    ///
+    /// Values that are not smaller than the last sorted value are appended
+    /// in O(1), so a list that is already sorted takes O(n) and each value
+    /// out of place adds an O(n) walk. Prefer [`sort`](Self::sort) unless
+    /// the list is known to be nearly sorted.
+    pub fn insertion_sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.insertion_sort_by(T::cmp);
+    }
+
+    /// Sort the linked list in place with a stable insertion sort, using a
+    /// comparator.
+    pub fn insertion_sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        let mut relink = Relink::new(self);
+        relink.rest = relink.done.take();
+        while let Some(node) = relink.rest {
+            // SAFETY: `node` heads the unsorted chain and `done` is a run of
+            // live nodes; `node` is only moved once its place is found.
+            unsafe {
+                let value = &(*node.as_ptr()).value;
+                let after = match relink.done.tail {
+                    Some(tail) if compare(value, &(*tail.as_ptr()).value) == Ordering::Less => {
+                        // Insert after the last value not greater than this
+                        // one, which keeps equal values in order.
+                        let mut after = None;
+                        let mut current = relink.done.head;
+                        while let Some(candidate) = current {
+                            if compare(value, &(*candidate.as_ptr()).value) == Ordering::Less {
+                                break;
+                            }
+                            after = current;
+                            current = (*candidate.as_ptr()).next;
+                        }
+                        after
+                    }
+                    tail => tail,
+                };
+                relink.rest = (*node.as_ptr()).next;
+                relink.done.insert_after(after, node);
+            }
+        }
+    }
+
+    /// Merge the sorted values of `other` into this sorted list in O(n + m),
+    /// leaving `other` empty.
+    ///
+    /// The merge is stable: equal values keep their order, with the values
+    /// of `self` first.
+    pub fn merge(&mut self, other: &mut Self)
+    where
+        T: Ord,
+    {
+        self.merge_by(other, T::cmp);
+    }
+
+    /// Merge the values of `other` into this list, both sorted by
+    /// `compare`, leaving `other` empty.
+    ///
+    /// If the lists use different allocators, `other`'s values are moved
+    /// into new nodes from this list's allocator.
+    pub fn merge_by<F: FnMut(&T, &T) -> Ordering>(&mut self, other: &mut Self, mut compare: F) {
+        let (right, length) = other.take_nodes_for(&self.alloc);
+        self.length += length;
+        let mut relink = Relink::new(self);
+        relink.left = relink.done.take();
+        relink.right = right;
+        // SAFETY: `left` and `right` are disjoint chains owned by `relink`.
+        unsafe { relink.merge(&mut compare) };
+    }
+
+    /// Get the middle value, found in one pass with a slow pointer and a
+    /// fast pointer that moves two nodes at a time.
+    ///
+    /// For an even length this is the second of the two middle values.
+    /// O(n) time, O(1) space.
+    pub fn find_middle(&self) -> Option<&T> {
+        let mut slow = self.head?;
+        let mut fast = slow;
+        // SAFETY: every node reached is live for as long as `self` is
+        // borrowed, and `slow` never passes `fast`.
+        unsafe {
+            while let Some(next) = (*fast.as_ptr()).next {
+                slow = (*slow.as_ptr()).next?;
+                match (*next.as_ptr()).next {
+                    Some(after) => fast = after,
+                    None => break,
+                }
+            }
+            Some(&(*slow.as_ptr()).value)
+        }
+    }
+
+    /// Get the value `k` places before the tail, so `0` is the last value.
+    ///
+    /// Walks a leading pointer `k` nodes ahead, then moves both pointers
+    /// until the leader reaches the tail. O(n) time, O(1) space.
+    pub fn kth_from_end(&self, k: usize) -> Result<&T, Error> {
+        let out_of_bounds = Error::out_of_bounds(k, self.length);
+        let mut lead = self.head.ok_or(out_of_bounds)?;
+        let mut trail = lead;
+        // SAFETY: every node reached is live for as long as `self` is
+        // borrowed.
+        unsafe {
+            for _ in 0..k {
+                lead = (*lead.as_ptr()).next.ok_or(out_of_bounds)?;
+            }
+            while let Some(next) = (*lead.as_ptr()).next {
+                lead = next;
+                trail = (*trail.as_ptr()).next.expect("trail is behind lead");
+            }
+            Ok(&(*trail.as_ptr()).value)
+        }
+    }
+
+    /// Check if the values read the same forwards and backwards.
+    ///
+    /// A fast iterator finds the middle while the first half is pushed onto
+    /// a stack, then the second half is checked against the stack. O(n)
+    /// time, O(n) space.
+    pub fn is_palindrome(&self) -> bool
+    where
+        T: PartialEq,
+    {
+        let mut slow = self.iter();
+        let mut fast = self.iter();
+        let mut first_half = Vec::with_capacity(self.length / 2);
+        loop {
+            match (fast.next(), fast.next()) {
+                (Some(_), Some(_)) => first_half.extend(slow.next()),
+                (Some(_), None) => {
+                    // Odd length: the middle value pairs with itself.
+                    slow.next();
+                    break;
+                }
+                _ => break,
+            }
+        }
+        slow.eq(first_half.into_iter().rev())
+    }
+
+    /// Move every value less than `pivot` in front of the others, keeping
+    /// the relative order within both groups. O(n) time, O(1) space.
+    pub fn partition(&mut self, pivot: &T)
+    where
+        T: Ord,
+    {
+        self.partition_by(|value| value < pivot);
+    }
+
+    /// Move every value matching `predicate` in front of the others,
+    /// keeping the relative order within both groups, by relinking nodes.
+    /// O(n) time, O(1) space.
+    pub fn partition_by<P: FnMut(&T) -> bool>(&mut self, mut predicate: P) {
+        let mut relink = Relink::new(self);
+        relink.rest = relink.done.take();
+        while let Some(node) = relink.rest {
+            // SAFETY: `node` heads the unvisited chain; it is moved onto the
+            // end of one of the two runs only after `predicate` returns.
+            unsafe {
+                let front = predicate(&(*node.as_ptr()).value);
+                relink.rest = (*node.as_ptr()).next;
+                if front {
+                    relink.done.push(node);
+                } else {
+                    relink.merged.push(node);
+                }
+            }
+        }
+    }
+
+    /// Rotate the list left by `k` places, so the value at index `k % len`
+    /// becomes the first. O(n) time, O(1) space.
+    pub fn rotate_left(&mut self, k: usize) {
+        let (Some(head), Some(tail)) = (self.head, self.tail) else {
+            return;
+        };
+        let k = k % self.length;
+        if k == 0 {
+            return;
+        }
+        let Some(new_tail) = self.node_at(k - 1) else {
+            return;
+        };
+        // SAFETY: `new_tail` is not the tail, so it has a successor; the
+        // old tail is linked to the old head to close the chain before it
+        // is cut after `new_tail`.
+        unsafe {
+            (*tail.as_ptr()).next = Some(head);
+            self.head = (*new_tail.as_ptr()).next.take();
+        }
+        self.tail = Some(new_tail);
+    }
+
+    /// Rotate the list right by `k` places, so the last `k % len` values
+    /// move to the front. O(n) time, O(1) space.
+    pub fn rotate_right(&mut self, k: usize) {
+        if self.length != 0 {
+            self.rotate_left(self.length - k % self.length);
+        }
+    }
+
+    /// Reverse each group of `k` consecutive values in place. A final group
+    /// shorter than `k` keeps its order. O(n) time, O(1) space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn reverse_in_groups(&mut self, k: usize) {
+        assert!(k != 0, "group size must be non-zero");
+        let mut done = Run::new();
+        let mut rest = self.head.take();
+        // SAFETY: the list's nodes are split into disjoint chains, each
+        // reversed or kept whole and appended to `done`, which then becomes
+        // the list again.
+        unsafe {
+            for _ in 0..self.length / k {
+                let group = rest;
+                rest = cut_after(group, k);
+                let mut reversed = Run::new();
+                let mut current = group;
+                while let Some(node) = current {
+                    current = (*node.as_ptr()).next;
+                    reversed.insert_after(None, node);
+                }
+                done.append(&mut reversed);
+            }
+            done.append_chain(rest);
         }
-        if self.head.as_ref().unwrap().next.is_none() {
-            return self.remove_from_head();
+        self.head = done.head;
+        self.tail = done.tail;
+    }
+
+    /// Remove consecutive repeated values, so a sorted list ends up with no
+    /// duplicates. O(n) time, O(1) space.
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        let mut previous: Option<*const T> = None;
+        let mut cursor = self.cursor_front_mut();
+        while let Some(value) = cursor.current() {
+            let value: *const T = value;
+            // SAFETY: `previous` points at a value that was kept, and kept
+            // nodes are neither moved nor freed while the cursor walks on.
+            if previous.is_some_and(|previous| unsafe { *previous == *value }) {
+                cursor.remove_current();
+            } else {
+                previous = Some(value);
+                cursor.move_next();
+            }
         }
-        let mut current = self.head.as_mut().unwrap();  
-        while let Some(ref next) = current.next {  
-            if next.next.is_none() {  
-                let tail = current.next.take().unwrap();  
-                self.length -= 1;
-                return Some(tail.value);  
+    }
+
+    /// Remove every value equal to an earlier one, keeping first
+    /// occurrences in order. O(n) expected time, O(n) space for the set of
+    /// values seen.
+    #[cfg(feature = "std")]
+    pub fn remove_duplicates(&mut self)
+    where
+        T: Eq + core::hash::Hash,
+    {
+        let mut seen = std::collections::HashSet::with_capacity(self.length);
+        let mut cursor = self.cursor_front_mut();
+        while let Some(value) = cursor.current() {
+            // SAFETY: only values that are kept enter `seen`, and kept nodes
+            // are neither moved nor freed before `seen` is dropped.
+            let value: &T = unsafe { &*(value as *const T) };
+            if seen.insert(value) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
             }
-            current = current.next.as_mut().unwrap();  
         }
-        None  
     }
-    // remove from index 
-    pub fn remove_from_index(&mut self, index: usize) -> Result<T, LinkedListError> {
+
+    /// Remove every value equal to an earlier one, keeping first
+    /// occurrences in order, for values that are only ordered. O(n log n)
+    /// time, O(n) space for the set of values seen.
+    pub fn remove_duplicates_ord(&mut self)
+    where
+        T: Ord,
+    {
+        let mut seen = alloc::collections::BTreeSet::new();
+        let mut cursor = self.cursor_front_mut();
+        while let Some(value) = cursor.current() {
+            // SAFETY: only values that are kept enter `seen`, and kept nodes
+            // are neither moved nor freed before `seen` is dropped.
+            let value: &T = unsafe { &*(value as *const T) };
+            if seen.insert(value) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
+            }
+        }
+    }
+
+    /// Get a reference to the value at index
+    pub fn get(&self, index: usize) -> Result<&T, Error> {
+        match self.node_at(index) {
+            // SAFETY: the node is live for as long as `self` is borrowed.
+            Some(node) => Ok(unsafe { &(*node.as_ptr()).value }),
+            None => Err(Error::out_of_bounds(index, self.length)),
+        }
+    }
+
+    /// Get a mutable reference to the value at index
+    pub fn get_mut(&mut self, index: usize) -> Result<&mut T, Error> {
+        match self.node_at(index) {
+            // SAFETY: the node is live and uniquely borrowed through `self`.
+            Some(node) => Ok(unsafe { &mut (*node.as_ptr()).value }),
+            None => Err(Error::out_of_bounds(index, self.length)),
+        }
+    }
+
+    /// Get a reference to the first value
+    pub fn peek_front(&self) -> Option<&T> {
+        // SAFETY: the node is live for as long as `self` is borrowed.
+        self.head.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Get a mutable reference to the first value
+    pub fn peek_front_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is live and uniquely borrowed through `self`.
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Get a reference to the last value in O(1)
+    pub fn peek_back(&self) -> Option<&T> {
+        // SAFETY: the node is live for as long as `self` is borrowed.
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Get a mutable reference to the last value in O(1)
+    pub fn peek_back_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is live and uniquely borrowed through `self`.
+        self.tail.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Find the first value matching the predicate
+    pub fn find<P: FnMut(&T) -> bool>(&self, mut predicate: P) -> Option<&T> {
+        self.iter().find(|value| predicate(value))
+    }
+
+    /// Check if the linked list contains a value
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.find(|candidate| candidate == value).is_some()
+    }
+
+    /// Remove every value from the linked list
+    pub fn clear(&mut self) {
+        while self.remove_from_head().is_some() {}
+    }
+
+    /// Iterate over the values from head to tail
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head,
+            remaining: self.length,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Iterate mutably over the values from head to tail
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head,
+            remaining: self.length,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get a cursor positioned on the first value
+    pub fn cursor_front(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self,
+            current: self.head,
+            index: 0,
+        }
+    }
+
+    /// Get a mutable cursor positioned on the first value
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            current: self.head,
+            prev: None,
+            index: 0,
+            list: self,
+        }
+    }
+
+    /// Walk to the node at `index`
+    fn node_at(&self, index: usize) -> Link<T> {
         if index >= self.length {
-            return Err(LinkedListError::IndexOutOfBounds);
+            return None;
         }
-        if index == 0 {
-            return self.remove_from_head().ok_or(LinkedListError::EmptyList);
+        if index == self.length - 1 {
+            return self.tail;
+        }
+        let mut current = self.head;
+        for _ in 0..index {
+            // SAFETY: `index < length`, so every node on the way is live and
+            // has a successor.
+            current = unsafe { (*current?.as_ptr()).next };
+        }
+        current
+    }
+
+    /// Give up ownership of the nodes, returning the head and length of a
+    /// chain that `alloc` can free. The nodes are moved into new ones from
+    /// `alloc` unless it is the same as the list's allocator.
+    fn take_nodes_for(&mut self, alloc: &A) -> (Link<T>, usize) {
+        if self.alloc.same_as(alloc) {
+            self.tail = None;
+            return (self.head.take(), core::mem::take(&mut self.length));
+        }
+        let (mut head, mut tail, mut length): (Link<T>, Link<T>, _) = (None, None, 0);
+        while let Some(value) = self.remove_from_head() {
+            let node = Node::alloc(alloc, value, None);
+            match tail {
+                // SAFETY: `tail` is the live end of the chain built so far.
+                Some(tail) => unsafe { (*tail.as_ptr()).next = Some(node) },
+                None => head = Some(node),
+            }
+            tail = Some(node);
+            length += 1;
+        }
+        (head, length)
+    }
+}
+
+/// A chain of nodes from `head` to `tail`, where `tail.next` is `None`
+struct Run<T> {
+    head: Link<T>,
+    tail: Link<T>,
+}
+
+impl<T> Run<T> {
+    /// Create an empty run
+    fn new() -> Self {
+        Self {
+            head: None,
+            tail: None,
+        }
+    }
+
+    /// Take the nodes out of the run, leaving it empty
+    fn take(&mut self) -> Link<T> {
+        self.tail = None;
+        self.head.take()
+    }
+
+    /// Link a node in after `after`, or at the front if `after` is `None`
+    ///
+    /// # Safety
+    ///
+    /// `node` must be live and owned by the caller, and `after` must be a
+    /// node of this run.
+    unsafe fn insert_after(&mut self, after: Link<T>, node: NonNull<Node<T>>) {
+        match after {
+            Some(after) => {
+                (*node.as_ptr()).next = (*after.as_ptr()).next;
+                (*after.as_ptr()).next = Some(node);
+            }
+            None => {
+                (*node.as_ptr()).next = self.head;
+                self.head = Some(node);
+            }
+        }
+        if after == self.tail {
+            self.tail = Some(node);
+        }
+    }
+
+    /// Append a single node
+    ///
+    /// # Safety
+    ///
+    /// `node` must be live, owned by the caller and not part of this run.
+    unsafe fn push(&mut self, node: NonNull<Node<T>>) {
+        self.insert_after(self.tail, node);
+    }
+
+    /// Append a `None`-terminated chain, walking it to find the new tail
+    ///
+    /// # Safety
+    ///
+    /// `chain` must be live nodes owned by the caller and disjoint from
+    /// this run.
+    unsafe fn append_chain(&mut self, chain: Link<T>) {
+        let Some(mut last) = chain else {
+            return;
+        };
+        match self.tail {
+            Some(tail) => (*tail.as_ptr()).next = chain,
+            None => self.head = chain,
+        }
+        while let Some(next) = (*last.as_ptr()).next {
+            last = next;
+        }
+        self.tail = Some(last);
+    }
+
+    /// Append another run in O(1), leaving it empty
+    ///
+    /// # Safety
+    ///
+    /// `other` must be disjoint from this run.
+    unsafe fn append(&mut self, other: &mut Run<T>) {
+        let Some(tail) = other.tail else {
+            return;
+        };
+        match self.tail {
+            Some(last) => (*last.as_ptr()).next = other.head,
+            None => self.head = other.head,
+        }
+        self.tail = Some(tail);
+        other.take();
+    }
+}
+
+/// Cut a chain after its first `count` nodes, returning the rest
+///
+/// # Safety
+///
+/// `chain` must be a `None`-terminated chain of live nodes.
+unsafe fn cut_after<T>(chain: Link<T>, count: usize) -> Link<T> {
+    let mut last = chain?;
+    for _ in 1..count {
+        last = (*last.as_ptr()).next?;
+    }
+    (*last.as_ptr()).next.take()
+}
+
+/// The nodes of a list while a sort or merge relinks them.
+///
+/// Each node sits in exactly one of the chains. Dropping this links all of
+/// them back into the list, so a panicking comparator leaves the list valid
+/// (in an unspecified order) instead of leaking or losing nodes.
+struct Relink<'a, T, A: Allocator> {
+    list: &'a mut LinkedList<T, A>,
+    /// Nodes already in their final order for this pass
+    done: Run<T>,
+    /// The merge in progress, or the values after the pivot of a partition
+    merged: Run<T>,
+    left: Link<T>,
+    right: Link<T>,
+    /// Nodes not yet looked at in this pass
+    rest: Link<T>,
+}
+
+impl<'a, T, A: Allocator> Relink<'a, T, A> {
+    /// Take every node of `list` into `done`
+    fn new(list: &'a mut LinkedList<T, A>) -> Self {
+        let done = Run {
+            head: list.head.take(),
+            tail: list.tail.take(),
+        };
+        Self {
+            list,
+            done,
+            merged: Run::new(),
+            left: None,
+            right: None,
+            rest: None,
+        }
+    }
+
+    /// Stably merge `left` and `right` onto the end of `done`
+    ///
+    /// # Safety
+    ///
+    /// `left` and `right` must be disjoint `None`-terminated chains owned by
+    /// this `Relink`.
+    unsafe fn merge<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: &mut F) {
+        while let (Some(left), Some(right)) = (self.left, self.right) {
+            // Take from the right only when strictly smaller, for stability.
+            let node =
+                if compare(&(*right.as_ptr()).value, &(*left.as_ptr()).value) == Ordering::Less {
+                    self.right = (*right.as_ptr()).next;
+                    right
+                } else {
+                    self.left = (*left.as_ptr()).next;
+                    left
+                };
+            self.merged.push(node);
+        }
+        self.merged.append_chain(self.left.take());
+        self.merged.append_chain(self.right.take());
+        self.done.append(&mut self.merged);
+    }
+}
+
+impl<T, A: Allocator> Drop for Relink<'_, T, A> {
+    fn drop(&mut self) {
+        let mut all = Run::new();
+        // SAFETY: the chains are disjoint and together hold every node of
+        // the list, so relinking them restores its `head`/`tail` invariant.
+        // `length` never changes while nodes are relinked.
+        unsafe {
+            all.append(&mut self.done);
+            all.append(&mut self.merged);
+            all.append_chain(self.left.take());
+            all.append_chain(self.right.take());
+            all.append_chain(self.rest.take());
+        }
+        self.list.head = all.head;
+        self.list.tail = all.tail;
+    }
+}
+
+impl<T, A: Allocator> Drop for LinkedList<T, A> {
+    fn drop(&mut self) {
+        // Free nodes one at a time so long lists don't drop recursively.
+        self.clear();
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for LinkedList<T, A> {
+    fn clone(&self) -> Self {
+        let mut list = Self::new_in(self.alloc.clone());
+        list.extend(self.iter().cloned());
+        list
+    }
+}
+
+impl<T: core::fmt::Debug, A: Allocator> core::fmt::Debug for LinkedList<T, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq for LinkedList<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for LinkedList<T, A> {}
+
+impl<T: core::hash::Hash, A: Allocator> core::hash::Hash for LinkedList<T, A> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(self.length);
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
+impl<T, A: Allocator + Default> FromIterator<T> for LinkedList<T, A> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::default();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for LinkedList<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert_at_tail(value);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator> Extend<&'a T> for LinkedList<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for LinkedList<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a LinkedList<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut LinkedList<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// A read-only cursor over a [`LinkedList`].
+///
+/// The cursor rests either on a value or on the "ghost" position past the
+/// tail, where [`current`](Cursor::current) returns `None`. Moving past the
+/// ghost wraps around to the head.
+pub struct Cursor<'a, T, A: Allocator = Global> {
+    list: &'a LinkedList<T, A>,
+    current: Link<T>,
+    index: usize,
+}
+
+impl<'a, T, A: Allocator> Cursor<'a, T, A> {
+    /// Get the index of the current value, or `None` on the ghost position
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Get the current value
+    pub fn current(&self) -> Option<&'a T> {
+        // SAFETY: the list is borrowed for `'a`, so the node stays live.
+        self.current.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Get the value after the current one without moving
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            // SAFETY: the list is borrowed for `'a`, so the node stays live.
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+        // SAFETY: as above.
+        next.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Move to the next value, or from the tail onto the ghost position
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => {
+                // SAFETY: the list is borrowed for `'a`, so the node stays live.
+                self.current = unsafe { (*node.as_ptr()).next };
+                self.index += 1;
+            }
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+}
+
+impl<T, A: Allocator> Clone for Cursor<'_, T, A> {
+    fn clone(&self) -> Self {
+        Self {
+            list: self.list,
+            current: self.current,
+            index: self.index,
+        }
+    }
+}
+
+/// A cursor over a [`LinkedList`] that can edit the list around it.
+///
+/// Like [`Cursor`], it rests on a value or on the ghost position past the
+/// tail. It remembers the node before it, so inserting before the cursor and
+/// removing the current value are O(1).
+pub struct CursorMut<'a, T, A: Allocator = Global> {
+    list: &'a mut LinkedList<T, A>,
+    /// The node before `current`; on the ghost position this is the tail
+    prev: Link<T>,
+    current: Link<T>,
+    index: usize,
+}
+
+impl<T, A: Allocator> CursorMut<'_, T, A> {
+    /// Get the index of the current value, or `None` on the ghost position
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Get the current value
+    pub fn current(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is live and uniquely borrowed through the cursor.
+        self.current
+            .map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Get the value after the current one without moving
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            // SAFETY: the node is live while the list is borrowed.
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+        // SAFETY: the node is live and uniquely borrowed through the cursor.
+        next.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Move to the next value, or from the tail onto the ghost position
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.prev = Some(node);
+                // SAFETY: the node is live while the list is borrowed.
+                self.current = unsafe { (*node.as_ptr()).next };
+                self.index += 1;
+            }
+            None => {
+                self.prev = None;
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    /// Get a read-only cursor at the same position
+    pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self.list,
+            current: self.current,
+            index: self.index,
+        }
+    }
+
+    /// Insert a value before the current one, keeping the cursor on the
+    /// current value. On the ghost position this appends to the list.
+    pub fn insert_before(&mut self, value: T) {
+        let node = Node::alloc(&self.list.alloc, value, self.current);
+        self.link_after_prev(Some(node));
+        if self.current.is_none() {
+            self.list.tail = Some(node);
+        }
+        self.prev = Some(node);
+        self.index += 1;
+        self.list.length += 1;
+    }
+
+    /// Insert a value after the current one, keeping the cursor on the
+    /// current value. On the ghost position this prepends to the list.
+    pub fn insert_after(&mut self, value: T) {
+        match self.current {
+            Some(current) => {
+                // SAFETY: `current` is a live node of the list; the new node
+                // is linked directly after it.
+                unsafe {
+                    let node = Node::alloc(&self.list.alloc, value, (*current.as_ptr()).next);
+                    (*current.as_ptr()).next = Some(node);
+                    if self.list.tail == Some(current) {
+                        self.list.tail = Some(node);
+                    }
+                }
+                self.list.length += 1;
+            }
+            None => {
+                self.list.insert_at_head(value);
+                self.prev = self.list.tail;
+                self.index = self.list.length;
+            }
+        }
+    }
+
+    /// Remove the current value, moving the cursor to the next one.
+    ///
+    /// Returns `None`, leaving the list untouched, on the ghost position.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        // SAFETY: `node` is a live node of the list; it is unlinked from its
+        // predecessor (or the head) before being freed.
+        unsafe {
+            let next = (*node.as_ptr()).next;
+            self.link_after_prev(next);
+            if self.list.tail == Some(node) {
+                self.list.tail = self.prev;
+            }
+            self.current = next;
+            self.list.length -= 1;
+            Some(Node::free(&self.list.alloc, node))
+        }
+    }
+
+    /// Point the link after `prev` (or the head) at `link`
+    fn link_after_prev(&mut self, link: Link<T>) {
+        match self.prev {
+            // SAFETY: `prev` is a live node of the list.
+            Some(prev) => unsafe { (*prev.as_ptr()).next = link },
+            None => self.list.head = link,
+        }
+    }
+}
+
+/// A borrowing iterator over the values of a [`LinkedList`]
+pub struct Iter<'a, T> {
+    next: Link<T>,
+    remaining: usize,
+    _marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.next?;
+        // SAFETY: the list is borrowed for `'a`, so the node stays live.
+        let node = unsafe { &*node.as_ptr() };
+        self.next = node.next;
+        self.remaining -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+// SAFETY: the iterator only hands out `&T`, like `&LinkedList<T>` does.
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+// SAFETY: as above.
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> core::iter::FusedIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            next: self.next,
+            remaining: self.remaining,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A mutable iterator over the values of a [`LinkedList`]
+pub struct IterMut<'a, T> {
+    next: Link<T>,
+    remaining: usize,
+    _marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        let node = self.next?;
+        // SAFETY: the list is uniquely borrowed for `'a` and each node is
+        // yielded once, so the references never alias.
+        let node = unsafe { &mut *node.as_ptr() };
+        self.next = node.next;
+        self.remaining -= 1;
+        Some(&mut node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+// SAFETY: the iterator hands out `&mut T`, like `&mut LinkedList<T>` does.
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+// SAFETY: shared access to the iterator gives no access to the values.
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> core::iter::FusedIterator for IterMut<'_, T> {}
+
+/// An owning iterator over the values of a [`LinkedList`]
+pub struct IntoIter<T, A: Allocator = Global> {
+    list: LinkedList<T, A>,
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.remove_from_head()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.length, Some(self.list.length))
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> core::iter::FusedIterator for IntoIter<T, A> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::{Bump, Counting};
+    use core::mem;
+
+    fn list_of(values: &[i32]) -> LinkedList<i32> {
+        let mut list = LinkedList::new();
+        for &value in values {
+            list.insert_at_tail(value);
+        }
+        list
+    }
+
+    fn to_vec(list: &LinkedList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_linked_list() {
+        let mut list = LinkedList::new();
+        assert!(list.is_empty());
+        list.insert_at_head(1);
+        list.insert_at_tail(2);
+        list.insert_at_index(1, 3).unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.get(0).unwrap(), &1);
+        assert_eq!(list.get(1).unwrap(), &3);
+        assert_eq!(list.get(2).unwrap(), &2);
+        assert_eq!(list.remove_from_index(1).unwrap(), 3);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.remove_from_head().unwrap(), 1);
+        assert_eq!(list.remove_from_tail().unwrap(), 2);
+        assert!(list.is_empty());
+        list.insert_at_head(4);
+        list.insert_at_tail(5);
+        list.reverse();
+        assert_eq!(list.get(0).unwrap(), &5);
+        assert_eq!(list.get(1).unwrap(), &4);
+    }
+
+    #[test]
+    fn test_new() {
+        let list: LinkedList<i32> = LinkedList::new();
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+        assert_eq!(list.peek_front(), None);
+        let list: LinkedList<i32> = Default::default();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_insert_at_head() {
+        let mut list = LinkedList::new();
+        list.insert_at_head(1);
+        list.insert_at_head(2);
+        assert_eq!(to_vec(&list), [2, 1]);
+    }
+
+    #[test]
+    fn test_insert_at_tail() {
+        let mut list = LinkedList::new();
+        list.insert_at_tail(1);
+        list.insert_at_tail(2);
+        assert_eq!(to_vec(&list), [1, 2]);
+    }
+
+    #[test]
+    fn test_insert_at_index() {
+        let mut list = list_of(&[1, 3]);
+        list.insert_at_index(0, 0).unwrap();
+        list.insert_at_index(2, 2).unwrap();
+        list.insert_at_index(4, 4).unwrap();
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4]);
+        assert_eq!(list.insert_at_index(6, 6), Err(Error::out_of_bounds(6, 5)));
+    }
+
+    #[test]
+    fn test_remove_from_head() {
+        let mut list = list_of(&[1, 2]);
+        assert_eq!(list.remove_from_head(), Some(1));
+        assert_eq!(list.remove_from_head(), Some(2));
+        assert_eq!(list.remove_from_head(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn test_remove_from_tail() {
+        let mut list = list_of(&[1, 2, 3]);
+        assert_eq!(list.remove_from_tail(), Some(3));
+        assert_eq!(list.remove_from_tail(), Some(2));
+        assert_eq!(list.remove_from_tail(), Some(1));
+        assert_eq!(list.remove_from_tail(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_remove_from_index() {
+        let mut list = list_of(&[1, 2, 3, 4]);
+        assert_eq!(list.remove_from_index(3), Ok(4));
+        assert_eq!(list.remove_from_index(0), Ok(1));
+        assert_eq!(list.remove_from_index(2), Err(Error::out_of_bounds(2, 2)));
+        assert_eq!(to_vec(&list), [2, 3]);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list = list_of(&[1, 2, 3, 4]);
+        list.reverse();
+        assert_eq!(to_vec(&list), [4, 3, 2, 1]);
+        let mut empty: LinkedList<i32> = LinkedList::new();
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_get() {
+        let list = list_of(&[10, 20]);
+        assert_eq!(list.get(0), Ok(&10));
+        assert_eq!(list.get(1), Ok(&20));
+        assert_eq!(list.get(2), Err(Error::out_of_bounds(2, 2)));
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut list = list_of(&[10, 20]);
+        *list.get_mut(1).unwrap() = 42;
+        assert_eq!(list.get(1), Ok(&42));
+        assert_eq!(list.get_mut(2), Err(Error::out_of_bounds(2, 2)));
+    }
+
+    #[test]
+    fn test_peek_front() {
+        let mut list = list_of(&[1, 2]);
+        assert_eq!(list.peek_front(), Some(&1));
+        *list.peek_front_mut().unwrap() = 10;
+        assert_eq!(to_vec(&list), [10, 2]);
+    }
+
+    #[test]
+    fn test_peek_back() {
+        let mut list = list_of(&[1, 2]);
+        assert_eq!(list.peek_back(), Some(&2));
+        *list.peek_back_mut().unwrap() = 20;
+        assert_eq!(to_vec(&list), [1, 20]);
+        let mut empty: LinkedList<i32> = LinkedList::new();
+        assert_eq!(empty.peek_back(), None);
+        assert_eq!(empty.peek_back_mut(), None);
+    }
+
+    #[test]
+    fn test_find() {
+        let list = list_of(&[1, 4, 6, 9]);
+        assert_eq!(list.find(|v| v % 2 == 0), Some(&4));
+        assert_eq!(list.find(|v| *v > 10), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        let list = list_of(&[1, 2, 3]);
+        assert!(list.contains(&2));
+        assert!(!list.contains(&5));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut list = list_of(&[1, 2, 3]);
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.peek_front(), None);
+        list.insert_at_tail(4);
+        assert_eq!(to_vec(&list), [4]);
+    }
+
+    // The tests below exercise the raw-pointer bookkeeping and are meant to
+    // be run under Miri as well: `cargo +nightly miri test`.
+
+    #[test]
+    fn test_tail_tracks_every_edit() {
+        let mut list = list_of(&[1, 2, 3]);
+        assert_eq!(list.remove_from_index(2), Ok(3));
+        assert_eq!(list.peek_back(), Some(&2));
+        list.insert_at_tail(4);
+        assert_eq!(list.remove_from_tail(), Some(4));
+        assert_eq!(list.peek_back(), Some(&2));
+        list.insert_at_index(2, 5).unwrap();
+        assert_eq!(list.peek_back(), Some(&5));
+        list.reverse();
+        assert_eq!(list.peek_back(), Some(&1));
+        list.insert_at_tail(6);
+        assert_eq!(to_vec(&list), [5, 2, 1, 6]);
+        while list.remove_from_head().is_some() {}
+        assert_eq!(list.peek_back(), None);
+        list.insert_at_head(7);
+        assert_eq!(list.peek_back(), Some(&7));
+        list.insert_at_tail(8);
+        assert_eq!(to_vec(&list), [7, 8]);
+    }
+
+    #[test]
+    fn test_queue_usage() {
+        let mut queue = LinkedList::new();
+        let mut next_out = 0;
+        for i in 0..1000 {
+            queue.insert_at_tail(i);
+            if i % 2 == 1 {
+                assert_eq!(queue.remove_from_head(), Some(next_out));
+                next_out += 1;
+            }
+        }
+        assert_eq!(queue.len(), 500);
+        assert_eq!(queue.peek_front(), Some(&500));
+        assert_eq!(queue.peek_back(), Some(&999));
+    }
+
+    #[test]
+    fn test_owned_values_are_dropped() {
+        use std::rc::Rc;
+
+        let tracker = Rc::new(());
+        let mut list = LinkedList::new();
+        for _ in 0..10 {
+            list.insert_at_tail(Rc::clone(&tracker));
+        }
+        list.remove_from_index(4).unwrap();
+        list.remove_from_tail();
+        assert_eq!(Rc::strong_count(&tracker), 9);
+        drop(list);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn test_zero_sized_values() {
+        let mut list = LinkedList::new();
+        for _ in 0..5 {
+            list.insert_at_tail(());
         }
-        let mut current = self.head.as_mut();
-        for _ in 0..index - 1 {
-            if let Some(node) = current {
-                current = node.next.as_mut();
+        assert_eq!(list.remove_from_tail(), Some(()));
+        assert_eq!(list.remove_from_index(1), Ok(()));
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_clone() {
+        let list = list_of(&[1, 2, 3]);
+        let mut cloned = list.clone();
+        assert_eq!(to_vec(&cloned), [1, 2, 3]);
+        cloned.insert_at_tail(4);
+        assert_eq!(cloned.peek_back(), Some(&4));
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(format!("{:?}", list_of(&[1, 2, 3])), "[1, 2, 3]");
+        assert_eq!(format!("{:?}", LinkedList::<i32>::new()), "[]");
+    }
+
+    #[test]
+    fn test_eq() {
+        assert_eq!(list_of(&[1, 2, 3]), list_of(&[1, 2, 3]));
+        assert_ne!(list_of(&[1, 2, 3]), list_of(&[1, 2]));
+        assert_ne!(list_of(&[1, 2, 3]), list_of(&[1, 2, 4]));
+        assert_eq!(LinkedList::<i32>::new(), LinkedList::new());
+    }
+
+    /// Run `f` on a thread with a small stack, so any recursion proportional
+    /// to the list length overflows quickly instead of passing by luck
+    fn with_small_stack<F: FnOnce() + Send + 'static>(f: F) {
+        std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(f)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    const DEEP: usize = 2_000_000;
+
+    fn deep_list() -> LinkedList<usize> {
+        let mut list = LinkedList::new();
+        for i in 0..DEEP {
+            list.insert_at_tail(i);
+        }
+        list
+    }
+
+    #[test]
+    fn test_drop_long_list() {
+        with_small_stack(|| drop(deep_list()));
+    }
+
+    #[test]
+    fn test_clear_long_list() {
+        with_small_stack(|| {
+            let mut list = deep_list();
+            list.clear();
+            assert!(list.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_clone_and_eq_long_list() {
+        with_small_stack(|| {
+            let list = deep_list();
+            let mut cloned = list.clone();
+            assert!(cloned == list);
+            cloned.insert_at_tail(0);
+            assert!(cloned != list);
+            cloned.remove_from_head();
+            cloned.insert_at_head(0);
+            assert!(cloned != list);
+        });
+    }
+
+    #[test]
+    fn test_debug_long_list() {
+        use std::fmt::Write;
+
+        /// Counts formatted bytes without storing them
+        struct Counter(usize);
+
+        impl Write for Counter {
+            fn write_str(&mut self, s: &str) -> std::fmt::Result {
+                self.0 += s.len();
+                Ok(())
+            }
+        }
+
+        with_small_stack(|| {
+            let mut list = LinkedList::new();
+            for _ in 0..DEEP {
+                list.insert_at_head(0u8);
             }
+            let mut counter = Counter(0);
+            write!(counter, "{list:?}").unwrap();
+            // "[" + "0, " for every value but the last + "0]"
+            assert_eq!(counter.0, 3 * DEEP);
+        });
+    }
+
+    #[test]
+    fn test_iter() {
+        let list = list_of(&[1, 2, 3]);
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), None);
+        assert_eq!((&list).into_iter().sum::<i32>(), 6);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut list = list_of(&[1, 2, 3]);
+        for value in list.iter_mut() {
+            *value *= 10;
         }
-        if let Some(node) = current {
-            if let Some(mut to_remove) = node.next.take() {
-                node.next = to_remove.next.take();
-                self.length -= 1;
-                return Ok(to_remove.value);
+        for value in &mut list {
+            *value += 1;
+        }
+        assert_eq!(to_vec(&list), [11, 21, 31]);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let list: LinkedList<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next().as_deref(), Some("a"));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.collect::<Vec<_>>(), ["b", "c"]);
+    }
+
+    #[test]
+    fn test_from_iterator_and_extend() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        list.extend(vec![4, 5]);
+        list.extend(&[6]);
+        assert_eq!(to_vec(&list), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(list.peek_back(), Some(&6));
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let set: HashSet<LinkedList<i32>> = [list_of(&[1, 2]), list_of(&[1, 2]), list_of(&[2, 1])]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_cursor() {
+        let list = list_of(&[1, 2, 3]);
+        let mut cursor = list.cursor_front();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&1));
+        assert_eq!(cursor.peek_next(), Some(&2));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&3));
+        assert_eq!(cursor.peek_next(), None);
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&1));
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(0));
+    }
+
+    #[test]
+    fn test_cursor_on_empty_list() {
+        let mut list: LinkedList<i32> = LinkedList::new();
+        assert_eq!(list.cursor_front().current(), None);
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.remove_current(), None);
+        cursor.insert_before(1);
+        cursor.insert_after(0);
+        assert_eq!(to_vec(&list), [0, 1]);
+        assert_eq!(list.peek_back(), Some(&1));
+    }
+
+    #[test]
+    fn test_cursor_mut_edits() {
+        let mut list = list_of(&[1, 3, 5]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_before(2);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.insert_after(4);
+        *cursor.current().unwrap() *= 10;
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(cursor.current(), Some(&mut 5));
+        assert_eq!(cursor.as_cursor().peek_next(), None);
+        assert_eq!(to_vec(&list), [1, 2, 30, 5]);
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn test_cursor_mut_keeps_tail_in_sync() {
+        let mut list = list_of(&[1, 2]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_after(3);
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.index(), None);
+        cursor.insert_before(4);
+        cursor.insert_before(5);
+        assert_eq!(list.peek_back(), Some(&5));
+        list.insert_at_tail(6);
+        assert_eq!(to_vec(&list), [1, 2, 4, 5, 6]);
+
+        let mut cursor = list.cursor_front_mut();
+        while cursor.remove_current().is_some() {}
+        assert!(list.is_empty());
+        assert_eq!(list.peek_back(), None);
+        list.insert_at_tail(7);
+        assert_eq!(to_vec(&list), [7]);
+    }
+
+    #[test]
+    fn test_cursor_mut_filter() {
+        let mut list: LinkedList<i32> = (1..=10).collect();
+        let mut cursor = list.cursor_front_mut();
+        while let Some(value) = cursor.current() {
+            if *value % 3 == 0 {
+                cursor.remove_current();
+            } else {
+                cursor.move_next();
             }
         }
-        Err(LinkedListError::InvalidIndex)
+        assert_eq!(to_vec(&list), [1, 2, 4, 5, 7, 8, 10]);
+        assert_eq!(list.peek_back(), Some(&10));
+    }
+
+    /// Deterministic pseudo-random values for sorting tests
+    fn scrambled(len: usize, modulus: i32) -> Vec<i32> {
+        let mut state: u32 = 0x2545_f491;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (state >> 16) as i32 % modulus
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        for len in [0, 1, 2, 3, 7, 8, 9, 100, 1_000] {
+            let values = scrambled(len, 50);
+            let mut list = list_of(&values);
+            list.sort();
+            let mut expected = values.clone();
+            expected.sort();
+            assert_eq!(to_vec(&list), expected);
+            assert_eq!(list.len(), len);
+            assert_eq!(list.peek_back(), expected.last());
+            list.insert_at_tail(i32::MAX);
+            assert_eq!(list.peek_back(), Some(&i32::MAX));
+        }
+    }
+
+    #[test]
+    fn test_sort_is_stable() {
+        let values = scrambled(500, 10);
+        let mut list: LinkedList<(i32, usize)> = values.iter().copied().zip(0..).collect();
+        list.sort_by_key(|&(key, _)| key);
+        let mut expected: Vec<(i32, usize)> = values.iter().copied().zip(0..).collect();
+        expected.sort_by_key(|&(key, _)| key);
+        assert!(list.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let mut list = list_of(&[3, 1, 4, 1, 5, 9, 2, 6]);
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(to_vec(&list), [9, 6, 5, 4, 3, 2, 1, 1]);
+    }
+
+    #[test]
+    fn test_sort_relinks_without_moving_values() {
+        let mut list = list_of(&[3, 1, 2]);
+        let addresses: Vec<*const i32> = list.iter().map(|value| value as *const i32).collect();
+        list.sort();
+        let sorted: Vec<*const i32> = list.iter().map(|value| value as *const i32).collect();
+        assert_eq!(sorted, [addresses[1], addresses[2], addresses[0]]);
+    }
+
+    #[test]
+    fn test_insertion_sort() {
+        for len in [0, 1, 2, 5, 64] {
+            let values = scrambled(len, 20);
+            let mut list = list_of(&values);
+            list.insertion_sort();
+            let mut expected = values.clone();
+            expected.sort();
+            assert_eq!(to_vec(&list), expected);
+            assert_eq!(list.peek_back(), expected.last());
+        }
+        let mut pairs: LinkedList<(i32, char)> = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')]
+            .into_iter()
+            .collect();
+        pairs.insertion_sort_by(|a, b| a.0.cmp(&b.0));
+        let order: String = pairs.iter().map(|&(_, c)| c).collect();
+        assert_eq!(order, "ebdac");
+    }
+
+    #[test]
+    fn test_insertion_sort_nearly_sorted() {
+        let mut comparisons = 0;
+        let mut values: Vec<i32> = (0..1_000).collect();
+        values.swap(10, 11);
+        let mut list = list_of(&values);
+        list.insertion_sort_by(|a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+        assert!(list.iter().copied().eq(0..1_000));
+        assert!(comparisons < 1_100);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut list = list_of(&[1, 3, 5, 7]);
+        let mut other = list_of(&[2, 3, 4, 8, 9]);
+        list.merge(&mut other);
+        assert_eq!(to_vec(&list), [1, 2, 3, 3, 4, 5, 7, 8, 9]);
+        assert_eq!(list.len(), 9);
+        assert_eq!(list.peek_back(), Some(&9));
+        assert!(other.is_empty());
+        assert_eq!(other.peek_back(), None);
+
+        let mut empty = LinkedList::new();
+        empty.merge(&mut list);
+        assert_eq!(empty.len(), 9);
+        empty.merge(&mut LinkedList::new());
+        assert_eq!(empty.peek_back(), Some(&9));
+    }
+
+    #[test]
+    fn test_merge_by_is_stable() {
+        let mut list: LinkedList<(i32, char)> = [(1, 'a'), (2, 'b')].into_iter().collect();
+        let mut other: LinkedList<(i32, char)> = [(1, 'c'), (2, 'd')].into_iter().collect();
+        list.merge_by(&mut other, |a, b| a.0.cmp(&b.0));
+        let order: String = list.iter().map(|&(_, c)| c).collect();
+        assert_eq!(order, "acbd");
+    }
+
+    #[test]
+    fn test_sort_panic_keeps_every_value() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::rc::Rc;
+
+        let tracker = Rc::new(());
+        let mut list: LinkedList<(i32, Rc<()>)> = scrambled(100, 1_000)
+            .into_iter()
+            .map(|value| (value, Rc::clone(&tracker)))
+            .collect();
+        let mut comparisons = 0;
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            list.sort_by(|a, b| {
+                comparisons += 1;
+                assert!(comparisons < 300, "comparator gave up");
+                a.0.cmp(&b.0)
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(list.len(), 100);
+        assert_eq!(list.iter().count(), 100);
+        assert_eq!(Rc::strong_count(&tracker), 101);
+        list.insert_at_tail((0, Rc::clone(&tracker)));
+        list.sort_by_key(|&(value, _)| value);
+        drop(list);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn test_sort_long_list() {
+        with_small_stack(|| {
+            let mut list: LinkedList<usize> = (0..DEEP).rev().collect();
+            list.sort();
+            assert!(list.iter().copied().eq(0..DEEP));
+            assert_eq!(list.peek_back(), Some(&(DEEP - 1)));
+        });
+    }
+
+    #[test]
+    fn test_find_middle() {
+        assert_eq!(list_of(&[]).find_middle(), None);
+        assert_eq!(list_of(&[1]).find_middle(), Some(&1));
+        assert_eq!(list_of(&[1, 2]).find_middle(), Some(&2));
+        assert_eq!(list_of(&[1, 2, 3]).find_middle(), Some(&2));
+        assert_eq!(list_of(&[1, 2, 3, 4]).find_middle(), Some(&3));
+        assert_eq!(list_of(&[1, 2, 3, 4, 5]).find_middle(), Some(&3));
+    }
+
+    #[test]
+    fn test_kth_from_end() {
+        let list = list_of(&[1, 2, 3, 4]);
+        assert_eq!(list.kth_from_end(0), Ok(&4));
+        assert_eq!(list.kth_from_end(1), Ok(&3));
+        assert_eq!(list.kth_from_end(3), Ok(&1));
+        assert_eq!(list.kth_from_end(4), Err(Error::out_of_bounds(4, 4)));
+        assert_eq!(
+            list_of(&[]).kth_from_end(0),
+            Err(Error::out_of_bounds(0, 0))
+        );
+    }
+
+    #[test]
+    fn test_is_palindrome() {
+        assert!(list_of(&[]).is_palindrome());
+        assert!(list_of(&[1]).is_palindrome());
+        assert!(list_of(&[1, 1]).is_palindrome());
+        assert!(!list_of(&[1, 2]).is_palindrome());
+        assert!(list_of(&[1, 2, 1]).is_palindrome());
+        assert!(list_of(&[1, 2, 2, 1]).is_palindrome());
+        assert!(!list_of(&[1, 2, 3, 1]).is_palindrome());
+        assert!(!list_of(&[1, 2, 3, 2, 2]).is_palindrome());
+    }
+
+    #[test]
+    fn test_partition() {
+        let mut list = list_of(&[3, 5, 8, 5, 10, 2, 1]);
+        list.partition(&5);
+        assert_eq!(to_vec(&list), [3, 2, 1, 5, 8, 5, 10]);
+        assert_eq!(list.peek_back(), Some(&10));
+
+        let mut all_before = list_of(&[1, 2]);
+        all_before.partition(&5);
+        assert_eq!(to_vec(&all_before), [1, 2]);
+        assert_eq!(all_before.peek_back(), Some(&2));
+
+        let mut evens_first = list_of(&[1, 2, 3, 4, 5, 6]);
+        evens_first.partition_by(|value| value % 2 == 0);
+        assert_eq!(to_vec(&evens_first), [2, 4, 6, 1, 3, 5]);
+        evens_first.insert_at_tail(7);
+        assert_eq!(to_vec(&evens_first), [2, 4, 6, 1, 3, 5, 7]);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        list.rotate_left(2);
+        assert_eq!(to_vec(&list), [3, 4, 5, 1, 2]);
+        assert_eq!(list.peek_back(), Some(&2));
+        list.rotate_right(2);
+        assert_eq!(to_vec(&list), [1, 2, 3, 4, 5]);
+        list.rotate_right(7);
+        assert_eq!(to_vec(&list), [4, 5, 1, 2, 3]);
+        list.rotate_left(5);
+        assert_eq!(to_vec(&list), [4, 5, 1, 2, 3]);
+        list.insert_at_tail(6);
+        assert_eq!(to_vec(&list), [4, 5, 1, 2, 3, 6]);
+
+        let mut empty = list_of(&[]);
+        empty.rotate_left(3);
+        empty.rotate_right(3);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_reverse_in_groups() {
+        let mut list = list_of(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        list.reverse_in_groups(3);
+        assert_eq!(to_vec(&list), [3, 2, 1, 6, 5, 4, 7, 8]);
+        assert_eq!(list.peek_back(), Some(&8));
+        list.reverse_in_groups(1);
+        assert_eq!(to_vec(&list), [3, 2, 1, 6, 5, 4, 7, 8]);
+        list.reverse_in_groups(8);
+        assert_eq!(to_vec(&list), [8, 7, 4, 5, 6, 1, 2, 3]);
+        assert_eq!(list.peek_back(), Some(&3));
+        list.reverse_in_groups(20);
+        assert_eq!(to_vec(&list), [8, 7, 4, 5, 6, 1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "group size must be non-zero")]
+    fn test_reverse_in_groups_of_zero() {
+        list_of(&[1]).reverse_in_groups(0);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut list = list_of(&[1, 1, 2, 3, 3, 3, 1, 4, 4]);
+        list.dedup();
+        assert_eq!(to_vec(&list), [1, 2, 3, 1, 4]);
+        assert_eq!(list.peek_back(), Some(&4));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_remove_duplicates() {
+        let mut list = list_of(&[3, 1, 3, 2, 1, 1, 4, 2]);
+        list.remove_duplicates();
+        assert_eq!(to_vec(&list), [3, 1, 2, 4]);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.peek_back(), Some(&4));
+
+        let mut words: LinkedList<String> = ["a", "b", "a"].iter().map(|s| s.to_string()).collect();
+        words.remove_duplicates();
+        assert_eq!(words.iter().collect::<Vec<_>>(), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_remove_duplicates_ord() {
+        let mut list = list_of(&[3, 1, 3, 2, 1, 1, 4, 2]);
+        list.remove_duplicates_ord();
+        assert_eq!(to_vec(&list), [3, 1, 2, 4]);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.peek_back(), Some(&4));
+
+        let mut empty: LinkedList<i32> = LinkedList::new();
+        empty.remove_duplicates_ord();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_allocation_stats() {
+        let counting = Counting::new(Global);
+        let mut list = LinkedList::new_in(&counting);
+        for value in [5, 3, 8, 1] {
+            list.insert_at_tail(value);
+        }
+        list.insert_at_index(2, 7).unwrap();
+        assert_eq!(counting.stats().allocations, 5);
+        assert_eq!(
+            counting.stats().bytes_in_use,
+            5 * mem::size_of::<Node<i32>>()
+        );
+
+        // Relinking moves nodes around without allocating
+        list.sort();
+        list.reverse();
+        list.rotate_left(2);
+        assert_eq!(counting.stats().allocations, 5);
+
+        assert_eq!(list.remove_from_tail(), Some(7));
+        assert_eq!(list.cursor_front_mut().remove_current(), Some(5));
+        assert_eq!(counting.stats().deallocations, 2);
+        drop(list);
+        assert_eq!(counting.stats().live(), 0);
+        assert_eq!(counting.stats().peak_bytes, 5 * mem::size_of::<Node<i32>>());
+    }
 
+    #[test]
+    fn test_bump_allocator() {
+        let bump = Bump::new();
+        let mut left = LinkedList::new_in(&bump);
+        let mut right = LinkedList::new_in(&bump);
+        for i in 0..100 {
+            left.insert_at_tail(2 * i);
+            right.insert_at_tail(2 * i + 1);
+        }
+        left.merge(&mut right);
+        assert!(right.is_empty());
+        let values: Vec<i32> = left.clone().into_iter().collect();
+        assert_eq!(values, (0..200).collect::<Vec<_>>());
+        assert_eq!(left.allocator().capacity(), bump.capacity());
     }
-    // reverse singly-linked list
-}
\ No newline at end of file
+
+    #[test]
+    fn test_merge_across_allocators() {
+        let first = Bump::new();
+        let second = Bump::new();
+        let mut left = LinkedList::new_in(&first);
+        let mut right = LinkedList::new_in(&second);
+        for i in 0..100 {
+            left.insert_at_tail(2 * i);
+            right.insert_at_tail(2 * i + 1);
+        }
+        left.merge(&mut right);
+        drop(right);
+        assert_eq!(left.len(), 200);
+        assert_eq!(left.peek_back(), Some(&199));
+
+        let (own, moved) = (Counting::new(Global), Counting::new(Global));
+        let mut left = LinkedList::new_in(&own);
+        let mut right = LinkedList::new_in(&moved);
+        left.extend([1, 3]);
+        right.extend([0, 2, 4]);
+        left.merge(&mut right);
+        drop(right);
+        let stats = moved.stats();
+        assert_eq!((stats.allocations, stats.deallocations), (3, 3));
+        assert_eq!(stats.bytes_in_use, 0);
+        assert_eq!(left.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+
+        for _ in 0..3 {
+            left.remove_from_tail();
+        }
+        assert_eq!(own.stats().live(), 2);
+        drop(left);
+        let stats = own.stats();
+        assert_eq!((stats.allocations, stats.deallocations), (5, 5));
+        assert_eq!(stats.bytes_in_use, 0);
+    }
+
+    #[test]
+    fn test_merge_same_allocator() {
+        let counter = Counting::new(Global);
+        let mut left = LinkedList::new_in(&counter);
+        let mut right = LinkedList::new_in(&counter);
+        left.extend([1, 3]);
+        right.extend([0, 2, 4]);
+        left.merge(&mut right);
+        assert_eq!(left.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        let stats = counter.stats();
+        assert_eq!((stats.allocations, stats.deallocations), (5, 0));
+    }
+}
@@ -0,0 +1,565 @@
+// Lock-Free Stack and Queue implementation in Rust
+
+//! A Treiber stack and a Michael-Scott queue that many threads can push to
+//! and pop from at once without locks.
+//!
+//! Nodes are singly linked like those of a
+//! [`LinkedList`](crate::linked_lists::LinkedList), but every link is an
+//! atomic pointer swung with compare-and-swap. A popped node may still be
+//! read by a thread that loaded it just before, so nodes are reclaimed with
+//! hazard pointers instead of being freed at once.
+//!
+//! Build with `RUSTFLAGS="--cfg loom"` to run the loom model tests:
+//! `RUSTFLAGS="--cfg loom" cargo test --release lock_free`.
+
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ptr;
+
+use crate::hazard_pointer::{AtomicPtr, Domain, Ordering::SeqCst};
+
+/// A node in the stack, whose value is moved out when it is popped
+struct StackNode<T> {
+    value: ManuallyDrop<T>,
+    /// Fixed once the node is pushed, so readers never race on it
+    next: *mut StackNode<T>,
+}
+
+/// A lock-free LIFO stack (Treiber stack).
+///
+/// `push` and `pop` are a single compare-and-swap on the head when
+/// uncontended, and retry when another thread got there first.
+pub struct LockFreeStack<T> {
+    head: AtomicPtr<StackNode<T>>,
+    domain: Domain,
+    _marker: PhantomData<T>,
+}
+
+// SAFETY: values are moved in and out whole, never shared, so sending them
+// between threads is all the stack needs.
+unsafe impl<T: Send> Sync for LockFreeStack<T> {}
+
+impl<T> Default for LockFreeStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> LockFreeStack<T> {
+    /// Create a new empty stack
+    pub fn new() -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+            domain: Domain::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Check if the stack is empty. Other threads may change this at any
+    /// moment, so it is only a snapshot.
+    pub fn is_empty(&self) -> bool {
+        self.head.load(SeqCst).is_null()
+    }
+
+    /// Push a value onto the top of the stack
+    pub fn push(&self, value: T) {
+        let node = Box::into_raw(Box::new(StackNode {
+            value: ManuallyDrop::new(value),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(SeqCst);
+        loop {
+            // SAFETY: the node is not published yet, so this thread owns it.
+            unsafe { (*node).next = head };
+            match self.head.compare_exchange(head, node, SeqCst, SeqCst) {
+                Ok(_) => return,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    /// Pop the value from the top of the stack
+    pub fn pop(&self) -> Option<T> {
+        let hazard = self.domain.hazard();
+        loop {
+            let head = hazard.protect(&self.head);
+            if head.is_null() {
+                return None;
+            }
+            // SAFETY: the hazard keeps `head` from being freed, and `next`
+            // never changes after the push that published it. Since a node
+            // can't be freed and reused while protected, the exchange below
+            // can't succeed on a stale head (the ABA problem).
+            let next = unsafe { (*head).next };
+            if self
+                .head
+                .compare_exchange(head, next, SeqCst, SeqCst)
+                .is_ok()
+            {
+                hazard.reset();
+                // SAFETY: winning the exchange unlinked the node, so only this
+                // thread takes its value, and it is retired exactly once.
+                // Retiring frees the node without dropping the value.
+                unsafe {
+                    let value = ManuallyDrop::take(&mut (*head).value);
+                    self.domain.retire(head);
+                    return Some(value);
+                }
+            }
+        }
+    }
+}
+
+impl<T> Drop for LockFreeStack<T> {
+    fn drop(&mut self) {
+        let mut current = self.head.load(SeqCst);
+        while !current.is_null() {
+            // SAFETY: `&mut self` means no other thread is using the stack,
+            // and every linked node still owns its value.
+            unsafe {
+                let mut node = Box::from_raw(current);
+                ManuallyDrop::drop(&mut node.value);
+                current = node.next;
+            }
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for LockFreeStack<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LockFreeStack")
+            .field("is_empty", &self.is_empty())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A node in the queue. The front node is a sentinel whose value has
+/// already been popped, or was never set.
+struct QueueNode<T> {
+    value: MaybeUninit<T>,
+    next: AtomicPtr<QueueNode<T>>,
+}
+
+impl<T> QueueNode<T> {
+    /// Allocate a node with no successor
+    fn alloc(value: MaybeUninit<T>) -> *mut QueueNode<T> {
+        Box::into_raw(Box::new(QueueNode {
+            value,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+}
+
+/// A lock-free FIFO queue (Michael-Scott queue).
+///
+/// The queue always holds a sentinel node, so `push` only touches the tail
+/// and `pop` only touches the head. A push links its node after the tail
+/// and then swings the tail forward; any thread that finds the tail lagging
+/// helps swing it first.
+pub struct LockFreeQueue<T> {
+    head: AtomicPtr<QueueNode<T>>,
+    tail: AtomicPtr<QueueNode<T>>,
+    domain: Domain,
+    _marker: PhantomData<T>,
+}
+
+// SAFETY: values are moved in and out whole, never shared, so sending them
+// between threads is all the queue needs.
+unsafe impl<T: Send> Sync for LockFreeQueue<T> {}
+
+impl<T> Default for LockFreeQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> LockFreeQueue<T> {
+    /// Create a new empty queue
+    pub fn new() -> Self {
+        let sentinel = QueueNode::alloc(MaybeUninit::uninit());
+        Self {
+            head: AtomicPtr::new(sentinel),
+            tail: AtomicPtr::new(sentinel),
+            domain: Domain::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Check if the queue is empty. Other threads may change this at any
+    /// moment, so it is only a snapshot.
+    pub fn is_empty(&self) -> bool {
+        let hazard = self.domain.hazard();
+        let head = hazard.protect(&self.head);
+        // SAFETY: the hazard keeps the sentinel from being freed.
+        unsafe { (*head).next.load(SeqCst).is_null() }
+    }
+
+    /// Push a value onto the back of the queue
+    pub fn push(&self, value: T) {
+        let node = QueueNode::alloc(MaybeUninit::new(value));
+        let hazard = self.domain.hazard();
+        loop {
+            let tail = hazard.protect(&self.tail);
+            // SAFETY: the hazard keeps `tail` from being freed.
+            let next = unsafe { (*tail).next.load(SeqCst) };
+            if !next.is_null() {
+                // Another push linked its node but hasn't swung the tail yet.
+                let _ = self.tail.compare_exchange(tail, next, SeqCst, SeqCst);
+                continue;
+            }
+            // SAFETY: as above.
+            let linked = unsafe { &(*tail).next }
+                .compare_exchange(ptr::null_mut(), node, SeqCst, SeqCst)
+                .is_ok();
+            if linked {
+                let _ = self.tail.compare_exchange(tail, node, SeqCst, SeqCst);
+                return;
+            }
+        }
+    }
+
+    /// Pop the value from the front of the queue
+    pub fn pop(&self) -> Option<T> {
+        let head_hazard = self.domain.hazard();
+        let next_hazard = self.domain.hazard();
+        loop {
+            let head = head_hazard.protect(&self.head);
+            // SAFETY: the hazard keeps `head` from being freed.
+            let next = next_hazard.protect(unsafe { &(*head).next });
+            // `next` is only safe to use if it was published while `head`
+            // was still the sentinel; otherwise it may already be retired.
+            if self.head.load(SeqCst) != head {
+                continue;
+            }
+            if next.is_null() {
+                return None;
+            }
+            let tail = self.tail.load(SeqCst);
+            if head == tail {
+                // The tail lags behind a push; the sentinel can't be retired
+                // until the tail has moved past it.
+                let _ = self.tail.compare_exchange(tail, next, SeqCst, SeqCst);
+                continue;
+            }
+            if self
+                .head
+                .compare_exchange(head, next, SeqCst, SeqCst)
+                .is_ok()
+            {
+                // SAFETY: winning the exchange made `next` the sentinel, so
+                // only this thread takes its value, and unlinked the old
+                // sentinel, which is retired exactly once. Retiring frees
+                // the node without dropping a value.
+                unsafe {
+                    let value = (*next).value.assume_init_read();
+                    head_hazard.reset();
+                    next_hazard.reset();
+                    self.domain.retire(head);
+                    return Some(value);
+                }
+            }
+        }
+    }
+}
+
+impl<T> Drop for LockFreeQueue<T> {
+    fn drop(&mut self) {
+        // SAFETY: `&mut self` means no other thread is using the queue. The
+        // sentinel holds no value and every node after it holds one.
+        unsafe {
+            let sentinel = Box::from_raw(self.head.load(SeqCst));
+            let mut current = sentinel.next.load(SeqCst);
+            while !current.is_null() {
+                let mut node = Box::from_raw(current);
+                node.value.assume_init_drop();
+                current = node.next.load(SeqCst);
+            }
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for LockFreeQueue<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LockFreeQueue")
+            .field("is_empty", &self.is_empty())
+            .finish_non_exhaustive()
+    }
+}
+
+// Stress tests are meant to be run under Miri as well: `cargo +nightly miri
+// test lock_free` (with fewer iterations, via `cfg!(miri)`).
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    const THREADS: usize = 4;
+    const PER_THREAD: usize = if cfg!(miri) { 100 } else { 20_000 };
+
+    /// Counts how many values have been dropped
+    struct Tracked<'a>(&'a AtomicUsize);
+
+    impl Drop for Tracked<'_> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_stack_is_lifo() {
+        let stack = LockFreeStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+        for value in 1..=3 {
+            stack.push(value);
+        }
+        assert!(!stack.is_empty());
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+        stack.push(4);
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn test_queue_is_fifo() {
+        let queue = LockFreeQueue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+        for value in 1..=3 {
+            queue.push(value);
+        }
+        assert!(!queue.is_empty());
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.pop(), Some(2));
+        queue.push(4);
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), Some(4));
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_values_are_dropped_once() {
+        let drops = AtomicUsize::new(0);
+        {
+            let stack = LockFreeStack::new();
+            let queue = LockFreeQueue::new();
+            for _ in 0..10 {
+                stack.push(Tracked(&drops));
+                queue.push(Tracked(&drops));
+            }
+            drop(stack.pop());
+            drop(queue.pop());
+            assert_eq!(drops.load(Ordering::SeqCst), 2);
+        }
+        assert_eq!(drops.load(Ordering::SeqCst), 20);
+    }
+
+    #[test]
+    fn test_stack_stress() {
+        let stack = Arc::new(LockFreeStack::new());
+        let barrier = Arc::new(Barrier::new(THREADS * 2));
+        let pushers: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                let (stack, barrier) = (Arc::clone(&stack), Arc::clone(&barrier));
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..PER_THREAD {
+                        stack.push(thread * PER_THREAD + i);
+                    }
+                })
+            })
+            .collect();
+        let poppers: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let (stack, barrier) = (Arc::clone(&stack), Arc::clone(&barrier));
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut popped = Vec::new();
+                    for _ in 0..PER_THREAD {
+                        popped.extend(stack.pop());
+                    }
+                    popped
+                })
+            })
+            .collect();
+        for pusher in pushers {
+            pusher.join().unwrap();
+        }
+        let mut seen: Vec<usize> = poppers
+            .into_iter()
+            .flat_map(|popper| popper.join().unwrap())
+            .collect();
+        while let Some(value) = stack.pop() {
+            seen.push(value);
+        }
+        seen.sort_unstable();
+        assert!(seen.into_iter().eq(0..THREADS * PER_THREAD));
+    }
+
+    #[test]
+    fn test_queue_stress_keeps_each_producers_order() {
+        let queue = Arc::new(LockFreeQueue::new());
+        let barrier = Arc::new(Barrier::new(THREADS * 2));
+        let producers: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                let (queue, barrier) = (Arc::clone(&queue), Arc::clone(&barrier));
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..PER_THREAD {
+                        queue.push((thread, i));
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let (queue, barrier) = (Arc::clone(&queue), Arc::clone(&barrier));
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut popped = Vec::new();
+                    for _ in 0..PER_THREAD {
+                        popped.extend(queue.pop());
+                    }
+                    popped
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut batches: Vec<Vec<(usize, usize)>> = consumers
+            .into_iter()
+            .map(|consumer| consumer.join().unwrap())
+            .collect();
+        batches.push(std::iter::from_fn(|| queue.pop()).collect());
+
+        // Each consumer sees every producer's values in the order pushed.
+        for batch in &batches {
+            let mut last = [None; THREADS];
+            for &(thread, i) in batch {
+                assert!(last[thread] < Some(i));
+                last[thread] = Some(i);
+            }
+        }
+        let mut seen: Vec<(usize, usize)> = batches.into_iter().flatten().collect();
+        seen.sort_unstable();
+        assert_eq!(seen.len(), THREADS * PER_THREAD);
+        seen.dedup();
+        assert_eq!(seen.len(), THREADS * PER_THREAD);
+    }
+
+    #[test]
+    fn test_shared_work_stack() {
+        let stack = Arc::new(LockFreeStack::new());
+        for job in 0..1_000u64 {
+            stack.push(job);
+        }
+        let total = Arc::new(AtomicUsize::new(0));
+        let workers: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let (stack, total) = (Arc::clone(&stack), Arc::clone(&total));
+                thread::spawn(move || {
+                    while let Some(job) = stack.pop() {
+                        // Jobs above 500 split off a smaller follow-up job.
+                        if job > 500 {
+                            stack.push(job - 500);
+                        }
+                        total.fetch_add(1, Ordering::SeqCst);
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(total.load(Ordering::SeqCst), 1_000 + 499);
+        assert!(stack.is_empty());
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::*;
+    use loom::sync::Arc;
+    use loom::thread;
+
+    #[test]
+    fn loom_stack_push_pop() {
+        loom::model(|| {
+            let stack = Arc::new(LockFreeStack::new());
+            let other = Arc::clone(&stack);
+            let pusher = thread::spawn(move || {
+                other.push(1);
+                other.push(2);
+            });
+            let popped = stack.pop();
+            pusher.join().unwrap();
+            let mut seen: Vec<i32> = popped.into_iter().collect();
+            seen.extend(std::iter::from_fn(|| stack.pop()));
+            seen.sort_unstable();
+            assert_eq!(seen, [1, 2]);
+        });
+    }
+
+    #[test]
+    fn loom_stack_concurrent_pops() {
+        loom::model(|| {
+            let stack = Arc::new(LockFreeStack::new());
+            stack.push(1);
+            stack.push(2);
+            let other = Arc::clone(&stack);
+            let popper = thread::spawn(move || other.pop());
+            let mine = stack.pop();
+            let theirs = popper.join().unwrap();
+            let mut seen = [mine.unwrap(), theirs.unwrap()];
+            seen.sort_unstable();
+            assert_eq!(seen, [1, 2]);
+            assert!(stack.is_empty());
+        });
+    }
+
+    #[test]
+    fn loom_queue_push_pop() {
+        loom::model(|| {
+            let queue = Arc::new(LockFreeQueue::new());
+            let other = Arc::clone(&queue);
+            let producer = thread::spawn(move || {
+                other.push(1);
+                other.push(2);
+            });
+            let first = queue.pop();
+            producer.join().unwrap();
+            let rest: Vec<i32> = std::iter::from_fn(|| queue.pop()).collect();
+            match first {
+                Some(value) => {
+                    assert_eq!(value, 1);
+                    assert_eq!(rest, [2]);
+                }
+                None => assert_eq!(rest, [1, 2]),
+            }
+        });
+    }
+
+    #[test]
+    fn loom_queue_concurrent_pops() {
+        loom::model(|| {
+            let queue = Arc::new(LockFreeQueue::new());
+            queue.push(1);
+            queue.push(2);
+            let other = Arc::clone(&queue);
+            let consumer = thread::spawn(move || other.pop());
+            let mine = queue.pop();
+            let theirs = consumer.join().unwrap();
+            let mut seen = [mine.unwrap(), theirs.unwrap()];
+            seen.sort_unstable();
+            assert_eq!(seen, [1, 2]);
+            assert!(queue.is_empty());
+        });
+    }
+}
@@ -0,0 +1,524 @@
+// Fixed-Size Matrix implementation in Rust
+
+use core::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+
+use crate::arrays::Array;
+use crate::error::Error;
+use crate::numeric::{Field, Numeric};
+
+/// An `R`×`C` matrix stored row by row in an [`Array`] of rows.
+///
+/// The dimensions are part of the type, so adding matrices of different
+/// shapes, or multiplying when the inner dimensions differ, is a compile
+/// error rather than a runtime one.
+pub struct Matrix<T, const R: usize, const C: usize> {
+    rows: Array<Array<T, C>, R>,
+}
+
+impl<T, const R: usize, const C: usize> Matrix<T, R, C> {
+    /// Create a new matrix from its rows
+    pub fn new(rows: [[T; C]; R]) -> Self {
+        Self {
+            rows: Array::new(rows.map(Array::new)),
+        }
+    }
+
+    /// Create a new matrix whose entry at (row, column) is `f(row, column)`
+    pub fn from_fn(mut f: impl FnMut(usize, usize) -> T) -> Self {
+        Self::new(core::array::from_fn(|row| {
+            core::array::from_fn(|column| f(row, column))
+        }))
+    }
+
+    /// Get the number of rows
+    pub fn row_count(&self) -> usize {
+        R
+    }
+
+    /// Get the number of columns
+    pub fn column_count(&self) -> usize {
+        C
+    }
+
+    /// Get a reference to the entry at (row, column)
+    pub fn get(&self, row: usize, column: usize) -> Option<&T> {
+        self.rows.get(row)?.get(column)
+    }
+
+    /// Get a mutable reference to the entry at (row, column)
+    pub fn get_mut(&mut self, row: usize, column: usize) -> Option<&mut T> {
+        self.rows.get_mut(row)?.get_mut(column)
+    }
+
+    /// Get a row as a slice
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        self.rows.get(row).map(Array::as_slice)
+    }
+
+    /// Iterate over the rows as slices
+    pub fn rows(&self) -> Rows<'_, T, C> {
+        Rows {
+            rows: self.rows.iter(),
+        }
+    }
+
+    /// Iterate down a column, or get `None` if it is out of bounds
+    pub fn column(&self, column: usize) -> Option<Column<'_, T, C>> {
+        (column < C).then(|| Column {
+            rows: self.rows(),
+            column,
+        })
+    }
+
+    /// Iterate over the columns, each as an iterator down the column
+    pub fn columns(&self) -> Columns<'_, T, R, C> {
+        Columns {
+            matrix: self,
+            next: 0,
+        }
+    }
+
+    /// Apply `f` to every entry
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Matrix<U, R, C> {
+        Matrix::new(self.into_rows().map(|row| row.map(&mut f)))
+    }
+
+    /// Consume the matrix, returning its rows
+    pub fn into_rows(self) -> [[T; C]; R] {
+        self.rows.into_inner().map(Array::into_inner)
+    }
+}
+
+impl<T: Copy, const R: usize, const C: usize> Matrix<T, R, C> {
+    /// Get the transpose, whose rows are this matrix's columns
+    pub fn transpose(&self) -> Matrix<T, C, R> {
+        Matrix::from_fn(|row, column| self[(column, row)])
+    }
+}
+
+impl<T: Numeric, const R: usize, const C: usize> Matrix<T, R, C> {
+    /// Create a new matrix of zeros
+    pub fn zero() -> Self {
+        Self::from_fn(|_, _| T::zero())
+    }
+
+    /// Multiply every entry by `factor`
+    pub fn scale(&self, factor: T) -> Self {
+        Self::from_fn(|row, column| self[(row, column)] * factor)
+    }
+}
+
+impl<T: Numeric, const N: usize> Matrix<T, N, N> {
+    /// Create a new identity matrix
+    pub fn identity() -> Self {
+        Self::from_fn(|row, column| if row == column { T::one() } else { T::zero() })
+    }
+
+    /// Get the sum of the diagonal
+    pub fn trace(&self) -> T {
+        (0..N).fold(T::zero(), |sum, i| sum + self[(i, i)])
+    }
+
+    /// Get the determinant by fraction-free (Bareiss) elimination. O(N³).
+    ///
+    /// Every division is exact, so integer determinants come out exact as
+    /// long as no intermediate minor overflows.
+    pub fn determinant(&self) -> T
+    where
+        T: Neg<Output = T> + core::ops::Div<Output = T>,
+    {
+        let mut m = self.clone().into_rows();
+        let mut sign = T::one();
+        let mut previous = T::one();
+        for k in 0..N {
+            if m[k][k] == T::zero() {
+                let Some(pivot) = (k + 1..N).find(|&row| m[row][k] != T::zero()) else {
+                    return T::zero();
+                };
+                m.swap(k, pivot);
+                sign = -sign;
+            }
+            for i in k + 1..N {
+                for j in k + 1..N {
+                    m[i][j] = (m[i][j] * m[k][k] - m[i][k] * m[k][j]) / previous;
+                }
+            }
+            previous = m[k][k];
+        }
+        match N {
+            0 => T::one(),
+            _ => sign * m[N - 1][N - 1],
+        }
+    }
+
+    /// Get the inverse by Gauss-Jordan elimination with partial pivoting,
+    /// or [`Error::SingularMatrix`] if there is none. O(N³).
+    pub fn inverse(&self) -> Result<Self, Error>
+    where
+        T: Field,
+    {
+        let mut m = self.clone().into_rows();
+        let mut inverse = Self::identity().into_rows();
+        for k in 0..N {
+            let pivot = (k + 1..N).fold(k, |best, row| {
+                if m[row][k].better_pivot(m[best][k]) {
+                    row
+                } else {
+                    best
+                }
+            });
+            if m[pivot][k] == T::zero() {
+                return Err(Error::SingularMatrix);
+            }
+            m.swap(k, pivot);
+            inverse.swap(k, pivot);
+
+            let scale = T::one() / m[k][k];
+            for j in 0..N {
+                m[k][j] = m[k][j] * scale;
+                inverse[k][j] = inverse[k][j] * scale;
+            }
+            for i in (0..N).filter(|&i| i != k) {
+                let factor = m[i][k];
+                if factor == T::zero() {
+                    continue;
+                }
+                for j in 0..N {
+                    m[i][j] = m[i][j] - factor * m[k][j];
+                    inverse[i][j] = inverse[i][j] - factor * inverse[k][j];
+                }
+            }
+        }
+        Ok(Self::new(inverse))
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+
+    /// Get the entry at (row, column), panicking if it is out of bounds
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        &self.rows[row][column]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for Matrix<T, R, C> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        &mut self.rows[row][column]
+    }
+}
+
+impl<T: Numeric, const R: usize, const C: usize> Add for &Matrix<T, R, C> {
+    type Output = Matrix<T, R, C>;
+
+    fn add(self, rhs: Self) -> Matrix<T, R, C> {
+        Matrix::from_fn(|row, column| self[(row, column)] + rhs[(row, column)])
+    }
+}
+
+impl<T: Numeric, const R: usize, const C: usize> Add for Matrix<T, R, C> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl<T: Numeric, const R: usize, const C: usize> Sub for &Matrix<T, R, C> {
+    type Output = Matrix<T, R, C>;
+
+    fn sub(self, rhs: Self) -> Matrix<T, R, C> {
+        Matrix::from_fn(|row, column| self[(row, column)] - rhs[(row, column)])
+    }
+}
+
+impl<T: Numeric, const R: usize, const C: usize> Sub for Matrix<T, R, C> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        &self - &rhs
+    }
+}
+
+impl<T: Numeric, const R: usize, const K: usize, const C: usize> Mul<&Matrix<T, K, C>>
+    for &Matrix<T, R, K>
+{
+    type Output = Matrix<T, R, C>;
+
+    /// Multiply an `R`×`K` matrix by a `K`×`C` one. O(R·K·C).
+    fn mul(self, rhs: &Matrix<T, K, C>) -> Matrix<T, R, C> {
+        Matrix::from_fn(|row, column| {
+            (0..K).fold(T::zero(), |sum, k| sum + self[(row, k)] * rhs[(k, column)])
+        })
+    }
+}
+
+impl<T: Numeric, const R: usize, const K: usize, const C: usize> Mul<Matrix<T, K, C>>
+    for Matrix<T, R, K>
+{
+    type Output = Matrix<T, R, C>;
+
+    fn mul(self, rhs: Matrix<T, K, C>) -> Matrix<T, R, C> {
+        &self * &rhs
+    }
+}
+
+impl<T: Clone, const R: usize, const C: usize> Clone for Matrix<T, R, C> {
+    fn clone(&self) -> Self {
+        Self {
+            rows: self.rows.clone(),
+        }
+    }
+}
+
+impl<T: PartialEq, const R: usize, const C: usize> PartialEq for Matrix<T, R, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows().eq(other.rows())
+    }
+}
+
+impl<T: Eq, const R: usize, const C: usize> Eq for Matrix<T, R, C> {}
+
+impl<T: core::fmt::Debug, const R: usize, const C: usize> core::fmt::Debug for Matrix<T, R, C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.rows()).finish()
+    }
+}
+
+/// An iterator over the rows of a [`Matrix`] as slices
+pub struct Rows<'a, T, const C: usize> {
+    rows: core::slice::Iter<'a, Array<T, C>>,
+}
+
+impl<'a, T, const C: usize> Iterator for Rows<'a, T, C> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        self.rows.next().map(Array::as_slice)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<T, const C: usize> DoubleEndedIterator for Rows<'_, T, C> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rows.next_back().map(Array::as_slice)
+    }
+}
+
+impl<T, const C: usize> ExactSizeIterator for Rows<'_, T, C> {}
+
+impl<T, const C: usize> Clone for Rows<'_, T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            rows: self.rows.clone(),
+        }
+    }
+}
+
+/// An iterator down one column of a [`Matrix`]
+pub struct Column<'a, T, const C: usize> {
+    rows: Rows<'a, T, C>,
+    column: usize,
+}
+
+impl<'a, T, const C: usize> Iterator for Column<'a, T, C> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.rows.next().map(|row| &row[self.column])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<T, const C: usize> DoubleEndedIterator for Column<'_, T, C> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rows.next_back().map(|row| &row[self.column])
+    }
+}
+
+impl<T, const C: usize> ExactSizeIterator for Column<'_, T, C> {}
+
+impl<T, const C: usize> Clone for Column<'_, T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            rows: self.rows.clone(),
+            column: self.column,
+        }
+    }
+}
+
+/// An iterator over the columns of a [`Matrix`]
+pub struct Columns<'a, T, const R: usize, const C: usize> {
+    matrix: &'a Matrix<T, R, C>,
+    next: usize,
+}
+
+impl<'a, T, const R: usize, const C: usize> Iterator for Columns<'a, T, R, C> {
+    type Item = Column<'a, T, C>;
+
+    fn next(&mut self) -> Option<Column<'a, T, C>> {
+        let column = self.matrix.column(self.next)?;
+        self.next += 1;
+        Some(column)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (C - self.next, Some(C - self.next))
+    }
+}
+
+impl<T, const R: usize, const C: usize> ExactSizeIterator for Columns<'_, T, R, C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numeric::ModInt;
+
+    type Mod7 = ModInt<7>;
+
+    #[test]
+    fn test_new_and_index() {
+        let mut m = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(m.row_count(), 2);
+        assert_eq!(m.column_count(), 3);
+        assert_eq!(m[(1, 2)], 6);
+        assert_eq!(m.get(0, 1), Some(&2));
+        assert_eq!(m.get(2, 0), None);
+        assert_eq!(m.get(0, 3), None);
+        m[(0, 0)] = 10;
+        *m.get_mut(1, 0).unwrap() = 40;
+        assert_eq!(m.into_rows(), [[10, 2, 3], [40, 5, 6]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds() {
+        let m = Matrix::new([[1, 2], [3, 4]]);
+        let _ = m[(0, 2)];
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let m = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(m.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(m.row(2), None);
+        assert_eq!(m.rows().len(), 2);
+        assert_eq!(m.rows().next_back(), Some(&[4, 5, 6][..]));
+        assert_eq!(m.column(1).unwrap().copied().collect::<Vec<_>>(), [2, 5]);
+        assert!(m.column(3).is_none());
+        let columns: Vec<Vec<i32>> = m.columns().map(|c| c.copied().collect()).collect();
+        assert_eq!(columns, [[1, 4], [2, 5], [3, 6]]);
+        assert_eq!(m.columns().len(), 3);
+    }
+
+    #[test]
+    fn test_add_and_sub() {
+        let a = Matrix::new([[1, 2], [3, 4]]);
+        let b = Matrix::new([[10, 20], [30, 40]]);
+        assert_eq!(&a + &b, Matrix::new([[11, 22], [33, 44]]));
+        assert_eq!(b - a.clone(), Matrix::new([[9, 18], [27, 36]]));
+        assert_eq!(a.scale(3), Matrix::new([[3, 6], [9, 12]]));
+        assert_eq!(Matrix::<i32, 2, 3>::zero(), Matrix::new([[0; 3]; 2]));
+    }
+
+    #[test]
+    fn test_mul_changes_shape() {
+        let a = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+        let b = Matrix::new([[7, 8], [9, 10], [11, 12]]);
+        let product: Matrix<i32, 2, 2> = &a * &b;
+        assert_eq!(product, Matrix::new([[58, 64], [139, 154]]));
+        let outer: Matrix<i32, 3, 3> = b * a;
+        assert_eq!(outer[(0, 0)], 39);
+        assert_eq!(outer.trace(), 39 + 68 + 105);
+    }
+
+    #[test]
+    fn test_identity() {
+        let m = Matrix::new([[2.0, 1.0], [7.0, 3.0]]);
+        let identity = Matrix::identity();
+        assert_eq!(&m * &identity, m);
+        assert_eq!(&identity * &m, m);
+        assert_eq!(Matrix::<u8, 3, 3>::identity().trace(), 3);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let m = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+        let t: Matrix<i32, 3, 2> = m.transpose();
+        assert_eq!(t, Matrix::new([[1, 4], [2, 5], [3, 6]]));
+        assert_eq!(t.transpose(), m);
+    }
+
+    #[test]
+    fn test_determinant() {
+        assert_eq!(Matrix::<i64, 0, 0>::new([]).determinant(), 1);
+        assert_eq!(Matrix::new([[5]]).determinant(), 5);
+        assert_eq!(Matrix::new([[1, 2], [3, 4]]).determinant(), -2);
+        assert_eq!(
+            Matrix::new([[2, -3, 1], [2, 0, -1], [1, 4, 5]]).determinant(),
+            49
+        );
+        // A zero pivot forces a row swap, which flips the sign.
+        assert_eq!(
+            Matrix::new([[0, 1, 2], [1, 0, 3], [4, -3, 8]]).determinant(),
+            -2
+        );
+        assert_eq!(
+            Matrix::new([[1, 2, 3], [4, 5, 6], [7, 8, 9]]).determinant(),
+            0
+        );
+        let m = Matrix::new([[1.5, 2.0], [0.5, 4.0]]);
+        assert_eq!(m.determinant(), 5.0);
+    }
+
+    #[test]
+    fn test_determinant_of_product() {
+        let a = Matrix::new([[3, 1, 4, 1], [5, 9, 2, 6], [5, 3, 5, 8], [9, 7, 9, 3]]);
+        let b = Matrix::new([[2, 7, 1, 8], [2, 8, 1, 8], [2, 8, 4, 5], [9, 0, 4, 5]]);
+        assert_eq!((&a * &b).determinant(), a.determinant() * b.determinant());
+    }
+
+    #[test]
+    fn test_inverse_floats() {
+        let m = Matrix::new([[4.0, 7.0], [2.0, 6.0]]);
+        let inverse = m.inverse().unwrap();
+        let expected = Matrix::new([[0.6, -0.7], [-0.2, 0.4]]);
+        let close = |a: &Matrix<f64, 2, 2>, b: &Matrix<f64, 2, 2>| {
+            a.rows()
+                .flatten()
+                .zip(b.rows().flatten())
+                .all(|(x, y)| (x - y).abs() < 1e-12)
+        };
+        assert!(close(&inverse, &expected), "{inverse:?}");
+        assert!(close(&(&m * &inverse), &Matrix::identity()));
+    }
+
+    #[test]
+    fn test_inverse_mod_int() {
+        let m = Matrix::new([[0, 1, 2], [1, 0, 3], [4, 3, 1]]).map(Mod7::new);
+        let inverse = m.inverse().unwrap();
+        assert_eq!(&m * &inverse, Matrix::identity());
+        assert_eq!(&inverse * &m, Matrix::identity());
+        assert_eq!(inverse.determinant() * m.determinant(), Mod7::new(1));
+    }
+
+    #[test]
+    fn test_singular_inverse() {
+        let m = Matrix::new([[1.0, 2.0], [2.0, 4.0]]);
+        assert_eq!(m.inverse(), Err(Error::SingularMatrix));
+        let m = Matrix::new([[3, 6], [1, 2]]).map(Mod7::new);
+        assert_eq!(m.inverse(), Err(Error::SingularMatrix));
+    }
+
+    #[test]
+    fn test_debug() {
+        let m = Matrix::new([[1, 2], [3, 4]]);
+        assert_eq!(format!("{m:?}"), "[[1, 2], [3, 4]]");
+    }
+}
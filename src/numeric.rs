@@ -0,0 +1,280 @@
+// Numeric Traits implementation in Rust
+
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A number type with the arithmetic matrices need: the built-in integers
+/// and floats, and [`ModInt`]
+pub trait Numeric:
+    Copy + PartialEq + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// The additive identity
+    fn zero() -> Self;
+
+    /// The multiplicative identity
+    fn one() -> Self;
+}
+
+/// A [`Numeric`] type where every non-zero value has an exact
+/// multiplicative inverse, so matrices over it can be inverted.
+///
+/// Floats count as a field even though their division rounds.
+pub trait Field: Numeric + Neg<Output = Self> + Div<Output = Self> {
+    /// Check if `self` makes a better elimination pivot than `current`.
+    ///
+    /// Exact types take the first non-zero value; floats override this to
+    /// prefer the largest magnitude, which keeps rounding errors small.
+    fn better_pivot(self, current: Self) -> bool {
+        current == Self::zero() && self != Self::zero()
+    }
+}
+
+macro_rules! numeric {
+    ($zero:literal, $one:literal: $($ty:ty),*) => {
+        $(
+            impl Numeric for $ty {
+                fn zero() -> Self {
+                    $zero
+                }
+
+                fn one() -> Self {
+                    $one
+                }
+            }
+        )*
+    };
+}
+
+numeric!(0, 1: i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+numeric!(0.0, 1.0: f32, f64);
+
+macro_rules! float_field {
+    ($($ty:ty),*) => {
+        $(
+            impl Field for $ty {
+                fn better_pivot(self, current: Self) -> bool {
+                    self.abs() > current.abs()
+                }
+            }
+        )*
+    };
+}
+
+float_field!(f32, f64);
+
+/// An integer modulo `M`, always kept in `0..M`.
+///
+/// Division multiplies by the inverse from Fermat's little theorem, so it is
+/// only correct when `M` is prime.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const M: u64> {
+    value: u64,
+}
+
+impl<const M: u64> ModInt<M> {
+    /// Create a new modular integer, reducing `value` modulo `M`
+    pub const fn new(value: u64) -> Self {
+        assert!(M > 0, "modulus must be non-zero");
+        Self { value: value % M }
+    }
+
+    /// Get the value in `0..M`
+    pub const fn value(self) -> u64 {
+        self.value
+    }
+
+    /// Raise to the power `exp` by repeated squaring. O(log exp).
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut base = self;
+        let mut result = Self::new(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// Get the multiplicative inverse, or `None` for zero. `M` must be prime.
+    pub fn inverse(self) -> Option<Self> {
+        (self.value != 0).then(|| self.pow(M - 2))
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let (sum, overflowed) = self.value.overflowing_add(rhs.value);
+        if overflowed || sum >= M {
+            Self {
+                value: sum.wrapping_sub(M),
+            }
+        } else {
+            Self { value: sum }
+        }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let product = self.value as u128 * rhs.value as u128 % M as u128;
+        Self {
+            value: product as u64,
+        }
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    /// Multiply by the inverse of `rhs`, panicking if it is zero
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inverse().expect("attempt to divide by zero")
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        if self.value == 0 {
+            self
+        } else {
+            Self {
+                value: M - self.value,
+            }
+        }
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const M: u64> Numeric for ModInt<M> {
+    fn zero() -> Self {
+        Self::new(0)
+    }
+
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<const M: u64> Field for ModInt<M> {}
+
+impl<const M: u64> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {M})", self.value)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Mod7 = ModInt<7>;
+    type Big = ModInt<{ u64::MAX - 58 }>; // the largest 64-bit prime
+
+    #[test]
+    fn test_identities() {
+        assert_eq!(i32::zero() + 5, 5);
+        assert_eq!(u8::one() * 9, 9);
+        assert_eq!(f64::one(), 1.0);
+        assert_eq!(Mod7::zero().value(), 0);
+        assert_eq!(Mod7::one().value(), 1);
+    }
+
+    #[test]
+    fn test_mod_int_arithmetic() {
+        let a = Mod7::new(5);
+        let b = Mod7::new(4);
+        assert_eq!((a + b).value(), 2);
+        assert_eq!((b - a).value(), 6);
+        assert_eq!((a * b).value(), 6);
+        assert_eq!((-a).value(), 2);
+        assert_eq!((-Mod7::zero()).value(), 0);
+        assert_eq!(Mod7::new(23).value(), 2);
+        assert_eq!(Mod7::from(8), Mod7::new(1));
+    }
+
+    #[test]
+    fn test_mod_int_division() {
+        for value in 1..7 {
+            let x = Mod7::new(value);
+            assert_eq!((x * x.inverse().unwrap()).value(), 1);
+            assert_eq!((Mod7::new(3) / x * x).value(), 3);
+        }
+        assert_eq!(Mod7::zero().inverse(), None);
+        assert_eq!(Mod7::new(3).pow(6), Mod7::one());
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn test_mod_int_divide_by_zero() {
+        let _ = Mod7::one() / Mod7::zero();
+    }
+
+    #[test]
+    fn test_mod_int_near_u64_max() {
+        let max = Big::new(u64::MAX - 59);
+        assert_eq!((max + max).value(), u64::MAX - 60);
+        assert_eq!((max * max).value(), 1);
+        assert_eq!((max / max).value(), 1);
+    }
+
+    #[test]
+    fn test_better_pivot() {
+        assert!((-3.0f64).better_pivot(2.0));
+        assert!(!1.0f32.better_pivot(-2.0));
+        assert!(Mod7::new(1).better_pivot(Mod7::zero()));
+        assert!(!Mod7::new(6).better_pivot(Mod7::new(1)));
+        assert!(!Mod7::zero().better_pivot(Mod7::zero()));
+    }
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(format!("{:?}", Mod7::new(3)), "3 (mod 7)");
+        assert_eq!(Mod7::new(10).to_string(), "3");
+    }
+}
@@ -0,0 +1,389 @@
+// Persistent Linked List implementation in Rust
+
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// Define a persistent list type over a reference-counted pointer
+macro_rules! persistent_list {
+    (
+        $(#[$attr:meta])*
+        list: $list:ident,
+        node: $node:ident,
+        iter: $iter:ident,
+        ptr: $ptr:ident $(,)?
+    ) => {
+        /// A node shared by every list whose chain passes through it
+        struct $node<T> {
+            value: T,
+            next: Option<$ptr<$node<T>>>,
+        }
+
+        $(#[$attr])*
+        pub struct $list<T> {
+            head: Option<$ptr<$node<T>>>,
+            length: usize,
+        }
+
+        impl<T> $list<T> {
+            /// Create a new empty list
+            pub fn new() -> Self {
+                Self {
+                    head: None,
+                    length: 0,
+                }
+            }
+
+            /// Get the length of the list
+            pub fn len(&self) -> usize {
+                self.length
+            }
+
+            /// Check if the list is empty
+            pub fn is_empty(&self) -> bool {
+                self.length == 0
+            }
+
+            /// Get a new list with `value` in front of this one, sharing
+            /// every node of this list. O(1).
+            pub fn push_front(&self, value: T) -> Self {
+                Self {
+                    head: Some($ptr::new($node {
+                        value,
+                        next: self.head.clone(),
+                    })),
+                    length: self.length + 1,
+                }
+            }
+
+            /// Get a reference to the first value
+            pub fn head(&self) -> Option<&T> {
+                self.head.as_deref().map(|node| &node.value)
+            }
+
+            /// Get the list after the first value, sharing its nodes with
+            /// this one. O(1).
+            pub fn tail(&self) -> Option<Self> {
+                let node = self.head.as_deref()?;
+                Some(Self {
+                    head: node.next.clone(),
+                    length: self.length - 1,
+                })
+            }
+
+            /// Iterate over the values from head to tail
+            pub fn iter(&self) -> $iter<'_, T> {
+                $iter {
+                    next: self.head.as_deref(),
+                    remaining: self.length,
+                }
+            }
+
+            /// Get a new list with the values in reverse order. This copies
+            /// every value, since no node can be shared. O(n).
+            pub fn reverse(&self) -> Self
+            where
+                T: Clone,
+            {
+                self.iter()
+                    .fold(Self::new(), |reversed, value| reversed.push_front(value.clone()))
+            }
+
+            /// Check if two lists start at the same shared node, which means
+            /// they are equal without comparing any values
+            pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+                match (&this.head, &other.head) {
+                    (Some(a), Some(b)) => $ptr::ptr_eq(a, b),
+                    (None, None) => true,
+                    _ => false,
+                }
+            }
+        }
+
+        impl<T> Default for $list<T> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<T> Clone for $list<T> {
+            /// Share the whole list in O(1)
+            fn clone(&self) -> Self {
+                Self {
+                    head: self.head.clone(),
+                    length: self.length,
+                }
+            }
+        }
+
+        impl<T> Drop for $list<T> {
+            fn drop(&mut self) {
+                // Free unshared nodes one at a time so long lists don't drop
+                // recursively, stopping at the first node another list
+                // still holds. `into_inner` gives the node to exactly one of
+                // the lists dropping it at once, so the last to let go keeps
+                // walking.
+                let mut next = self.head.take();
+                while let Some(node) = next {
+                    next = $ptr::into_inner(node).and_then(|mut node| node.next.take());
+                }
+            }
+        }
+
+        impl<T: core::fmt::Debug> core::fmt::Debug for $list<T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_list().entries(self.iter()).finish()
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for $list<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.length == other.length
+                    && (Self::ptr_eq(self, other) || self.iter().eq(other.iter()))
+            }
+        }
+
+        impl<T: Eq> Eq for $list<T> {}
+
+        impl<T: core::hash::Hash> core::hash::Hash for $list<T> {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                state.write_usize(self.length);
+                for value in self.iter() {
+                    value.hash(state);
+                }
+            }
+        }
+
+        impl<T> FromIterator<T> for $list<T> {
+            /// Build a list holding the values in iteration order
+            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+                let values: Vec<T> = iter.into_iter().collect();
+                values
+                    .into_iter()
+                    .rev()
+                    .fold(Self::new(), |list, value| list.push_front(value))
+            }
+        }
+
+        impl<'a, T> IntoIterator for &'a $list<T> {
+            type Item = &'a T;
+            type IntoIter = $iter<'a, T>;
+
+            fn into_iter(self) -> $iter<'a, T> {
+                self.iter()
+            }
+        }
+
+        #[doc = concat!("An iterator over the values of a [`", stringify!($list), "`]")]
+        pub struct $iter<'a, T> {
+            next: Option<&'a $node<T>>,
+            remaining: usize,
+        }
+
+        impl<'a, T> Iterator for $iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<&'a T> {
+                let node = self.next?;
+                self.next = node.next.as_deref();
+                self.remaining -= 1;
+                Some(&node.value)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.remaining, Some(self.remaining))
+            }
+        }
+
+        impl<T> ExactSizeIterator for $iter<'_, T> {}
+
+        impl<T> core::iter::FusedIterator for $iter<'_, T> {}
+
+        impl<T> Clone for $iter<'_, T> {
+            fn clone(&self) -> Self {
+                Self {
+                    next: self.next,
+                    remaining: self.remaining,
+                }
+            }
+        }
+    };
+}
+
+persistent_list! {
+    /// An immutable singly linked list whose versions share structure.
+    ///
+    /// [`push_front`](Self::push_front) and [`tail`](Self::tail) return new
+    /// lists that share their nodes with the original through [`Rc`], so
+    /// every version stays valid and cloning is O(1). Use
+    /// [`ArcPersistentList`] to share versions between threads.
+    list: PersistentList,
+    node: Node,
+    iter: Iter,
+    ptr: Rc,
+}
+
+persistent_list! {
+    /// A thread-safe [`PersistentList`] whose nodes are shared through
+    /// [`Arc`].
+    list: ArcPersistentList,
+    node: ArcNode,
+    iter: ArcIter,
+    ptr: Arc,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_vec(list: &PersistentList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_new() {
+        let list: PersistentList<i32> = PersistentList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.head(), None);
+        assert!(list.tail().is_none());
+    }
+
+    #[test]
+    fn test_push_front_shares_the_tail() {
+        let base = PersistentList::new().push_front(3).push_front(2);
+        let left = base.push_front(1);
+        let right = base.push_front(10);
+        assert_eq!(to_vec(&base), [2, 3]);
+        assert_eq!(to_vec(&left), [1, 2, 3]);
+        assert_eq!(to_vec(&right), [10, 2, 3]);
+        assert!(PersistentList::ptr_eq(&left.tail().unwrap(), &base));
+        assert!(PersistentList::ptr_eq(&right.tail().unwrap(), &base));
+    }
+
+    #[test]
+    fn test_head_and_tail() {
+        let list: PersistentList<i32> = [1, 2, 3].into_iter().collect();
+        assert_eq!(list.head(), Some(&1));
+        let tail = list.tail().unwrap();
+        assert_eq!(to_vec(&tail), [2, 3]);
+        assert_eq!(tail.len(), 2);
+        let last = tail.tail().unwrap().tail().unwrap();
+        assert!(last.is_empty());
+        assert!(last.tail().is_none());
+        assert_eq!(to_vec(&list), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_clone_is_shared() {
+        let list: PersistentList<i32> = (0..5).collect();
+        let cloned = list.clone();
+        assert!(PersistentList::ptr_eq(&list, &cloned));
+        assert_eq!(list, cloned);
+        assert!(!PersistentList::ptr_eq(&list, &(0..5).collect()));
+    }
+
+    #[test]
+    fn test_reverse() {
+        let list: PersistentList<i32> = (1..=4).collect();
+        assert_eq!(to_vec(&list.reverse()), [4, 3, 2, 1]);
+        assert_eq!(to_vec(&list), [1, 2, 3, 4]);
+        assert!(PersistentList::<i32>::new().reverse().is_empty());
+    }
+
+    #[test]
+    fn test_traits() {
+        let list: PersistentList<i32> = (1..=3).collect();
+        assert_eq!(format!("{list:?}"), "[1, 2, 3]");
+        assert_eq!(list, (1..=3).collect());
+        assert_ne!(list, list.tail().unwrap());
+        assert_eq!(list.iter().len(), 3);
+        assert_eq!((&list).into_iter().sum::<i32>(), 6);
+    }
+
+    #[test]
+    fn test_dropping_a_version_keeps_shared_nodes() {
+        let tracker = Rc::new(());
+        let base = PersistentList::new()
+            .push_front(Rc::clone(&tracker))
+            .push_front(Rc::clone(&tracker));
+        let version = base.push_front(Rc::clone(&tracker));
+        drop(base);
+        assert_eq!(Rc::strong_count(&tracker), 4);
+        assert_eq!(version.len(), 3);
+        let tail = version.tail().unwrap();
+        drop(version);
+        assert_eq!(Rc::strong_count(&tracker), 3);
+        drop(tail);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn test_undo_history() {
+        let mut history = PersistentList::new().push_front("open");
+        let saved = history.clone();
+        history = history.push_front("type").push_front("delete");
+        assert_eq!(history.head(), Some(&"delete"));
+        history = history.tail().unwrap();
+        assert_eq!(history.head(), Some(&"type"));
+        history = saved;
+        assert_eq!(history.iter().copied().collect::<Vec<_>>(), ["open"]);
+    }
+
+    #[test]
+    fn test_drop_long_list() {
+        std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(|| {
+                let list: PersistentList<usize> = (0..1_000_000).collect();
+                let shared = list.tail().unwrap();
+                drop(list);
+                assert_eq!(shared.len(), 999_999);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_drops_of_a_shared_tail() {
+        for _ in 0..32 {
+            let base: ArcPersistentList<usize> = (0..50_000).collect();
+            let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+            let handles: Vec<_> = [base.clone(), base]
+                .into_iter()
+                .map(|version| {
+                    let barrier = barrier.clone();
+                    std::thread::Builder::new()
+                        .stack_size(128 * 1024)
+                        .spawn(move || {
+                            barrier.wait();
+                            drop(version);
+                        })
+                        .unwrap()
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_arc_list_across_threads() {
+        let base: ArcPersistentList<usize> = (0..100).collect();
+        let handles: Vec<_> = (0..4)
+            .map(|thread| {
+                let base = base.clone();
+                std::thread::spawn(move || {
+                    let version = base.push_front(1_000 + thread);
+                    assert!(ArcPersistentList::ptr_eq(&version.tail().unwrap(), &base));
+                    version.iter().sum::<usize>()
+                })
+            })
+            .collect();
+        let sums: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(sums, [5_950, 5_951, 5_952, 5_953]);
+        assert_eq!(base.len(), 100);
+        assert_eq!(base.reverse().head(), Some(&99));
+    }
+}
@@ -0,0 +1,857 @@
+// Skip List implementation in Rust
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+use core::ops::{Bound, RangeBounds};
+use core::ptr::NonNull;
+
+/// The most levels a node can span, enough for 2^32 entries at p = 1/2
+const MAX_LEVEL: usize = 32;
+
+/// A link to the next node on one level
+type Link<K, V> = Option<NonNull<Node<K, V>>>;
+
+/// A node in the skip list, linked forward on each of its levels
+struct Node<K, V> {
+    key: K,
+    value: V,
+    /// The next node on each level, so `next.len()` is the node's height
+    next: Box<[Link<K, V>]>,
+}
+
+impl<K, V> Node<K, V> {
+    /// Allocate a detached node spanning `height` levels
+    fn alloc(key: K, value: V, height: usize) -> NonNull<Node<K, V>> {
+        NonNull::from(Box::leak(Box::new(Node {
+            key,
+            value,
+            next: vec![None; height].into_boxed_slice(),
+        })))
+    }
+
+    /// Free a node that has been unlinked, returning its key and value
+    ///
+    /// # Safety
+    ///
+    /// `node` must come from [`Node::alloc`] and no link may still point at it.
+    unsafe fn free(node: NonNull<Node<K, V>>) -> (K, V) {
+        let node = Box::from_raw(node.as_ptr());
+        (node.key, node.value)
+    }
+}
+
+/// A small seedable pseudo-random number generator (SplitMix64).
+///
+/// Skip lists use it to pick node heights; seeding it makes the shape of a
+/// list, and so its performance, reproducible.
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Create a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Create a generator seeded from the standard library's per-process
+    /// random hashing keys
+    #[cfg(feature = "std")]
+    pub fn from_entropy() -> Self {
+        use core::hash::{BuildHasher, Hasher};
+
+        let seed = std::collections::hash_map::RandomState::new()
+            .build_hasher()
+            .finish();
+        Self::new(seed)
+    }
+
+    /// Get the next pseudo-random value
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Pick a node height: each extra level has probability 1/2
+    fn level(&mut self) -> usize {
+        (1 + self.next_u64().trailing_ones() as usize).min(MAX_LEVEL)
+    }
+}
+
+/// An ordered map backed by a skip list.
+///
+/// Every node is on the bottom level, a sorted singly linked list, and each
+/// higher level links a random half of the nodes below it. Searches start
+/// on the top level and drop down whenever the next key is too large, so
+/// `insert`, `remove` and `get` take O(log n) expected time.
+pub struct SkipMap<K, V> {
+    /// The first node on each level
+    head: [Link<K, V>; MAX_LEVEL],
+    /// How many levels currently have nodes
+    level: usize,
+    length: usize,
+    rng: SplitMix64,
+    _marker: PhantomData<Box<Node<K, V>>>,
+}
+
+// SAFETY: the map owns its nodes like a `Box<Node<K, V>>` chain would.
+unsafe impl<K: Send, V: Send> Send for SkipMap<K, V> {}
+// SAFETY: shared access only hands out `&K` and `&V`.
+unsafe impl<K: Sync, V: Sync> Sync for SkipMap<K, V> {}
+
+#[cfg(feature = "std")]
+impl<K: Ord, V> Default for SkipMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> SkipMap<K, V> {
+    /// Get the number of entries in the skip map
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Check if the skip map is empty
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Get the entry with the smallest key in O(1)
+    pub fn first(&self) -> Option<(&K, &V)> {
+        // SAFETY: the node is live for as long as `self` is borrowed.
+        self.head[0].map(|node| unsafe { entry(node) })
+    }
+
+    /// Get the entry with the largest key in O(log n) expected time
+    pub fn last(&self) -> Option<(&K, &V)> {
+        let mut pred: Link<K, V> = None;
+        for level in (0..self.level).rev() {
+            while let Some(next) = self.link_at(pred, level) {
+                pred = Some(next);
+            }
+        }
+        // SAFETY: the node is live for as long as `self` is borrowed.
+        pred.map(|node| unsafe { entry(node) })
+    }
+
+    /// Remove every entry from the skip map
+    pub fn clear(&mut self) {
+        let mut current = self.head[0];
+        while let Some(node) = current {
+            // SAFETY: every node is on the bottom level exactly once, so
+            // each is freed once, after its successor has been read.
+            unsafe {
+                current = (*node.as_ptr()).next[0];
+                Node::free(node);
+            }
+        }
+        self.head = [None; MAX_LEVEL];
+        self.level = 0;
+        self.length = 0;
+    }
+
+    /// Iterate over the entries in key order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            next: self.head[0],
+            remaining: self.length,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Count the nodes of each height: entry `i` is the number of nodes
+    /// spanning exactly `i + 1` levels.
+    ///
+    /// With a good generator each count is about half the one before it; a
+    /// seed that skews this makes searches slower.
+    pub fn level_distribution(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.level];
+        let mut current = self.head[0];
+        while let Some(node) = current {
+            // SAFETY: the node is live for as long as `self` is borrowed.
+            let node = unsafe { &*node.as_ptr() };
+            counts[node.next.len() - 1] += 1;
+            current = node.next[0];
+        }
+        counts
+    }
+
+    /// Get the link out of `pred` on `level`, where `None` is the head
+    fn link_at(&self, pred: Link<K, V>, level: usize) -> Link<K, V> {
+        match pred {
+            // SAFETY: `pred` is a live node spanning `level`.
+            Some(node) => unsafe { (&(*node.as_ptr()).next)[level] },
+            None => self.head[level],
+        }
+    }
+
+    /// Point the link out of `pred` on `level` at `link`
+    ///
+    /// # Safety
+    ///
+    /// `pred` must be a live node of this map spanning `level`, or `None`
+    /// for the head.
+    unsafe fn set_link(&mut self, pred: Link<K, V>, level: usize, link: Link<K, V>) {
+        match pred {
+            Some(node) => (&mut (*node.as_ptr()).next)[level] = link,
+            None => self.head[level] = link,
+        }
+    }
+
+    /// Find the last node before `bound` on every level, where a node is
+    /// before the bound if `before(key)` holds. Levels above the current
+    /// top stay at the head.
+    fn predecessors<F: FnMut(&K) -> bool>(&self, mut before: F) -> [Link<K, V>; MAX_LEVEL] {
+        let mut preds = [None; MAX_LEVEL];
+        let mut pred: Link<K, V> = None;
+        for level in (0..self.level).rev() {
+            while let Some(next) = self.link_at(pred, level) {
+                // SAFETY: the node is live for as long as `self` is borrowed.
+                if !before(unsafe { &(*next.as_ptr()).key }) {
+                    break;
+                }
+                pred = Some(next);
+            }
+            preds[level] = pred;
+        }
+        preds
+    }
+
+    /// Find the first node whose key is not before the bound
+    fn lower_bound<F: FnMut(&K) -> bool>(&self, mut before: F) -> Link<K, V> {
+        let mut pred: Link<K, V> = None;
+        for level in (0..self.level).rev() {
+            while let Some(next) = self.link_at(pred, level) {
+                // SAFETY: the node is live for as long as `self` is borrowed.
+                if !before(unsafe { &(*next.as_ptr()).key }) {
+                    break;
+                }
+                pred = Some(next);
+            }
+        }
+        self.link_at(pred, 0)
+    }
+}
+
+impl<K: Ord, V> SkipMap<K, V> {
+    /// Create a new empty skip map with a randomly seeded generator
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self::with_rng(SplitMix64::from_entropy())
+    }
+
+    /// Create a new empty skip map whose node heights come from `seed`, so
+    /// the same operations always build the same list
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(SplitMix64::new(seed))
+    }
+
+    fn with_rng(rng: SplitMix64) -> Self {
+        Self {
+            head: [None; MAX_LEVEL],
+            level: 0,
+            length: 0,
+            rng,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Insert a key and value, returning the previous value of the key if
+    /// there was one. O(log n) expected time.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let preds = self.predecessors(|candidate| *candidate < key);
+        if let Some(node) = self.link_at(preds[0], 0) {
+            // SAFETY: the node is live and uniquely borrowed through `self`.
+            let node = unsafe { &mut *node.as_ptr() };
+            if node.key == key {
+                return Some(core::mem::replace(&mut node.value, value));
+            }
+        }
+        let height = self.rng.level();
+        self.level = self.level.max(height);
+        let node = Node::alloc(key, value, height);
+        for (level, &pred) in preds.iter().enumerate().take(height) {
+            let next = self.link_at(pred, level);
+            // SAFETY: `node` is fresh and spans `height` levels; each `pred`
+            // is the head or a live node spanning its level.
+            unsafe {
+                (&mut (*node.as_ptr()).next)[level] = next;
+                self.set_link(pred, level, Some(node));
+            }
+        }
+        self.length += 1;
+        None
+    }
+
+    /// Remove a key, returning its value if it was in the map. O(log n)
+    /// expected time.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let preds = self.predecessors(|candidate| candidate.borrow() < key);
+        let node = self.link_at(preds[0], 0)?;
+        // SAFETY: the node is live; it is unlinked from every level it spans
+        // before being freed.
+        unsafe {
+            if (*node.as_ptr()).key.borrow() != key {
+                return None;
+            }
+            let height = (*node.as_ptr()).next.as_ref().len();
+            for (level, &pred) in preds.iter().enumerate().take(height) {
+                let next = (&(*node.as_ptr()).next)[level];
+                self.set_link(pred, level, next);
+            }
+            while self.level > 0 && self.head[self.level - 1].is_none() {
+                self.level -= 1;
+            }
+            self.length -= 1;
+            Some(Node::free(node).1)
+        }
+    }
+
+    /// Get a reference to the value of a key. O(log n) expected time.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = self.find(key)?;
+        // SAFETY: the node is live for as long as `self` is borrowed.
+        Some(unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Get a mutable reference to the value of a key. O(log n) expected
+    /// time.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = self.find(key)?;
+        // SAFETY: the node is live and uniquely borrowed through `self`.
+        Some(unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Check if the skip map contains a key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Iterate over the entries whose keys fall in `range`, in key order.
+    /// Finding the ends takes O(log n) expected time.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(start) => self.lower_bound(|key| key.borrow() < start),
+            Bound::Excluded(start) => self.lower_bound(|key| key.borrow() <= start),
+            Bound::Unbounded => self.head[0],
+        };
+        let past_end = |key: &K| match range.end_bound() {
+            Bound::Included(end) => key.borrow() > end,
+            Bound::Excluded(end) => key.borrow() >= end,
+            Bound::Unbounded => false,
+        };
+        // SAFETY: the node is live for as long as `self` is borrowed.
+        let start = start.filter(|node| !past_end(unsafe { &(*node.as_ptr()).key }));
+        let end = match range.end_bound() {
+            Bound::Included(end) => self.lower_bound(|key| key.borrow() <= end),
+            Bound::Excluded(end) => self.lower_bound(|key| key.borrow() < end),
+            Bound::Unbounded => None,
+        };
+        Range {
+            next: start,
+            end,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Find the node holding `key`
+    fn find<Q>(&self, key: &Q) -> Link<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = self.lower_bound(|candidate| candidate.borrow() < key)?;
+        // SAFETY: the node is live for as long as `self` is borrowed.
+        let found = unsafe { (*node.as_ptr()).key.borrow().cmp(key) } == Ordering::Equal;
+        found.then_some(node)
+    }
+}
+
+/// Borrow the key and value of a node
+///
+/// # Safety
+///
+/// `node` must stay live for `'a`.
+unsafe fn entry<'a, K, V>(node: NonNull<Node<K, V>>) -> (&'a K, &'a V) {
+    let node = &*node.as_ptr();
+    (&node.key, &node.value)
+}
+
+impl<K, V> Drop for SkipMap<K, V> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> Clone for SkipMap<K, V> {
+    /// Clone the entries into a new list that continues this one's
+    /// generator, so it gets its own node heights
+    fn clone(&self) -> Self {
+        let mut map = Self::with_rng(self.rng.clone());
+        map.extend(self.iter().map(|(key, value)| (key.clone(), value.clone())));
+        map
+    }
+}
+
+impl<K: core::fmt::Debug, V: core::fmt::Debug> core::fmt::Debug for SkipMap<K, V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for SkipMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for SkipMap<K, V> {}
+
+#[cfg(feature = "std")]
+impl<K: Ord, V> FromIterator<(K, V)> for SkipMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for SkipMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a SkipMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of a [`SkipMap`] in key order
+pub struct Iter<'a, K, V> {
+    next: Link<K, V>,
+    remaining: usize,
+    _marker: PhantomData<&'a Node<K, V>>,
+}
+
+// SAFETY: the iterator only hands out `&K` and `&V`, like `&SkipMap` does.
+unsafe impl<K: Sync, V: Sync> Send for Iter<'_, K, V> {}
+// SAFETY: as above.
+unsafe impl<K: Sync, V: Sync> Sync for Iter<'_, K, V> {}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        // SAFETY: the map is borrowed for `'a`, so the node stays live.
+        unsafe {
+            self.next = (&(*node.as_ptr()).next)[0];
+            self.remaining -= 1;
+            Some(entry(node))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> core::iter::FusedIterator for Iter<'_, K, V> {}
+
+/// An iterator over the entries of a [`SkipMap`] within a range of keys
+pub struct Range<'a, K, V> {
+    next: Link<K, V>,
+    /// The first node past the range, where iteration stops
+    end: Link<K, V>,
+    _marker: PhantomData<&'a Node<K, V>>,
+}
+
+// SAFETY: the iterator only hands out `&K` and `&V`, like `&SkipMap` does.
+unsafe impl<K: Sync, V: Sync> Send for Range<'_, K, V> {}
+// SAFETY: as above.
+unsafe impl<K: Sync, V: Sync> Sync for Range<'_, K, V> {}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.filter(|&node| Some(node) != self.end)?;
+        // SAFETY: the map is borrowed for `'a`, so the node stays live.
+        unsafe {
+            self.next = (&(*node.as_ptr()).next)[0];
+            Some(entry(node))
+        }
+    }
+}
+
+impl<K, V> core::iter::FusedIterator for Range<'_, K, V> {}
+
+/// An ordered set backed by a [`SkipMap`] with no values
+pub struct SkipSet<T> {
+    map: SkipMap<T, ()>,
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord> Default for SkipSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> SkipSet<T> {
+    /// Create a new empty skip set with a randomly seeded generator
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self {
+            map: SkipMap::new(),
+        }
+    }
+
+    /// Create a new empty skip set whose node heights come from `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            map: SkipMap::with_seed(seed),
+        }
+    }
+
+    /// Get the number of values in the skip set
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check if the skip set is empty
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Insert a value, returning `false` if it was already present
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Remove a value, returning `true` if it was present
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Check if the skip set contains a value
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Get the smallest value
+    pub fn first(&self) -> Option<&T> {
+        self.map.first().map(|(value, _)| value)
+    }
+
+    /// Get the largest value
+    pub fn last(&self) -> Option<&T> {
+        self.map.last().map(|(value, _)| value)
+    }
+
+    /// Iterate over the values in `range`, in order
+    pub fn range<Q, R>(&self, range: R) -> impl Iterator<Item = &T> + '_
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.map.range(range).map(|(value, _)| value)
+    }
+
+    /// Iterate over the values in order
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &T> + '_ {
+        self.map.iter().map(|(value, _)| value)
+    }
+
+    /// Count the nodes of each height, as [`SkipMap::level_distribution`]
+    pub fn level_distribution(&self) -> Vec<usize> {
+        self.map.level_distribution()
+    }
+}
+
+impl<T: core::fmt::Debug + Ord> core::fmt::Debug for SkipSet<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord> FromIterator<T> for SkipSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            map: iter.into_iter().map(|value| (value, ())).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn map_of(keys: &[i32]) -> SkipMap<i32, i32> {
+        let mut map = SkipMap::with_seed(42);
+        for &key in keys {
+            map.insert(key, key * 10);
+        }
+        map
+    }
+
+    fn keys<'a>(iter: impl Iterator<Item = (&'a i32, &'a i32)>) -> Vec<i32> {
+        iter.map(|(&key, _)| key).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_new() {
+        let map: SkipMap<i32, i32> = SkipMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.first(), None);
+        assert_eq!(map.last(), None);
+        assert_eq!(map.get(&1), None);
+        assert!(map.level_distribution().is_empty());
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut map = map_of(&[5, 1, 4, 2, 3]);
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get(&4), Some(&40));
+        assert_eq!(map.get(&6), None);
+        assert_eq!(map.insert(4, 41), Some(40));
+        assert_eq!(map.len(), 5);
+        *map.get_mut(&1).unwrap() = 11;
+        assert_eq!(keys(map.iter()), [1, 2, 3, 4, 5]);
+        assert_eq!(map.get(&1), Some(&11));
+        assert!(map.contains_key(&3));
+        assert!(!map.contains_key(&0));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map = map_of(&[1, 2, 3, 4, 5]);
+        assert_eq!(map.remove(&3), Some(30));
+        assert_eq!(map.remove(&3), None);
+        assert_eq!(map.remove(&9), None);
+        assert_eq!(keys(map.iter()), [1, 2, 4, 5]);
+        for key in [1, 2, 4, 5] {
+            assert_eq!(map.remove(&key), Some(key * 10));
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.level, 0);
+        map.insert(7, 70);
+        assert_eq!(keys(map.iter()), [7]);
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let mut map = map_of(&[3, 9, 1, 7]);
+        assert_eq!(map.first(), Some((&1, &10)));
+        assert_eq!(map.last(), Some((&9, &90)));
+        map.remove(&9);
+        map.remove(&1);
+        assert_eq!(map.first(), Some((&3, &30)));
+        assert_eq!(map.last(), Some((&7, &70)));
+    }
+
+    #[test]
+    fn test_range() {
+        let map = map_of(&[1, 3, 5, 7, 9]);
+        assert_eq!(keys(map.range(3..7)), [3, 5]);
+        assert_eq!(keys(map.range(3..=7)), [3, 5, 7]);
+        assert_eq!(keys(map.range(2..8)), [3, 5, 7]);
+        assert_eq!(keys(map.range(..4)), [1, 3]);
+        assert_eq!(keys(map.range(6..)), [7, 9]);
+        assert_eq!(keys(map.range(..)), [1, 3, 5, 7, 9]);
+        assert_eq!(
+            keys(map.range((Bound::Excluded(3), Bound::Excluded(9)))),
+            [5, 7]
+        );
+        assert!(map.range(4..5).next().is_none());
+        assert!(map.range(10..).next().is_none());
+        assert!(map.range(5..5).next().is_none());
+        assert!(map
+            .range((Bound::Excluded(5), Bound::Included(5)))
+            .next()
+            .is_none());
+        assert!(map
+            .range((Bound::Included(7), Bound::Excluded(3)))
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_borrowed_keys() {
+        let mut map = SkipMap::with_seed(1);
+        map.insert("banana".to_string(), 2);
+        map.insert("apple".to_string(), 1);
+        assert_eq!(map.get("apple"), Some(&1));
+        let fruit: Vec<&String> = map
+            .range::<str, _>((Bound::Included("b"), Bound::Unbounded))
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(fruit, ["banana"]);
+        assert_eq!(map.remove("banana"), Some(2));
+    }
+
+    #[test]
+    fn test_matches_btree_map() {
+        let mut map = SkipMap::with_seed(7);
+        let mut expected = BTreeMap::new();
+        let mut rng = SplitMix64::new(99);
+        for step in 0..5_000 {
+            let key = (rng.next_u64() % 500) as i32;
+            if rng.next_u64() % 3 == 1 {
+                assert_eq!(map.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(map.insert(key, step), expected.insert(key, step));
+            }
+        }
+        assert_eq!(map.len(), expected.len());
+        assert!(map.iter().eq(expected.iter()));
+        assert!(map.range(100..200).eq(expected.range(100..200)));
+        assert_eq!(map.first(), expected.first_key_value());
+        assert_eq!(map.last(), expected.last_key_value());
+    }
+
+    #[test]
+    fn test_same_seed_same_shape() {
+        let a: Vec<usize> = {
+            let mut map = SkipMap::with_seed(2024);
+            map.extend((0..1_000).map(|key| (key, ())));
+            map.level_distribution()
+        };
+        let mut map = SkipMap::with_seed(2024);
+        map.extend((0..1_000).map(|key| (key, ())));
+        assert_eq!(map.level_distribution(), a);
+        assert_eq!(a.iter().sum::<usize>(), 1_000);
+    }
+
+    #[test]
+    fn test_level_distribution_halves() {
+        let mut map = SkipMap::with_seed(5);
+        map.extend((0..20_000).map(|key| (key, ())));
+        let counts = map.level_distribution();
+        // Each level should hold about half as many nodes as the one below.
+        for pair in counts.windows(2).take(6) {
+            let ratio = pair[1] as f64 / pair[0] as f64;
+            assert!((0.4..0.6).contains(&ratio), "{counts:?}");
+        }
+        assert!(counts.len() <= 24, "{counts:?}");
+    }
+
+    #[test]
+    fn test_traits() {
+        let map = map_of(&[2, 1]);
+        assert_eq!(format!("{map:?}"), "{1: 10, 2: 20}");
+        let cloned = map.clone();
+        assert_eq!(cloned, map);
+        assert_eq!((&map).into_iter().len(), 2);
+        #[cfg(feature = "std")]
+        {
+            let collected: SkipMap<i32, i32> = [(1, 10), (2, 20)].into_iter().collect();
+            assert_eq!(collected, map);
+        }
+    }
+
+    #[test]
+    fn test_owned_values_are_dropped() {
+        use std::rc::Rc;
+
+        let tracker = Rc::new(());
+        let mut map = SkipMap::with_seed(3);
+        for key in 0..100 {
+            map.insert(key, Rc::clone(&tracker));
+        }
+        map.insert(5, Rc::clone(&tracker));
+        map.remove(&6);
+        assert_eq!(Rc::strong_count(&tracker), 100);
+        drop(map);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn test_drop_long_list() {
+        std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(|| {
+                let mut map = SkipMap::with_seed(11);
+                map.extend((0..200_000).map(|key| (key, key)));
+                assert_eq!(map.len(), 200_000);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_set() {
+        let mut set = SkipSet::with_seed(8);
+        assert!(set.insert(3));
+        assert!(set.insert(1));
+        assert!(!set.insert(3));
+        set.insert(2);
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&2));
+        assert_eq!(set.first(), Some(&1));
+        assert_eq!(set.last(), Some(&3));
+        assert_eq!(set.range(2..).copied().collect::<Vec<_>>(), [2, 3]);
+        assert!(set.remove(&2));
+        assert!(!set.remove(&2));
+        assert_eq!(format!("{set:?}"), "{1, 3}");
+        #[cfg(feature = "std")]
+        {
+            let collected: SkipSet<i32> = [3, 1, 3].into_iter().collect();
+            assert_eq!(collected.iter().len(), 2);
+            assert_eq!(collected.level_distribution().iter().sum::<usize>(), 2);
+        }
+    }
+}
@@ -0,0 +1,1042 @@
+use crate::dynamic_arrays::{self, DynamicArray};
+use crate::error::Error;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter;
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut, Index, IndexMut, RangeBounds};
+use core::ptr;
+use core::slice::SliceIndex;
+
+/// A dynamic array that stores up to `N` elements inline before spilling to
+/// the heap.
+///
+/// While it holds at most `N` elements no allocation is made. The first push
+/// past that moves the elements into a [`DynamicArray`], after which it
+/// behaves exactly like one.
+pub struct SmallArray<T, const N: usize> {
+    storage: Storage<T, N>,
+}
+
+enum Storage<T, const N: usize> {
+    /// The first `len` slots of `data` are initialized
+    Inline {
+        data: [MaybeUninit<T>; N],
+        len: usize,
+    },
+    Heap(DynamicArray<T>),
+}
+
+impl<T, const N: usize> SmallArray<T, N> {
+    /// Create a new empty small array
+    pub fn new() -> Self {
+        Self {
+            storage: Storage::Inline {
+                data: [const { MaybeUninit::uninit() }; N],
+                len: 0,
+            },
+        }
+    }
+
+    /// Create a new small array with a given capacity, allocating only if it
+    /// exceeds `N`
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= N {
+            Self::new()
+        } else {
+            Self {
+                storage: Storage::Heap(DynamicArray::with_capacity(capacity)),
+            }
+        }
+    }
+
+    /// Check whether the elements have moved to the heap
+    pub fn spilled(&self) -> bool {
+        matches!(self.storage, Storage::Heap(_))
+    }
+
+    /// Get the length of the small array
+    pub fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { len, .. } => *len,
+            Storage::Heap(arr) => arr.len(),
+        }
+    }
+
+    /// Check if the small array is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the capacity of the small array
+    pub fn capacity(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { .. } => N,
+            Storage::Heap(arr) => arr.capacity(),
+        }
+    }
+
+    /// View the elements as a slice
+    pub fn as_slice(&self) -> &[T] {
+        match &self.storage {
+            // SAFETY: the first `len` slots are initialized.
+            Storage::Inline { data, len } => unsafe {
+                core::slice::from_raw_parts(data.as_ptr() as *const T, *len)
+            },
+            Storage::Heap(arr) => arr.as_slice(),
+        }
+    }
+
+    /// View the elements as a mutable slice
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match &mut self.storage {
+            // SAFETY: the first `len` slots are initialized.
+            Storage::Inline { data, len } => unsafe {
+                core::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut T, *len)
+            },
+            Storage::Heap(arr) => arr.as_mut_slice(),
+        }
+    }
+
+    /// Push an element to the end
+    pub fn push(&mut self, value: T) {
+        match &mut self.storage {
+            Storage::Inline { data, len } if *len < N => {
+                data[*len].write(value);
+                *len += 1;
+            }
+            Storage::Inline { .. } => {
+                self.spill(N + 1);
+                self.push(value);
+            }
+            Storage::Heap(arr) => arr.push(value),
+        }
+    }
+
+    /// Pop an element from the end
+    pub fn pop(&mut self) -> Option<T> {
+        match &mut self.storage {
+            Storage::Inline { data, len } => {
+                if *len == 0 {
+                    return None;
+                }
+                *len -= 1;
+                // SAFETY: the slot was initialized and is no longer counted
+                // by `len`, so it is read exactly once.
+                Some(unsafe { data[*len].assume_init_read() })
+            }
+            Storage::Heap(arr) => arr.pop(),
+        }
+    }
+
+    /// Get a reference to the element at the given index
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    /// Get a mutable reference to the element at the given index
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+
+    /// Set the element at the given index
+    pub fn set(&mut self, index: usize, value: T) -> Result<(), Error> {
+        let len = self.len();
+        match self.get_mut(index) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(Error::out_of_bounds(index, len)),
+        }
+    }
+
+    /// Insert an element at the given index
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), Error> {
+        match &mut self.storage {
+            Storage::Inline { data, len } if *len < N => {
+                if index > *len {
+                    return Err(Error::out_of_bounds(index, *len));
+                }
+                // SAFETY: `index <= len < N`; the tail is shifted one slot to
+                // the right within the array before the gap is filled.
+                unsafe {
+                    let slot = data.as_mut_ptr().add(index);
+                    ptr::copy(slot, slot.add(1), *len - index);
+                    (*slot).write(value);
+                }
+                *len += 1;
+                Ok(())
+            }
+            Storage::Inline { len, .. } => {
+                if index > *len {
+                    return Err(Error::out_of_bounds(index, *len));
+                }
+                self.spill(N + 1);
+                self.insert(index, value)
+            }
+            Storage::Heap(arr) => arr.insert(index, value),
+        }
+    }
+
+    /// Remove an element at the given index
+    pub fn remove(&mut self, index: usize) -> Result<T, Error> {
+        match &mut self.storage {
+            Storage::Inline { data, len } => {
+                if index >= *len {
+                    return Err(Error::out_of_bounds(index, *len));
+                }
+                // SAFETY: `index < len`; the element is read out before the
+                // tail is shifted left over its slot.
+                unsafe {
+                    let slot = data.as_mut_ptr().add(index);
+                    let value = (*slot).assume_init_read();
+                    ptr::copy(slot.add(1), slot, *len - index - 1);
+                    *len -= 1;
+                    Ok(value)
+                }
+            }
+            Storage::Heap(arr) => arr.remove(index),
+        }
+    }
+
+    /// Drop every element past the first `new_len`
+    pub fn truncate(&mut self, new_len: usize) {
+        match &mut self.storage {
+            Storage::Inline { data, len } => {
+                if new_len >= *len {
+                    return;
+                }
+                let tail = ptr::slice_from_raw_parts_mut(
+                    // SAFETY: `new_len < len <= N`, so the offset is in bounds.
+                    unsafe { (data.as_mut_ptr() as *mut T).add(new_len) },
+                    *len - new_len,
+                );
+                // Forget the elements first so a panicking destructor can't
+                // lead to them being dropped a second time.
+                *len = new_len;
+                // SAFETY: the elements were initialized and are no longer
+                // reachable.
+                unsafe { ptr::drop_in_place(tail) };
+            }
+            Storage::Heap(arr) => arr.truncate(new_len),
+        }
+    }
+
+    /// Clear the small array
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Remove an element at the given index in O(1) by moving the last
+    /// element into its place
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, Error> {
+        match &mut self.storage {
+            Storage::Inline { data, len } => {
+                if index >= *len {
+                    return Err(Error::out_of_bounds(index, *len));
+                }
+                *len -= 1;
+                // SAFETY: both slots were initialized; the removed element is
+                // read out before the last one is moved over it.
+                unsafe {
+                    let base = data.as_mut_ptr();
+                    let value = (*base.add(index)).assume_init_read();
+                    ptr::copy(base.add(*len), base.add(index), 1);
+                    Ok(value)
+                }
+            }
+            Storage::Heap(arr) => arr.swap_remove(index),
+        }
+    }
+
+    /// Move every element of `other` to the end of this array, leaving
+    /// `other` empty
+    pub fn append<const M: usize>(&mut self, other: &mut SmallArray<T, M>) {
+        self.extend(other.drain(..));
+    }
+
+    /// Remove the elements in `range`, returning them as an iterator.
+    ///
+    /// While inline, the range is moved out up front and the elements after
+    /// it are shifted down immediately. Once spilled, this drains lazily like
+    /// [`DynamicArray::drain`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past the end.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, N> {
+        match self.storage {
+            Storage::Inline { .. } => Drain::Inline(self.splice(range, iter::empty()), PhantomData),
+            Storage::Heap(ref mut arr) => Drain::Heap(arr.drain(range)),
+        }
+    }
+
+    /// Replace the elements in `range` with the items of `replace_with`,
+    /// returning the removed elements.
+    ///
+    /// `replace_with` is collected first, so a panic inside it leaves the
+    /// array untouched. The array spills if the result no longer fits inline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past the end.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> IntoIter<T, N>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (start, end) = dynamic_arrays::slice_range(range, self.len());
+        let replacement: SmallArray<T, N> = replace_with.into_iter().collect();
+        let new_len = self.len() - (end - start) + replacement.len();
+        if new_len > N {
+            self.spill(new_len);
+        }
+        match &mut self.storage {
+            Storage::Inline { data, len } => {
+                // The replacement is no longer than the result, so it is
+                // inline too and forgetting it frees nothing.
+                let replacement = ManuallyDrop::new(replacement);
+                let count = replacement.len();
+                let mut removed = [const { MaybeUninit::uninit() }; N];
+                // SAFETY: all ranges are in bounds of their arrays. The
+                // removed elements move to `removed`, the tail moves once to
+                // make room, and the replacement moves into the gap; it is
+                // never dropped, so each element is owned exactly once.
+                unsafe {
+                    let base = data.as_mut_ptr();
+                    ptr::copy_nonoverlapping(base.add(start), removed.as_mut_ptr(), end - start);
+                    ptr::copy(base.add(end), base.add(start + count), *len - end);
+                    ptr::copy_nonoverlapping(
+                        replacement.as_slice().as_ptr() as *const MaybeUninit<T>,
+                        base.add(start),
+                        count,
+                    );
+                }
+                *len = new_len;
+                IntoIter::Inline {
+                    data: removed,
+                    start: 0,
+                    end: end - start,
+                }
+            }
+            Storage::Heap(arr) => IntoIter::Heap(arr.splice(start..end, replacement)),
+        }
+    }
+
+    /// Keep only the elements for which `keep` returns `true`
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut keep: F) {
+        self.retain_mut(|value| keep(value));
+    }
+
+    /// Keep only the elements for which `keep` returns `true`, letting the
+    /// predicate modify them
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, keep: F) {
+        match &mut self.storage {
+            // SAFETY: the first `len` slots are initialized.
+            Storage::Inline { data, len } => unsafe {
+                dynamic_arrays::retain_raw(data.as_mut_ptr() as *mut T, len, keep)
+            },
+            Storage::Heap(arr) => arr.retain_mut(keep),
+        }
+    }
+
+    /// Remove consecutive elements that map to the same key
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Remove consecutive elements for which `same_bucket(current, previous)`
+    /// returns `true`, in a single pass
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, same_bucket: F) {
+        match &mut self.storage {
+            // SAFETY: the first `len` slots are initialized.
+            Storage::Inline { data, len } => unsafe {
+                dynamic_arrays::dedup_raw(data.as_mut_ptr() as *mut T, len, same_bucket)
+            },
+            Storage::Heap(arr) => arr.dedup_by(same_bucket),
+        }
+    }
+
+    /// Remove consecutive equal elements
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Split the array in two at `at`, returning the elements from `at` on
+    pub fn split_off(&mut self, at: usize) -> Result<Self, Error> {
+        let len = self.len();
+        if at > len {
+            return Err(Error::out_of_bounds(at, len));
+        }
+        Ok(self.drain(at..).collect())
+    }
+
+    /// Clone every element of `other` onto the end of the array
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(other.len());
+        for value in other {
+            self.push(value.clone());
+        }
+    }
+
+    /// Iterate over the small array
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    /// Iterate mutably over the small array
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    /// Reserve additional capacity
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .expect("capacity overflow");
+        match &mut self.storage {
+            Storage::Inline { .. } if required > N => self.spill(required),
+            Storage::Inline { .. } => {}
+            Storage::Heap(arr) => arr.reserve(additional),
+        }
+    }
+
+    /// Shrink the capacity to fit the current length, moving the elements
+    /// back inline if they fit
+    pub fn shrink_to_fit(&mut self) {
+        let Storage::Heap(arr) = &mut self.storage else {
+            return;
+        };
+        if arr.len() > N {
+            arr.shrink_to_fit();
+            return;
+        }
+        let mut data = [const { MaybeUninit::uninit() }; N];
+        let len = arr.len();
+        for (slot, value) in data.iter_mut().zip(arr.drain(..)) {
+            slot.write(value);
+        }
+        self.storage = Storage::Inline { data, len };
+    }
+
+    /// Move the inline elements into a heap buffer of at least `capacity`
+    fn spill(&mut self, capacity: usize) {
+        let Storage::Inline { data, len } = &mut self.storage else {
+            return;
+        };
+        let mut arr = DynamicArray::with_capacity(capacity);
+        let count = mem::replace(len, 0);
+        for slot in &data[..count] {
+            // SAFETY: the slot is initialized, and `len` was zeroed so the
+            // inline storage no longer owns it.
+            arr.push(unsafe { slot.assume_init_read() });
+        }
+        self.storage = Storage::Heap(arr);
+    }
+}
+
+impl<T, const N: usize> Drop for SmallArray<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Deref for SmallArray<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallArray<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for SmallArray<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for SmallArray<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize, I: SliceIndex<[T]>> Index<I> for SmallArray<T, N> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        Index::index(self.as_slice(), index)
+    }
+}
+
+impl<T, const N: usize, I: SliceIndex<[T]>> IndexMut<I> for SmallArray<T, N> {
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        IndexMut::index_mut(self.as_mut_slice(), index)
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallArray<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: core::fmt::Debug, const N: usize> core::fmt::Debug for SmallArray<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SmallArray")
+            .field("data", &self.as_slice())
+            .field("spilled", &self.spilled())
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for SmallArray<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<SmallArray<U, M>>
+    for SmallArray<T, N>
+{
+    fn eq(&self, other: &SmallArray<U, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<[U; M]> for SmallArray<T, N> {
+    fn eq(&self, other: &[U; M]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallArray<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for SmallArray<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for SmallArray<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for SmallArray<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallArray<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut arr = Self::new();
+        arr.extend(iter);
+        arr
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallArray<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T, const N: usize> From<DynamicArray<T>> for SmallArray<T, N> {
+    fn from(arr: DynamicArray<T>) -> Self {
+        let mut small = Self {
+            storage: Storage::Heap(arr),
+        };
+        small.shrink_to_fit();
+        small
+    }
+}
+
+impl<T, const N: usize> IntoIterator for SmallArray<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(mut self) -> IntoIter<T, N> {
+        let storage = mem::replace(&mut self.storage, Storage::Heap(DynamicArray::new()));
+        match storage {
+            Storage::Inline { data, len } => IntoIter::Inline {
+                data,
+                start: 0,
+                end: len,
+            },
+            Storage::Heap(arr) => IntoIter::Heap(arr.into_iter()),
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallArray<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallArray<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An owning iterator over the elements of a [`SmallArray`]
+pub enum IntoIter<T, const N: usize> {
+    #[doc(hidden)]
+    Inline {
+        data: [MaybeUninit<T>; N],
+        start: usize,
+        end: usize,
+    },
+    #[doc(hidden)]
+    Heap(dynamic_arrays::IntoIter<T>),
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self {
+            IntoIter::Inline { data, start, end } => {
+                if start == end {
+                    return None;
+                }
+                *start += 1;
+                // SAFETY: the slot is initialized and `start` has moved past
+                // it, so it is read exactly once.
+                Some(unsafe { data[*start - 1].assume_init_read() })
+            }
+            IntoIter::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            IntoIter::Inline { start, end, .. } => (end - start, Some(end - start)),
+            IntoIter::Heap(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        match self {
+            IntoIter::Inline { data, start, end } => {
+                if start == end {
+                    return None;
+                }
+                *end -= 1;
+                // SAFETY: the slot is initialized and now lies past `end`,
+                // so it is read exactly once.
+                Some(unsafe { data[*end].assume_init_read() })
+            }
+            IntoIter::Heap(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        if let IntoIter::Inline { data, start, end } = self {
+            let remaining = &mut data[*start..*end];
+            *start = *end;
+            // SAFETY: the remaining slots are initialized and were never
+            // yielded.
+            unsafe { ptr::drop_in_place(remaining as *mut [MaybeUninit<T>] as *mut [T]) };
+        }
+    }
+}
+
+/// A draining iterator over a range of a [`SmallArray`]
+pub enum Drain<'a, T, const N: usize> {
+    #[doc(hidden)]
+    Inline(IntoIter<T, N>, PhantomData<&'a mut [T]>),
+    #[doc(hidden)]
+    Heap(dynamic_arrays::Drain<'a, T>),
+}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self {
+            Drain::Inline(iter, _) => iter.next(),
+            Drain::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Drain::Inline(iter, _) => iter.size_hint(),
+            Drain::Heap(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        match self {
+            Drain::Inline(iter, _) => iter.next_back(),
+            Drain::Heap(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct DropCounter<'a>(&'a Cell<usize>);
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let arr: SmallArray<i32, 4> = SmallArray::new();
+        assert_eq!(arr.len(), 0);
+        assert!(arr.is_empty());
+        assert_eq!(arr.capacity(), 4);
+        assert!(!arr.spilled());
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let arr: SmallArray<i32, 4> = SmallArray::with_capacity(4);
+        assert!(!arr.spilled());
+        let arr: SmallArray<i32, 4> = SmallArray::with_capacity(10);
+        assert!(arr.spilled());
+        assert!(arr.capacity() >= 10);
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut arr: SmallArray<i32, 2> = SmallArray::new();
+        arr.push(1);
+        arr.push(2);
+        assert!(!arr.spilled());
+        arr.push(3);
+        assert!(arr.spilled());
+        assert_eq!(arr, [1, 2, 3]);
+        assert_eq!(arr.pop(), Some(3));
+        assert_eq!(arr.pop(), Some(2));
+        assert_eq!(arr.pop(), Some(1));
+        assert_eq!(arr.pop(), None);
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let mut arr: SmallArray<i32, 4> = [1, 2].into_iter().collect();
+        assert_eq!(arr.get(1), Some(&2));
+        assert_eq!(arr.get(2), None);
+        *arr.get_mut(0).unwrap() = 10;
+        assert!(arr.set(1, 20).is_ok());
+        assert!(arr.set(2, 30).is_err());
+        assert_eq!(arr, [10, 20]);
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut arr: SmallArray<String, 3> = SmallArray::new();
+        arr.push(String::from("a"));
+        arr.push(String::from("c"));
+        arr.insert(1, String::from("b")).unwrap();
+        assert!(!arr.spilled());
+        arr.insert(0, String::from("z")).unwrap();
+        assert!(arr.spilled());
+        assert_eq!(arr, ["z", "a", "b", "c"]);
+        assert!(arr.insert(9, String::from("x")).is_err());
+        assert_eq!(arr.remove(0).unwrap(), "z");
+        assert!(arr.remove(3).is_err());
+        arr.shrink_to_fit();
+        assert!(!arr.spilled());
+        assert_eq!(arr.remove(1).unwrap(), "b");
+        assert_eq!(arr, ["a", "c"]);
+    }
+
+    #[test]
+    fn test_insert_out_of_bounds_when_full_does_not_spill() {
+        let mut arr: SmallArray<i32, 2> = [1, 2].into_iter().collect();
+        assert!(arr.insert(3, 9).is_err());
+        assert!(!arr.spilled());
+    }
+
+    #[test]
+    fn test_reserve_and_shrink_to_fit() {
+        let mut arr: SmallArray<i32, 4> = SmallArray::new();
+        arr.push(1);
+        arr.reserve(3);
+        assert!(!arr.spilled());
+        arr.reserve(4);
+        assert!(arr.spilled());
+        assert!(arr.capacity() >= 5);
+        arr.shrink_to_fit();
+        assert!(!arr.spilled());
+        assert_eq!(arr, [1]);
+    }
+
+    #[test]
+    fn test_clear_and_truncate() {
+        let drops = Cell::new(0);
+        let mut arr: SmallArray<DropCounter, 4> = SmallArray::new();
+        for _ in 0..3 {
+            arr.push(DropCounter(&drops));
+        }
+        arr.truncate(1);
+        assert_eq!(drops.get(), 2);
+        arr.clear();
+        assert_eq!(drops.get(), 3);
+        assert!(arr.is_empty());
+    }
+
+    #[test]
+    fn test_drop_inline_and_spilled() {
+        let drops = Cell::new(0);
+        {
+            let mut arr: SmallArray<DropCounter, 4> = SmallArray::new();
+            for _ in 0..3 {
+                arr.push(DropCounter(&drops));
+            }
+        }
+        assert_eq!(drops.get(), 3);
+        {
+            let mut arr: SmallArray<DropCounter, 2> = SmallArray::new();
+            for _ in 0..5 {
+                arr.push(DropCounter(&drops));
+            }
+            assert!(arr.spilled());
+        }
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[test]
+    fn test_iter_and_slice_methods() {
+        let mut arr: SmallArray<i32, 4> = [3, 1, 2].into_iter().collect();
+        for val in arr.iter_mut() {
+            *val *= 10;
+        }
+        arr.sort();
+        assert_eq!(arr[0], 10);
+        assert_eq!(arr.iter().sum::<i32>(), 60);
+        assert_eq!((&arr).into_iter().count(), 3);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let drops = Cell::new(0);
+        let mut arr: SmallArray<DropCounter, 4> = SmallArray::new();
+        for _ in 0..4 {
+            arr.push(DropCounter(&drops));
+        }
+        let mut iter = arr.into_iter();
+        assert_eq!(iter.len(), 4);
+        drop(iter.next());
+        drop(iter.next_back());
+        assert_eq!(drops.get(), 2);
+        drop(iter);
+        assert_eq!(drops.get(), 4);
+
+        let spilled: SmallArray<i32, 1> = (0..5).collect();
+        assert_eq!(
+            spilled.into_iter().rev().collect::<Vec<_>>(),
+            [4, 3, 2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_clone_and_eq() {
+        let arr: SmallArray<String, 2> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let cloned = arr.clone();
+        assert_eq!(arr, cloned);
+        let inline: SmallArray<String, 8> = arr.iter().cloned().collect();
+        assert!(!inline.spilled());
+        assert_eq!(arr, inline);
+    }
+
+    #[test]
+    fn test_from_dynamic_array() {
+        let arr: SmallArray<i32, 4> = SmallArray::from(crate::dsa_vec![1, 2]);
+        assert!(!arr.spilled());
+        assert_eq!(arr, [1, 2]);
+    }
+
+    #[test]
+    fn test_zero_inline_capacity() {
+        let mut arr: SmallArray<i32, 0> = SmallArray::new();
+        assert_eq!(arr.capacity(), 0);
+        arr.push(1);
+        assert!(arr.spilled());
+        assert_eq!(arr, [1]);
+    }
+
+    #[test]
+    fn test_zero_sized_types() {
+        let mut arr: SmallArray<(), 2> = SmallArray::new();
+        for _ in 0..5 {
+            arr.push(());
+        }
+        assert_eq!(arr.len(), 5);
+        assert!(arr.spilled());
+        arr.truncate(1);
+        arr.shrink_to_fit();
+        assert!(!arr.spilled());
+        assert_eq!(arr.pop(), Some(()));
+    }
+    #[test]
+    fn test_swap_remove() {
+        let mut arr: SmallArray<i32, 4> = [1, 2, 3, 4].into_iter().collect();
+        assert_eq!(arr.swap_remove(0).unwrap(), 1);
+        assert_eq!(arr, [4, 2, 3]);
+        assert!(arr.swap_remove(3).is_err());
+        let mut spilled: SmallArray<i32, 1> = [1, 2, 3].into_iter().collect();
+        assert_eq!(spilled.swap_remove(0).unwrap(), 1);
+        assert_eq!(spilled, [3, 2]);
+    }
+
+    #[test]
+    fn test_drain() {
+        let drops = Cell::new(0);
+        let mut arr: SmallArray<DropCounter, 4> = SmallArray::new();
+        for _ in 0..4 {
+            arr.push(DropCounter(&drops));
+        }
+        let mut drain = arr.drain(1..3);
+        assert_eq!(drain.len(), 2);
+        drop(drain.next());
+        drop(drain);
+        assert_eq!(drops.get(), 2);
+        assert_eq!(arr.len(), 2);
+        assert!(!arr.spilled());
+
+        let mut spilled: SmallArray<i32, 2> = (0..6).collect();
+        assert_eq!(spilled.drain(..4).rev().collect::<Vec<_>>(), [3, 2, 1, 0]);
+        assert_eq!(spilled, [4, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_drain_out_of_bounds() {
+        let mut arr: SmallArray<i32, 4> = [1, 2].into_iter().collect();
+        arr.drain(1..3);
+    }
+
+    #[test]
+    fn test_splice() {
+        let mut arr: SmallArray<i32, 4> = [1, 2, 3].into_iter().collect();
+        let removed: Vec<_> = arr.splice(1..2, [7, 8]).collect();
+        assert_eq!(removed, [2]);
+        assert!(!arr.spilled());
+        assert_eq!(arr, [1, 7, 8, 3]);
+        let removed: Vec<_> = arr.splice(..1, [5, 6]).collect();
+        assert_eq!(removed, [1]);
+        assert!(arr.spilled());
+        assert_eq!(arr, [5, 6, 7, 8, 3]);
+        let removed: Vec<_> = arr.splice(2.., []).collect();
+        assert_eq!(removed, [7, 8, 3]);
+        assert_eq!(arr, [5, 6]);
+    }
+
+    #[test]
+    fn test_retain_and_dedup() {
+        let mut arr: SmallArray<i32, 8> = [1, 1, 2, 3, 3, 3, 4].into_iter().collect();
+        arr.dedup();
+        assert_eq!(arr, [1, 2, 3, 4]);
+        arr.retain(|&x| x % 2 == 0);
+        assert_eq!(arr, [2, 4]);
+        arr.retain_mut(|x| {
+            *x *= 10;
+            true
+        });
+        assert_eq!(arr, [20, 40]);
+        assert!(!arr.spilled());
+
+        let mut spilled: SmallArray<i32, 2> = [1, 2, 12, 3, 13].into_iter().collect();
+        spilled.dedup_by_key(|x| *x % 10);
+        assert_eq!(spilled, [1, 2, 3]);
+        spilled.retain(|&x| x != 2);
+        assert_eq!(spilled, [1, 3]);
+    }
+
+    #[test]
+    fn test_append_and_split_off() {
+        let mut arr: SmallArray<i32, 4> = [1, 2].into_iter().collect();
+        let mut other: SmallArray<i32, 2> = [3, 4, 5].into_iter().collect();
+        arr.append(&mut other);
+        assert!(other.is_empty());
+        assert!(arr.spilled());
+        assert_eq!(arr, [1, 2, 3, 4, 5]);
+        let tail = arr.split_off(3).unwrap();
+        assert!(!tail.spilled());
+        assert_eq!(tail, [4, 5]);
+        assert_eq!(arr, [1, 2, 3]);
+        assert!(arr.split_off(4).is_err());
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let mut arr: SmallArray<String, 2> = SmallArray::new();
+        arr.extend_from_slice(&[String::from("a")]);
+        assert!(!arr.spilled());
+        arr.extend_from_slice(&[String::from("b"), String::from("c")]);
+        assert!(arr.spilled());
+        assert_eq!(arr, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_index_ord_and_hash() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let mut arr: SmallArray<i32, 4> = [1, 2, 3].into_iter().collect();
+        arr[0] = 5;
+        assert_eq!(arr[1..], [2, 3]);
+        let spilled: SmallArray<i32, 4> = (0..6).collect();
+        assert!(spilled < arr);
+        assert_eq!(arr.as_ref(), &[5, 2, 3]);
+
+        let hash = |arr: &SmallArray<i32, 4>| {
+            let mut hasher = DefaultHasher::new();
+            arr.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut inline: SmallArray<i32, 4> = (0..6).collect();
+        inline.truncate(3);
+        inline.shrink_to_fit();
+        assert_eq!(hash(&inline), hash(&[0, 1, 2].into_iter().collect()));
+    }
+}
@@ -0,0 +1,599 @@
+// Unrolled Linked List implementation in Rust
+
+use crate::array_vec::ArrayVec;
+use crate::doubly_linked_list::{self, CursorMut, DoublyLinkedList};
+use crate::error::Error;
+use core::ops::Deref;
+
+/// A linked list of fixed-capacity chunks holding up to `B` values each.
+///
+/// Keeping `B` values next to each other means iteration touches one node
+/// per chunk instead of one per value, and indexing walks O(n/B) nodes.
+/// A full chunk is split in half before an insertion, and a chunk that
+/// falls under half full after a removal is merged with a neighbour when
+/// the two fit in one chunk. No chunk is ever left empty.
+pub struct UnrolledList<T, const B: usize = 16> {
+    chunks: DoublyLinkedList<ArrayVec<T, B>>,
+    length: usize,
+}
+
+impl<T, const B: usize> Default for UnrolledList<T, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const B: usize> UnrolledList<T, B> {
+    /// Create a new empty unrolled list. `B` must be at least two so that
+    /// a full chunk can split; smaller values fail to compile.
+    pub fn new() -> Self {
+        const { assert!(B >= 2, "chunks must hold at least two values") };
+        Self {
+            chunks: DoublyLinkedList::new(),
+            length: 0,
+        }
+    }
+
+    /// Get the length of the unrolled list
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Check if the unrolled list is empty
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Get the number of chunks the values are spread over
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Insert at head in O(B)
+    pub fn insert_at_head(&mut self, value: T) {
+        match self.chunks.peek_front_mut() {
+            Some(chunk) if !chunk.is_full() => chunk.insert(0, value).expect("chunk has room"),
+            _ => self.chunks.insert_at_head(Self::chunk_of(value)),
+        }
+        self.length += 1;
+    }
+
+    /// Insert at tail in O(1)
+    pub fn insert_at_tail(&mut self, value: T) {
+        match self.chunks.peek_back_mut() {
+            Some(chunk) if !chunk.is_full() => chunk.push(value).expect("chunk has room"),
+            _ => self.chunks.insert_at_tail(Self::chunk_of(value)),
+        }
+        self.length += 1;
+    }
+
+    /// Insert at index in O(n/B + B), splitting the chunk if it is full
+    pub fn insert_at_index(&mut self, index: usize, value: T) -> Result<(), Error> {
+        if index > self.length {
+            return Err(Error::out_of_bounds(index, self.length));
+        }
+        if index == self.length {
+            self.insert_at_tail(value);
+            return Ok(());
+        }
+        let (mut cursor, mut offset) = chunk_at(&mut self.chunks, self.length, index);
+        let chunk = cursor.current().expect("cursor is on a chunk");
+        if chunk.is_full() {
+            let half = B / 2;
+            let back = chunk.split_off(half).expect("half is in bounds");
+            cursor.insert_after(back);
+            if offset > half {
+                cursor.move_next();
+                offset -= half;
+            }
+        }
+        cursor
+            .current()
+            .expect("cursor is on a chunk")
+            .insert(offset, value)
+            .expect("chunk has room");
+        self.length += 1;
+        Ok(())
+    }
+
+    /// Remove from head in O(B)
+    pub fn remove_from_head(&mut self) -> Option<T> {
+        self.remove_from_index(0).ok()
+    }
+
+    /// Remove from tail in O(1)
+    pub fn remove_from_tail(&mut self) -> Option<T> {
+        let chunk = self.chunks.peek_back_mut()?;
+        let value = chunk.pop();
+        if chunk.is_empty() {
+            self.chunks.remove_from_tail();
+        }
+        self.length -= 1;
+        value
+    }
+
+    /// Remove from index in O(n/B + B), merging the chunk with a neighbour
+    /// if it falls under half full
+    pub fn remove_from_index(&mut self, index: usize) -> Result<T, Error> {
+        if index >= self.length {
+            return Err(Error::out_of_bounds(index, self.length));
+        }
+        let (mut cursor, offset) = chunk_at(&mut self.chunks, self.length, index);
+        let chunk = cursor.current().expect("cursor is on a chunk");
+        let value = chunk.remove(offset).expect("offset is in bounds");
+        let remaining = chunk.len();
+        if remaining == 0 {
+            cursor.remove_current();
+        } else if remaining < B / 2 {
+            merge_with_neighbour(&mut cursor, remaining);
+        }
+        self.length -= 1;
+        Ok(value)
+    }
+
+    /// Get a reference to the value at index in O(n/B), walking from the
+    /// nearer end
+    pub fn get(&self, index: usize) -> Result<&T, Error> {
+        locate(self.chunks.iter(), self.length, index)
+            .map(|(chunk, offset)| &chunk[offset])
+            .ok_or(Error::out_of_bounds(index, self.length))
+    }
+
+    /// Get a mutable reference to the value at index in O(n/B), walking
+    /// from the nearer end
+    pub fn get_mut(&mut self, index: usize) -> Result<&mut T, Error> {
+        let length = self.length;
+        locate(self.chunks.iter_mut(), length, index)
+            .map(|(chunk, offset)| &mut chunk[offset])
+            .ok_or(Error::out_of_bounds(index, length))
+    }
+
+    /// Get a reference to the first value
+    pub fn peek_front(&self) -> Option<&T> {
+        self.chunks.peek_front()?.first()
+    }
+
+    /// Get a reference to the last value
+    pub fn peek_back(&self) -> Option<&T> {
+        self.chunks.peek_back()?.last()
+    }
+
+    /// Remove every value from the unrolled list
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.length = 0;
+    }
+
+    /// Iterate over the values from head to tail
+    pub fn iter(&self) -> Iter<'_, T, B> {
+        Iter {
+            inner: self.chunks.iter().flatten(),
+            remaining: self.length,
+        }
+    }
+
+    /// Iterate mutably over the values from head to tail
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, B> {
+        IterMut {
+            inner: self.chunks.iter_mut().flatten(),
+            remaining: self.length,
+        }
+    }
+
+    fn chunk_of(value: T) -> ArrayVec<T, B> {
+        let mut chunk = ArrayVec::new();
+        chunk.push(value).expect("chunk has room");
+        chunk
+    }
+}
+
+/// Put a mutable cursor on the chunk holding `index`, walking from the
+/// nearer end, and return it with the offset of `index` in that chunk
+fn chunk_at<T, const B: usize>(
+    chunks: &mut DoublyLinkedList<ArrayVec<T, B>>,
+    length: usize,
+    index: usize,
+) -> (CursorMut<'_, ArrayVec<T, B>>, usize) {
+    if index < length / 2 {
+        let mut cursor = chunks.cursor_front_mut();
+        let mut offset = index;
+        loop {
+            let len = cursor.current().expect("index is in bounds").len();
+            if offset < len {
+                return (cursor, offset);
+            }
+            offset -= len;
+            cursor.move_next();
+        }
+    } else {
+        let mut cursor = chunks.cursor_back_mut();
+        // Count the values from `index` to the end instead.
+        let mut from_end = length - index;
+        loop {
+            let len = cursor.current().expect("index is in bounds").len();
+            if from_end <= len {
+                return (cursor, len - from_end);
+            }
+            from_end -= len;
+            cursor.move_prev();
+        }
+    }
+}
+
+/// Find the chunk holding `index` and the offset in it, walking from the
+/// nearer end
+fn locate<T, const B: usize, C: Deref<Target = ArrayVec<T, B>>>(
+    mut chunks: impl DoubleEndedIterator<Item = C>,
+    length: usize,
+    index: usize,
+) -> Option<(C, usize)> {
+    if index >= length {
+        return None;
+    }
+    if index < length / 2 {
+        let mut offset = index;
+        chunks.find_map(|chunk| {
+            if offset < chunk.len() {
+                return Some((chunk, offset));
+            }
+            offset -= chunk.len();
+            None
+        })
+    } else {
+        let mut from_end = length - index;
+        chunks.rev().find_map(|chunk| {
+            let len = chunk.len();
+            if from_end <= len {
+                return Some((chunk, len - from_end));
+            }
+            from_end -= len;
+            None
+        })
+    }
+}
+
+/// Merge the chunk under the cursor, holding `len` values, with the next
+/// chunk or else the previous one, if the two fit in a single chunk
+fn merge_with_neighbour<T, const B: usize>(cursor: &mut CursorMut<'_, ArrayVec<T, B>>, len: usize) {
+    if cursor.peek_next().is_some_and(|next| len + next.len() <= B) {
+        cursor.move_next();
+        let next = cursor.remove_current().expect("cursor is on a chunk");
+        cursor.move_prev();
+        let chunk = cursor.current().expect("cursor is on a chunk");
+        for value in next {
+            chunk.push(value).expect("chunks fit together");
+        }
+    } else if cursor.peek_prev().is_some_and(|prev| len + prev.len() <= B) {
+        let chunk = cursor.remove_current().expect("cursor is on a chunk");
+        cursor.move_prev();
+        let prev = cursor.current().expect("cursor is on a chunk");
+        for value in chunk {
+            prev.push(value).expect("chunks fit together");
+        }
+    }
+}
+
+impl<T: Clone, const B: usize> Clone for UnrolledList<T, B> {
+    fn clone(&self) -> Self {
+        Self {
+            chunks: self.chunks.clone(),
+            length: self.length,
+        }
+    }
+}
+
+impl<T: core::fmt::Debug, const B: usize> core::fmt::Debug for UnrolledList<T, B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const B: usize> PartialEq for UnrolledList<T, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, const B: usize> Eq for UnrolledList<T, B> {}
+
+impl<T, const B: usize> FromIterator<T> for UnrolledList<T, B> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, const B: usize> Extend<T> for UnrolledList<T, B> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert_at_tail(value);
+        }
+    }
+}
+
+impl<T, const B: usize> IntoIterator for UnrolledList<T, B> {
+    type Item = T;
+    type IntoIter = IntoIter<T, B>;
+
+    fn into_iter(self) -> IntoIter<T, B> {
+        IntoIter {
+            remaining: self.length,
+            inner: self.chunks.into_iter().flatten(),
+        }
+    }
+}
+
+impl<'a, T, const B: usize> IntoIterator for &'a UnrolledList<T, B> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, B>;
+
+    fn into_iter(self) -> Iter<'a, T, B> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const B: usize> IntoIterator for &'a mut UnrolledList<T, B> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T, B>;
+
+    fn into_iter(self) -> IterMut<'a, T, B> {
+        self.iter_mut()
+    }
+}
+
+/// Define an iterator over the values of an [`UnrolledList`] that flattens
+/// an iterator over its chunks and keeps an exact count
+macro_rules! chunked_iter {
+    ($(#[$attr:meta])* $name:ident<$($lt:lifetime,)? T, B> => $item:ty, $chunks:ty) => {
+        $(#[$attr])*
+        pub struct $name<$($lt,)? T, const B: usize> {
+            inner: core::iter::Flatten<$chunks>,
+            remaining: usize,
+        }
+
+        impl<$($lt,)? T, const B: usize> Iterator for $name<$($lt,)? T, B> {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<$item> {
+                let value = self.inner.next()?;
+                self.remaining -= 1;
+                Some(value)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.remaining, Some(self.remaining))
+            }
+        }
+
+        impl<$($lt,)? T, const B: usize> DoubleEndedIterator for $name<$($lt,)? T, B> {
+            fn next_back(&mut self) -> Option<$item> {
+                let value = self.inner.next_back()?;
+                self.remaining -= 1;
+                Some(value)
+            }
+        }
+
+        impl<$($lt,)? T, const B: usize> ExactSizeIterator for $name<$($lt,)? T, B> {}
+
+        impl<$($lt,)? T, const B: usize> core::iter::FusedIterator for $name<$($lt,)? T, B> {}
+    };
+}
+
+chunked_iter! {
+    /// A borrowing iterator over the values of an [`UnrolledList`]
+    Iter<'a, T, B> => &'a T, doubly_linked_list::Iter<'a, ArrayVec<T, B>>
+}
+
+chunked_iter! {
+    /// A mutable iterator over the values of an [`UnrolledList`]
+    IterMut<'a, T, B> => &'a mut T, doubly_linked_list::IterMut<'a, ArrayVec<T, B>>
+}
+
+chunked_iter! {
+    /// An owning iterator over the values of an [`UnrolledList`]
+    IntoIter<T, B> => T, doubly_linked_list::IntoIter<ArrayVec<T, B>>
+}
+
+impl<T, const B: usize> Clone for Iter<'_, T, B> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Small = UnrolledList<i32, 4>;
+
+    fn to_vec<const B: usize>(list: &UnrolledList<i32, B>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    /// Check the chunk invariants: no chunk is empty and the lengths add up
+    fn check<const B: usize>(list: &UnrolledList<i32, B>) {
+        assert!(list.chunks.iter().all(|chunk| !chunk.is_empty()));
+        assert_eq!(
+            list.chunks.iter().map(|chunk| chunk.len()).sum::<usize>(),
+            list.len()
+        );
+        assert_eq!(list.iter().len(), list.len());
+    }
+
+    #[test]
+    fn test_new() {
+        let list: UnrolledList<i32> = UnrolledList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.chunk_count(), 0);
+        assert_eq!(list.peek_front(), None);
+        assert_eq!(list.get(0), Err(Error::out_of_bounds(0, 0)));
+    }
+
+    #[test]
+    fn test_insert_at_tail_fills_chunks() {
+        let list: Small = (0..10).collect();
+        assert_eq!(to_vec(&list), (0..10).collect::<Vec<_>>());
+        assert_eq!(list.chunk_count(), 3);
+        assert_eq!(list.peek_back(), Some(&9));
+        check(&list);
+    }
+
+    #[test]
+    fn test_insert_at_head() {
+        let mut list = Small::new();
+        for value in (0..9).rev() {
+            list.insert_at_head(value);
+        }
+        assert_eq!(to_vec(&list), (0..9).collect::<Vec<_>>());
+        assert_eq!(list.peek_front(), Some(&0));
+        check(&list);
+    }
+
+    #[test]
+    fn test_insert_at_index_splits_full_chunks() {
+        let mut list: Small = (0..8).collect();
+        assert_eq!(list.chunk_count(), 2);
+        list.insert_at_index(1, 100).unwrap();
+        assert_eq!(list.chunk_count(), 3);
+        list.insert_at_index(7, 200).unwrap();
+        list.insert_at_index(10, 300).unwrap();
+        assert_eq!(to_vec(&list), [0, 100, 1, 2, 3, 4, 5, 200, 6, 7, 300]);
+        assert_eq!(
+            list.insert_at_index(12, 0),
+            Err(Error::out_of_bounds(12, 11))
+        );
+        check(&list);
+    }
+
+    #[test]
+    fn test_remove_merges_sparse_chunks() {
+        let mut list: Small = (0..12).collect();
+        assert_eq!(list.chunk_count(), 3);
+        assert_eq!(list.remove_from_tail(), Some(11));
+        assert_eq!(list.remove_from_index(10), Ok(10));
+        assert_eq!(list.remove_from_index(5), Ok(5));
+        assert_eq!(list.remove_from_index(4), Ok(4));
+        assert_eq!(list.chunk_count(), 3);
+        assert_eq!(list.remove_from_index(4), Ok(6));
+        // The middle chunk fell to one value and merged with the next one.
+        assert_eq!(list.chunk_count(), 2);
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 7, 8, 9]);
+        // The last chunk can only merge backwards.
+        list.insert_at_index(4, 4).unwrap();
+        list.remove_from_index(6).unwrap();
+        list.remove_from_index(6).unwrap();
+        list.remove_from_index(0).unwrap();
+        list.remove_from_index(0).unwrap();
+        assert_eq!(to_vec(&list), [2, 3, 4, 7]);
+        assert_eq!(list.chunk_count(), 2);
+        assert_eq!(list.remove_from_index(3), Ok(7));
+        assert_eq!(list.chunk_count(), 1);
+        assert_eq!(to_vec(&list), [2, 3, 4]);
+        assert_eq!(list.remove_from_index(3), Err(Error::out_of_bounds(3, 3)));
+        check(&list);
+    }
+
+    #[test]
+    fn test_remove_from_ends() {
+        let mut list: Small = (0..6).collect();
+        assert_eq!(list.remove_from_head(), Some(0));
+        assert_eq!(list.remove_from_tail(), Some(5));
+        assert_eq!(list.remove_from_tail(), Some(4));
+        assert_eq!(to_vec(&list), [1, 2, 3]);
+        check(&list);
+        while list.remove_from_head().is_some() {}
+        assert!(list.is_empty());
+        assert_eq!(list.chunk_count(), 0);
+        assert_eq!(list.remove_from_tail(), None);
+    }
+
+    #[test]
+    fn test_get() {
+        let mut list: Small = (0..30).collect();
+        for index in 0..30 {
+            assert_eq!(list.get(index), Ok(&(index as i32)));
+        }
+        *list.get_mut(17).unwrap() = -1;
+        *list.get_mut(2).unwrap() = -2;
+        assert_eq!(list.get(17), Ok(&-1));
+        assert_eq!(list.get(2), Ok(&-2));
+        assert_eq!(list.get_mut(30), Err(Error::out_of_bounds(30, 30)));
+    }
+
+    #[test]
+    fn test_matches_vec_under_random_edits() {
+        let mut list = Small::new();
+        let mut expected = Vec::new();
+        let mut state: u32 = 7;
+        let mut next = |bound: usize| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as usize % bound
+        };
+        for step in 0..2_000 {
+            if expected.is_empty() || next(3) != 0 {
+                let index = next(expected.len() + 1);
+                list.insert_at_index(index, step).unwrap();
+                expected.insert(index, step);
+            } else {
+                let index = next(expected.len());
+                assert_eq!(list.remove_from_index(index), Ok(expected.remove(index)));
+            }
+            if step % 97 == 0 {
+                check(&list);
+                assert_eq!(to_vec(&list), expected);
+            }
+        }
+        assert_eq!(to_vec(&list), expected);
+        check(&list);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let mut list: Small = (1..=6).collect();
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            [6, 5, 4, 3, 2, 1]
+        );
+        for value in &mut list {
+            *value *= 10;
+        }
+        let mut iter = list.clone().into_iter();
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.next(), Some(10));
+        assert_eq!(iter.next_back(), Some(60));
+        assert_eq!(iter.collect::<Vec<_>>(), [20, 30, 40, 50]);
+        assert_eq!((&list).into_iter().count(), 6);
+    }
+
+    #[test]
+    fn test_traits() {
+        let list: Small = (1..=5).collect();
+        let mut cloned = list.clone();
+        assert_eq!(list, cloned);
+        cloned.remove_from_head();
+        assert_ne!(list, cloned);
+        assert_eq!(format!("{list:?}"), "[1, 2, 3, 4, 5]");
+        cloned.clear();
+        assert!(cloned.is_empty());
+        assert_eq!(cloned.chunk_count(), 0);
+    }
+
+    #[test]
+    fn test_owned_values_are_dropped() {
+        use std::rc::Rc;
+
+        let tracker = Rc::new(());
+        let mut list: UnrolledList<Rc<()>, 3> = (0..10).map(|_| Rc::clone(&tracker)).collect();
+        list.remove_from_index(4).unwrap();
+        list.insert_at_index(2, Rc::clone(&tracker)).unwrap();
+        let mut iter = list.into_iter();
+        iter.next();
+        assert_eq!(Rc::strong_count(&tracker), 10);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+}
@@ -0,0 +1,76 @@
+// no_std build checks for the crate
+
+//! Build the library without `std` to catch paths that only resolve with
+//! the standard library linked in.
+//!
+//! The host checks always run. The embedded check builds for
+//! `thumbv7em-none-eabihf`, which has no `std` at all. It runs when that
+//! target is installed (`rustup target add thumbv7em-none-eabihf`) and is
+//! skipped with a message on stderr otherwise.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A bare-metal target with atomics, so every alloc module is built
+const EMBEDDED_TARGET: &str = "thumbv7em-none-eabihf";
+
+/// Check the library with the given feature flags, panicking with the
+/// compiler output if it fails
+fn check(args: &[&str]) {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = Command::new(env!("CARGO"))
+        .arg("check")
+        .arg("--lib")
+        .arg("--no-default-features")
+        .args(args)
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        // A separate target directory keeps this from waiting on the lock
+        // held by the cargo running the tests
+        .env(
+            "CARGO_TARGET_DIR",
+            manifest_dir.join("target").join("no_std"),
+        )
+        .env_remove("RUSTFLAGS")
+        .output()
+        .expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "cargo check --no-default-features {} failed:\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Check if the standard library for `target` is installed
+fn target_installed(target: &str) -> bool {
+    let output = Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".into()))
+        .args(["--print", "sysroot"])
+        .output()
+        .expect("failed to run rustc");
+    let sysroot = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    sysroot.join("lib").join("rustlib").join(target).exists()
+}
+
+#[test]
+fn test_core_only() {
+    check(&[]);
+}
+
+#[test]
+fn test_alloc_without_std() {
+    check(&["--features", "alloc"]);
+}
+
+#[test]
+fn test_embedded_target() {
+    if !target_installed(EMBEDDED_TARGET) {
+        eprintln!(
+            "skipping test_embedded_target: the {EMBEDDED_TARGET} target is not installed; \
+             run `rustup target add {EMBEDDED_TARGET}` to enable it"
+        );
+        return;
+    }
+    check(&["--target", EMBEDDED_TARGET]);
+    check(&["--target", EMBEDDED_TARGET, "--features", "alloc"]);
+}